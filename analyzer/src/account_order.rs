@@ -30,6 +30,8 @@ impl AccountOrder {
                             is_mut: account.is_mut,
                             must_be_initialized: account.initialized_by.is_some(),
                             initialization_order,
+                            closed_by: account.instructions_with(ConstraintType::Close),
+                            reallocated_by: account.instructions_with(ConstraintType::Realloc),
                         });
 
                         initialization_order = initialization_order.saturating_add(1);
@@ -49,6 +51,8 @@ impl AccountOrder {
                     is_mut: account.is_mut,
                     must_be_initialized: false,
                     initialization_order,
+                    closed_by: account.instructions_with(ConstraintType::Close),
+                    reallocated_by: account.instructions_with(ConstraintType::Realloc),
                 });
                 initialization_order = initialization_order.saturating_add(1);
             }
@@ -66,7 +70,9 @@ impl AccountOrder {
         }
 
         for edge in &graph.edges {
-            *in_degree.get_mut(&edge.to).unwrap() += 1;
+            *in_degree
+                .get_mut(&edge.to)
+                .ok_or_else(|| edge.dangling())? += 1;
         }
 
         let mut queue: std::collections::VecDeque<String> = in_degree
@@ -82,7 +88,9 @@ impl AccountOrder {
 
             for edge in &graph.edges {
                 if edge.from == node {
-                    let degree = in_degree.get_mut(&edge.to).unwrap();
+                    let degree = in_degree
+                        .get_mut(&edge.to)
+                        .ok_or_else(|| edge.dangling())?;
                     *degree -= 1;
                     if *degree == 0 {
                         queue.push_back(edge.to.clone());
@@ -92,7 +100,7 @@ impl AccountOrder {
         }
 
         if sorted.len() != graph.nodes.len() {
-            return Err(SolifyError::CircularDependency.into());
+            return Err(SolifyError::CircularDependency);
         }
 
         Ok(sorted)
@@ -135,10 +143,8 @@ impl AccountOrder {
         let mut recursion_stack = std::collections::HashSet::new();
 
         for account in graph.keys() {
-            if !visited.contains(account) {
-                if self.has_circular_dependency(account, &graph, &mut visited, &mut recursion_stack) {
-                    return Err(SolifyError::CircularDependency.into());
-                }
+            if !visited.contains(account) && self.has_circular_dependency(account, &graph, &mut visited, &mut recursion_stack) {
+                return Err(SolifyError::CircularDependency);
             }
         }
 
@@ -1,4 +1,5 @@
 pub use solify_common::types::{IdlData, IdlInstruction, IdlAccountItem};
+use solify_common::types::IdlSeed;
 pub use solify_common::errors::{SolifyError, Result};
 
 #[derive(Debug, Clone)]
@@ -14,21 +15,21 @@ pub struct AccountInfo {
     pub constraints: Vec<ConstraintInfo>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct SeedInfo {
     pub seed_type: SeedType,
     pub value: String,
     pub source: SeedSource,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum SeedType {
     Static,
     AccountKey,
     Argument,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum SeedSource {
     Authority,
     UserAccount,
@@ -36,13 +37,13 @@ pub enum SeedSource {
     Custom(String),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ConstraintInfo {
     pub constraint_type: ConstraintType,
     pub value: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ConstraintType {
     Init,
     Mut,
@@ -51,8 +52,20 @@ pub enum ConstraintType {
     HasOne,
     Owner,
     Constraint,
-    Close,  
-    Realloc, 
+    Close,
+    Realloc,
+}
+
+impl AccountInfo {
+    /// Instructions recorded on the account's `constraint_type` constraints, e.g.
+    /// the instructions that close it.
+    pub fn instructions_with(&self, constraint_type: ConstraintType) -> Vec<String> {
+        self.constraints
+            .iter()
+            .filter(|c| c.constraint_type == constraint_type)
+            .filter_map(|c| c.value.clone())
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -76,6 +89,17 @@ pub struct DependencyEdge {
     pub account: String,
 }
 
+impl DependencyEdge {
+    /// The error for an edge whose target instruction is not a node of its graph,
+    /// which the graph builder never produces.
+    pub(crate) fn dangling(&self) -> SolifyError {
+        SolifyError::DependencyAnalysisFailed(format!(
+            "the edge for account {} leads to {}, which is not in the dependency graph",
+            self.account, self.to
+        ))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum DependencyType {
     Initialization,
@@ -96,14 +120,27 @@ impl AccountRegistry {
 
     pub fn add_or_update_account(&mut self, account: AccountInfo) {
         if let Some(existing) = self.accounts.iter_mut().find(|a| a.name == account.name) {
-            // Update existing account
-            existing.used_in.extend(account.used_in);
+            // Update existing account; an account listed twice in one
+            // instruction must not count as two uses.
+            for instruction in account.used_in {
+                if !existing.used_in.contains(&instruction) {
+                    existing.used_in.push(instruction);
+                }
+            }
             if account.initialized_by.is_some() {
                 existing.initialized_by = account.initialized_by;
             }
-            if !account.seeds.is_empty() {
+            if !account.seeds.is_empty() && existing.seeds != account.seeds {
                 existing.seeds = account.seeds;
             }
+            // Close and realloc belong to the instruction, so each one is kept.
+            for constraint in account.constraints {
+                if matches!(constraint.constraint_type, ConstraintType::Close | ConstraintType::Realloc)
+                    && !existing.constraints.contains(&constraint)
+                {
+                    existing.constraints.push(constraint);
+                }
+            }
         } else {
             self.accounts.push(account);
         }
@@ -113,18 +150,122 @@ impl AccountRegistry {
         self.accounts.iter().find(|a| a.name == name)
     }
 
+    /// Account a PDA seed depends on: user and vault seeds by name, and any other
+    /// account-key seed whose path resolves to a registered account. Authority
+    /// seeds only count when they name another PDA, since a signing authority is
+    /// never initialized. Constants, arguments and bumps never create a dependency.
+    pub fn seed_dependency<'a>(&self, seed: &'a SeedInfo) -> Option<&'a str> {
+        match &seed.source {
+            SeedSource::UserAccount | SeedSource::Vault => Some(&seed.value),
+            source if matches!(seed.seed_type, SeedType::AccountKey) => {
+                let account = seed.value.split('.').next().unwrap_or(&seed.value);
+                let registered = self.get_account(account)?;
+                match source {
+                    SeedSource::Authority => registered.is_pda.then_some(account),
+                    _ => Some(account),
+                }
+            }
+            _ => None,
+        }
+    }
+
     pub fn find_accounts_initialized_by(&self, instruction: &str) -> Vec<&AccountInfo> {
         self.accounts
             .iter()
-            .filter(|a| a.initialized_by.as_ref().map_or(false, |i| i == instruction))
+            .filter(|a| a.initialized_by.as_ref().is_some_and(|i| i == instruction))
             .collect()
     }
 }
 
-pub struct DependencyAnalyzerImpl;
+impl Default for AccountRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Words in an instruction name that mark it as creating the accounts it takes.
+pub const DEFAULT_INIT_KEYWORDS: &[&str] = &["init", "create", "open", "new", "setup", "register"];
+
+/// Words in an instruction name that mark it as closing the accounts it writes.
+pub const CLOSE_KEYWORDS: &[&str] = &["close", "delete", "remove", "destroy"];
+
+/// Words in an instruction name that mark it as growing the accounts it writes.
+pub const REALLOC_KEYWORDS: &[&str] = &["realloc", "resize", "extend", "grow", "expand"];
+
+/// Writable accounts that receive the rent of a closed account, or pay for a
+/// resize, rather than being closed or resized themselves.
+const RENT_ACCOUNT_NAMES: &[&str] = &["destination", "recipient", "receiver", "payer", "authority", "owner"];
+
+/// Whether any word of `instruction_name`, split on `_` and camelCase humps, starts with
+/// one of `keywords`, either directly or after a `re` prefix. `init` covers `initialize`,
+/// `userInit` and `reinitialize`, but `register` does not match `deregister`.
+pub fn is_initializing_instruction(instruction_name: &str, keywords: &[String]) -> bool {
+    has_keyword(instruction_name, keywords.iter().map(String::as_str))
+}
+
+fn has_keyword<'a>(instruction_name: &str, keywords: impl IntoIterator<Item = &'a str> + Clone) -> bool {
+    name_words(instruction_name).iter().any(|word| {
+        let unprefixed = word.strip_prefix("re");
+        keywords.clone().into_iter().any(|keyword| {
+            word.starts_with(keyword) || unprefixed.is_some_and(|rest| rest.starts_with(keyword))
+        })
+    })
+}
+
+/// The lowercase words of a snake_case or camelCase name.
+fn name_words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut previous_lowercase = false;
+    for c in name.chars() {
+        if (c == '_' || (c.is_uppercase() && previous_lowercase)) && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        if c != '_' {
+            word.extend(c.to_lowercase());
+        }
+        previous_lowercase = c.is_lowercase() || c.is_ascii_digit();
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Whether the account's docs carry an Anchor `init` or `init_if_needed` constraint,
+/// such as a copied `#[account(init, payer = user, space = 8)]` attribute.
+fn declares_init(account_item: &IdlAccountItem) -> bool {
+    account_item.docs.iter().any(|doc| {
+        doc.split(|c: char| !c.is_alphanumeric() && c != '_')
+            .any(|token| token == "init" || token == "init_if_needed")
+    })
+}
+
+/// Writable accounts of a closing or resizing instruction that are not signers,
+/// rent recipients or fixed-address programs. Anchor puts `close` and `realloc`
+/// on exactly these accounts, but the IDL does not record either constraint.
+fn is_resized_or_closed_candidate(account_item: &IdlAccountItem) -> bool {
+    let name = account_item.name.to_lowercase();
+    account_item.is_mut
+        && !account_item.is_signer
+        && account_item.address.is_none()
+        && !RENT_ACCOUNT_NAMES.iter().any(|rent| name.contains(rent))
+}
+
+pub struct DependencyAnalyzerImpl {
+    pub init_keywords: Vec<String>,
+}
+
+impl Default for DependencyAnalyzerImpl {
+    fn default() -> Self {
+        Self {
+            init_keywords: DEFAULT_INIT_KEYWORDS.iter().map(|k| k.to_string()).collect(),
+        }
+    }
+}
 
 impl DependencyAnalyzerImpl {
-    pub fn build_account_registry(&self, idl_data: &IdlData, program: &String) -> Result<AccountRegistry> {
+    pub fn build_account_registry(&self, idl_data: &IdlData, program: &str) -> Result<AccountRegistry> {
         let mut registry = AccountRegistry::new();
 
         for instruction in &idl_data.instructions {
@@ -138,7 +279,7 @@ impl DependencyAnalyzerImpl {
         &self,
         instruction: &IdlInstruction,
         registry: &mut AccountRegistry,
-        program: &String,
+        program: &str,
     ) -> Result<()> {
         for account_item in &instruction.accounts {
             let account_info = self.parse_account_info(account_item, instruction, program)?;
@@ -151,44 +292,27 @@ impl DependencyAnalyzerImpl {
         &self,
         account_item: &IdlAccountItem,
         instruction: &IdlInstruction,
-        program: &String,
+        program: &str,
     ) -> Result<AccountInfo> {
         let mut seeds = Vec::new();
         let mut constraints = Vec::new();
         let mut initialized_by = None;
         let mut is_pda = false;
-        let mut program_pda = program.clone();  
+        let mut program_pda = program.to_string();
 
         if let Some(pda_info) = &account_item.pda {
             is_pda = true;
 
-            if pda_info.program == *program {
+            // The IDL only carries a program for PDAs owned by another program.
+            if !pda_info.program.is_empty() {
                 program_pda = pda_info.program.clone();
-            } else {
-                program_pda = program.clone();
             }
             for idl_seed in &pda_info.seeds {
-                let seed_type = match idl_seed.kind.as_str() {
-                    "const" | "constant" => SeedType::Static,
-                    "arg" | "argument" => SeedType::Argument,
-                    "account" => SeedType::AccountKey,
-                    _ => {
-                        println!("Unknown seed kind: {}, defaulting to Static", idl_seed.kind);
-                        SeedType::Static
-                    }
-                };
-                
-                let seed_value = match seed_type {
-                    SeedType::Static => {
-                        if !idl_seed.value.is_empty() {
-                            idl_seed.value.clone()
-                        } else {
-                            // Fallback: try to extract from path if value is empty
-                            idl_seed.path.clone()
-                        }
-                    },
-                    _ => idl_seed.path.clone(),
-                };
+                let seed_type = seed_type_for_kind(&idl_seed.kind).unwrap_or_else(|| {
+                    tracing::warn!("Unknown seed kind: {}, defaulting to Static", idl_seed.kind);
+                    SeedType::Static
+                });
+                let seed_value = seed_value(idl_seed, &seed_type);
                 
                 let source = if idl_seed.path.contains("authority") || idl_seed.path.contains("owner") {
                     SeedSource::Authority
@@ -212,40 +336,55 @@ impl DependencyAnalyzerImpl {
                 value: Some(format!("{} seeds", seeds.len())),
             });
             
-            println!("Found PDA account '{}' with {} seeds", account_item.name, seeds.len());
+            tracing::debug!("Found PDA account '{}' with {} seeds", account_item.name, seeds.len());
         }
 
-        if account_item.is_mut == true {
+        if account_item.is_mut {
             constraints.push(ConstraintInfo {
                 constraint_type: ConstraintType::Mut,
                 value: Some(String::from("mut")),
             });
         }
 
-        if account_item.is_signer == true {
+        if account_item.is_signer {
             constraints.push(ConstraintInfo {
                 constraint_type: ConstraintType::Signer,
                 value: Some(String::from("signer")),
             });
         }
 
-        let instruction_name_lower = instruction.name.to_lowercase();
-        if instruction_name_lower.contains("init") || 
-           instruction_name_lower.contains("create") ||
-           instruction_name_lower.contains("initialize") {
+        let declares_init = declares_init(account_item);
+        if declares_init || is_initializing_instruction(&instruction.name, &self.init_keywords) {
             initialized_by = Some(instruction.name.clone());
             
-            if account_item.is_mut {
+            if declares_init || account_item.is_mut {
+                // `init` implies `mut`, so it replaces the Mut constraint instead of joining it.
+                constraints.retain(|c| c.constraint_type != ConstraintType::Mut);
                 constraints.push(ConstraintInfo {
                     constraint_type: ConstraintType::Init,
                     value: None,
                 });
-                println!("Inferred init constraint for '{}' in instruction '{}'", 
+                tracing::debug!("Inferred init constraint for '{}' in instruction '{}'",
                      account_item.name, instruction.name);
             }
         }
 
 
+        if is_resized_or_closed_candidate(account_item) {
+            let mentions_realloc = account_item.docs.iter().any(|doc| doc.to_lowercase().contains("realloc"));
+            if has_keyword(&instruction.name, CLOSE_KEYWORDS.iter().copied()) {
+                constraints.push(ConstraintInfo {
+                    constraint_type: ConstraintType::Close,
+                    value: Some(instruction.name.clone()),
+                });
+            } else if mentions_realloc || has_keyword(&instruction.name, REALLOC_KEYWORDS.iter().copied()) {
+                constraints.push(ConstraintInfo {
+                    constraint_type: ConstraintType::Realloc,
+                    value: Some(instruction.name.clone()),
+                });
+            }
+        }
+
         Ok(AccountInfo {
             name: account_item.name.clone(),
             is_pda,
@@ -307,8 +446,8 @@ impl DependencyAnalyzerImpl {
             for account_name in &node.initializes {
                 if let Some(account) = registry.get_account(account_name) {
                     for seed in &account.seeds {
-                        if let SeedSource::UserAccount | SeedSource::Vault = seed.source {
-                            if let Some(dep_node_index) = graph.nodes[..i].iter().position(|n| n.initializes.contains(&seed.value)) {
+                        if let Some(seed_account) = registry.seed_dependency(seed) {
+                            if let Some(dep_node_index) = graph.nodes[..i].iter().position(|n| n.initializes.iter().any(|a| a == seed_account)) {
                                 graph.edges.push(DependencyEdge {
                                     from: graph.nodes[dep_node_index].name.clone(),
                                     to: node.name.clone(),
@@ -358,15 +497,13 @@ impl DependencyAnalyzerImpl {
         let mut recursion_stack = std::collections::HashSet::new();
 
         for node in &graph.nodes {
-            if !visited.contains(&node.name) {
-                if self.has_cycle(
-                    graph,
-                    &node.name,
-                    &mut visited,
-                    &mut recursion_stack,
-                )? {
-                    return Err(SolifyError::CircularDependency.into());
-                }
+            if !visited.contains(&node.name) && self.has_cycle(
+                graph,
+                &node.name,
+                &mut visited,
+                &mut recursion_stack,
+            )? {
+                return Err(SolifyError::CircularDependency);
             }
         }
 
@@ -388,10 +525,8 @@ impl DependencyAnalyzerImpl {
                 if recursion_stack.contains(&edge.to) {
                     return Ok(true);
                 }
-                if !visited.contains(&edge.to) {
-                    if self.has_cycle(graph, &edge.to, visited, recursion_stack)? {
-                        return Ok(true);
-                    }
+                if !visited.contains(&edge.to) && self.has_cycle(graph, &edge.to, visited, recursion_stack)? {
+                    return Ok(true);
                 }
             }
         }
@@ -400,9 +535,79 @@ impl DependencyAnalyzerImpl {
         Ok(false)
     }
 
-    // kahn's algorithm
+    /// Like `build_dependency_graph`, but links an instruction to the ones initializing
+    /// its accounts wherever they sit in `execution_order`, so the graph describes the
+    /// order the instructions need rather than the order given.
+    pub fn build_ordering_graph(
+        &self,
+        idl_data: &IdlData,
+        execution_order: &[String],
+        registry: &AccountRegistry,
+    ) -> Result<DependencyGraph> {
+        let mut graph = DependencyGraph {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        };
+
+        for instruction_name in execution_order {
+            let instruction = idl_data.instructions
+                .iter()
+                .find(|i| &i.name == instruction_name)
+                .ok_or(SolifyError::InvalidInstructionOrder(instruction_name.clone()))?;
+            graph.nodes.push(self.create_instruction_node(instruction, registry));
+        }
+
+        for (i, node) in graph.nodes.iter().enumerate() {
+            let initializer = |account_name: &str| {
+                graph.nodes
+                    .iter()
+                    .enumerate()
+                    .find(|(j, n)| *j != i && n.initializes.iter().any(|a| a == account_name))
+                    .map(|(_, n)| n.name.clone())
+            };
+
+            for account_name in &node.requires {
+                if let Some(from) = initializer(account_name) {
+                    graph.edges.push(DependencyEdge {
+                        from,
+                        to: node.name.clone(),
+                        dependency_type: DependencyType::Initialization,
+                        account: account_name.clone(),
+                    });
+                }
+            }
+
+            for account_name in &node.initializes {
+                let Some(account) = registry.get_account(account_name) else {
+                    continue;
+                };
+                for seed in &account.seeds {
+                    if let Some(seed_account) = registry.seed_dependency(seed) {
+                        if let Some(from) = initializer(seed_account) {
+                            graph.edges.push(DependencyEdge {
+                                from,
+                                to: node.name.clone(),
+                                dependency_type: DependencyType::SeedDependency,
+                                account: account_name.clone(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(graph)
+    }
+
+    // kahn's algorithm; among the instructions that are ready, the one listed first
+    // in the graph goes next, so an order that already satisfies every edge is kept
     pub fn topological_sort(&self, graph: &DependencyGraph) -> Result<Vec<String>> {
         let mut in_degree = std::collections::HashMap::new();
+        let position: std::collections::HashMap<&str, usize> = graph.nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.name.as_str(), i))
+            .collect();
         
         // Initialize in-degree for all nodes
         for node in &graph.nodes {
@@ -411,31 +616,37 @@ impl DependencyAnalyzerImpl {
 
         // Calculate in-degree
         for edge in &graph.edges {
-            *in_degree.get_mut(&edge.to).unwrap() += 1;
+            *in_degree
+                .get_mut(&edge.to)
+                .ok_or_else(|| edge.dangling())? += 1;
         }
 
         // Find nodes with in-degree 0
-        let mut queue: std::collections::VecDeque<String> = in_degree
+        let mut ready: std::collections::BTreeSet<usize> = graph.nodes
             .iter()
-            .filter(|(_, &degree)| degree == 0)
-            .map(|(name, _)| name.clone())
+            .enumerate()
+            .filter(|(_, node)| in_degree[&node.name] == 0)
+            .map(|(i, _)| i)
             .collect();
 
         let mut sorted = Vec::new();
         let mut visited_edges = 0;
 
-        while let Some(node) = queue.pop_front() {
+        while let Some(index) = ready.pop_first() {
+            let node = &graph.nodes[index].name;
             sorted.push(node.clone());
 
             // Decrease in-degree of neighbors
             for edge in &graph.edges {
-                if edge.from == node {
-                    let to_degree = in_degree.get_mut(&edge.to).unwrap();
+                if &edge.from == node {
+                    let to_degree = in_degree
+                        .get_mut(&edge.to)
+                        .ok_or_else(|| edge.dangling())?;
                     *to_degree -= 1;
                     visited_edges += 1;
 
                     if *to_degree == 0 {
-                        queue.push_back(edge.to.clone());
+                        ready.insert(position[edge.to.as_str()]);
                     }
                 }
             }
@@ -443,9 +654,29 @@ impl DependencyAnalyzerImpl {
 
         // Check if we have a cycle
         if visited_edges != graph.edges.len() {
-            return Err(SolifyError::CircularDependency.into());
+            return Err(SolifyError::CircularDependency);
         }
 
         Ok(sorted)
     }
-}
\ No newline at end of file
+}
+
+/// Maps an IDL seed kind to its seed type, or `None` for kinds we don't know.
+pub(crate) fn seed_type_for_kind(kind: &str) -> Option<SeedType> {
+    match kind {
+        "const" | "constant" => Some(SeedType::Static),
+        "arg" | "argument" => Some(SeedType::Argument),
+        "account" => Some(SeedType::AccountKey),
+        _ => None,
+    }
+}
+
+/// The value recorded for an IDL seed: the literal for constants, the path otherwise.
+pub(crate) fn seed_value(idl_seed: &IdlSeed, seed_type: &SeedType) -> String {
+    match seed_type {
+        // Fallback: try to extract from path if value is empty
+        SeedType::Static if idl_seed.value.is_empty() => idl_seed.path.clone(),
+        SeedType::Static => idl_seed.value.clone(),
+        _ => idl_seed.path.clone(),
+    }
+}
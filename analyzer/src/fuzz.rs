@@ -0,0 +1,116 @@
+use rand::distributions::{ Alphanumeric, DistString };
+use rand::{ Rng, SeedableRng };
+use rand_chacha::ChaCha8Rng;
+use solify_common::types::{ ArgumentConstraint, ArgumentInfo, ArgumentType };
+
+use crate::ArgumentValueProvider;
+
+/// Longest string drawn for a string argument that declares no maximum.
+const MAX_FUZZ_STRING_LEN: u32 = 32;
+
+/// Randomized positive cases added for every instruction. The same seed always
+/// produces the same values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FuzzConfig {
+    /// Fuzz cases per instruction.
+    pub cases: usize,
+    pub seed: u64,
+}
+
+impl FuzzConfig {
+    /// The generator for `instruction`. Mixing in the name keeps each
+    /// instruction's values the same when the execution order changes.
+    pub(crate) fn rng(&self, instruction: &str) -> ChaCha8Rng {
+        // FNV-1a
+        let name_hash = instruction
+            .bytes()
+            .fold(0xcbf29ce484222325u64, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3));
+        ChaCha8Rng::seed_from_u64(self.seed ^ name_hash)
+    }
+}
+
+/// A random value for `arg` inside its type's range and declared constraints.
+/// Pubkeys and types without a range fall back to `fallback`, since the test
+/// needs an account it actually holds.
+pub(crate) fn fuzz_value(
+    arg: &ArgumentInfo,
+    rng: &mut impl Rng,
+    fallback: &dyn ArgumentValueProvider
+) -> String {
+    match &arg.arg_type {
+        ArgumentType::Bool => rng.gen_bool(0.5).to_string(),
+        ArgumentType::String { max_length } => {
+            let (min, max) = string_length_bounds(arg, *max_length);
+            let len = rng.gen_range(min..=max) as usize;
+            format!("\"{}\"", Alphanumeric.sample_string(rng, len))
+        }
+        ArgumentType::Option { inner_type } => {
+            if rng.gen_bool(0.5) {
+                "null".to_string()
+            } else {
+                let inner = ArgumentInfo { arg_type: (**inner_type).clone(), ..arg.clone() };
+                fuzz_value(&inner, rng, fallback)
+            }
+        }
+        arg_type => match integer_bounds(arg_type, &arg.constraints) {
+            Some((min, max)) if min <= max => {
+                let non_zero = arg.constraints.iter().any(|c| matches!(c, ArgumentConstraint::NonZero));
+                loop {
+                    let value = rng.gen_range(min..=max);
+                    if !(non_zero && value == 0 && (min, max) != (0, 0)) {
+                        return value.to_string();
+                    }
+                }
+            }
+            _ => fallback.value_for(arg),
+        },
+    }
+}
+
+/// The range of an integer argument, narrowed by its constraints. `u128` is
+/// drawn up to `i128::MAX`.
+fn integer_bounds(arg_type: &ArgumentType, constraints: &[ArgumentConstraint]) -> Option<(i128, i128)> {
+    let (mut min, mut max) = match arg_type {
+        ArgumentType::U8 => (0, i128::from(u8::MAX)),
+        ArgumentType::U16 => (0, i128::from(u16::MAX)),
+        ArgumentType::U32 => (0, i128::from(u32::MAX)),
+        ArgumentType::U64 => (0, i128::from(u64::MAX)),
+        ArgumentType::U128 => (0, i128::MAX),
+        ArgumentType::I8 => (i128::from(i8::MIN), i128::from(i8::MAX)),
+        ArgumentType::I16 => (i128::from(i16::MIN), i128::from(i16::MAX)),
+        ArgumentType::I32 => (i128::from(i32::MIN), i128::from(i32::MAX)),
+        ArgumentType::I64 => (i128::from(i64::MIN), i128::from(i64::MAX)),
+        ArgumentType::I128 => (i128::MIN, i128::MAX),
+        _ => return None,
+    };
+    for constraint in constraints {
+        match constraint {
+            ArgumentConstraint::Min { value } => min = min.max(i128::from(*value)),
+            ArgumentConstraint::Max { value } => max = max.min(i128::from(*value)),
+            ArgumentConstraint::Range { min: low, max: high } => {
+                min = min.max(i128::from(*low));
+                max = max.min(i128::from(*high));
+            }
+            _ => {}
+        }
+    }
+    Some((min, max))
+}
+
+/// The length range of a string argument. Without a declared minimum the string
+/// is never empty, since programs commonly reject empty names and titles.
+fn string_length_bounds(arg: &ArgumentInfo, max_length: Option<u32>) -> (u32, u32) {
+    let mut min = None;
+    let mut max = max_length.unwrap_or(MAX_FUZZ_STRING_LEN).min(MAX_FUZZ_STRING_LEN);
+    for constraint in &arg.constraints {
+        match constraint {
+            ArgumentConstraint::MinLength { value } => min = Some(min.unwrap_or(0).max(*value)),
+            ArgumentConstraint::MaxLength { value } => max = max.min(*value),
+            _ => {}
+        }
+    }
+    match min {
+        Some(min) => (min, max.max(min)),
+        None => (max.min(1), max),
+    }
+}
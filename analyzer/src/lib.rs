@@ -11,95 +11,123 @@ pub mod setup_generator;
 pub use setup_generator::*;
 pub mod test_case_generator;
 pub use test_case_generator::*;
-pub struct DependencyAnalyzer;
+pub mod fuzz;
+pub use fuzz::FuzzConfig;
+
+#[cfg(test)]
+mod tests;
+
+/// Called with each instruction as its test cases are generated.
+pub type ProgressCallback = Box<dyn Fn(&InstructionProgress) + Send + Sync>;
+
+/// Builds test metadata from an IDL. Construct it with [`DependencyAnalyzer::new`]
+/// or `Default`, then configure it with the `with_*` methods.
+pub struct DependencyAnalyzer {
+    init_keywords: Vec<String>,
+    on_progress: Option<ProgressCallback>,
+    fuzz: Option<FuzzConfig>,
+}
+
+impl Default for DependencyAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl DependencyAnalyzer {
     pub fn new() -> Self {
-        Self
+        Self::with_init_keywords(DEFAULT_INIT_KEYWORDS.iter().map(|k| k.to_string()).collect())
     }
 
-    pub fn analyze_dependencies(
+    /// Uses `init_keywords` instead of [`DEFAULT_INIT_KEYWORDS`] to decide which
+    /// instructions initialize the accounts they take.
+    pub fn with_init_keywords(init_keywords: Vec<String>) -> Self {
+        Self { init_keywords, on_progress: None, fuzz: None }
+    }
+
+    /// Reports each instruction to `on_progress` as [`DependencyAnalyzer::analyze`]
+    /// reaches it, so callers can show "Analyzing 3/12: increment".
+    pub fn with_progress(mut self, on_progress: impl Fn(&InstructionProgress) + Send + Sync + 'static) -> Self {
+        self.on_progress = Some(Box::new(on_progress));
+        self
+    }
+
+    /// Adds randomized positive cases to every instruction; see [`TestCaseGenerator::with_fuzz`].
+    pub fn with_fuzz(mut self, fuzz: FuzzConfig) -> Self {
+        self.fuzz = Some(fuzz);
+        self
+    }
+
+    /// The dependency-respecting order closest to `execution_order`, or `None` when
+    /// `execution_order` already respects every dependency (or they form a cycle).
+    pub fn suggest_execution_order(
         &self,
         idl_data: &IdlData,
         execution_order: &[String],
-        program: String,
-    ) -> Result<TestMetadata> {
-        println!("Starting dependency analysis...");
-
-        // Build account registry
-        let dependency_analyzer = DependencyAnalyzerImpl;
-        let account_registry = dependency_analyzer.build_account_registry(idl_data, &program).map_err(|e| SolifyError::DependencyAnalysisFailed(e.to_string()))?;
-        println!("Account registry built with {} accounts", account_registry.accounts.len());
-        println!("Account registry: {:#?}", account_registry);
+        program: &str,
+    ) -> Result<Option<Vec<String>>> {
+        let dependency_analyzer = DependencyAnalyzerImpl { init_keywords: self.init_keywords.clone() };
+        let account_registry = dependency_analyzer.build_account_registry(idl_data, program)?;
+        let graph = dependency_analyzer.build_ordering_graph(idl_data, execution_order, &account_registry)?;
+        match dependency_analyzer.topological_sort(&graph) {
+            Ok(sorted) if sorted != execution_order => Ok(Some(sorted)),
+            Ok(_) => Ok(None),
+            Err(SolifyError::CircularDependency) => {
+                tracing::debug!("instruction dependencies form a cycle, no order to suggest");
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
 
-        // Build dependency graph
+    /// Analyzes `idl_data` into test metadata for `execution_order`. Errors from
+    /// any step, such as accounts whose seeds depend on each other, are returned
+    /// rather than logged, and nothing is printed.
+    pub fn analyze(
+        &self,
+        idl_data: &IdlData,
+        execution_order: &[String],
+        program: &str,
+    ) -> Result<TestMetadata> {
+        let dependency_analyzer = DependencyAnalyzerImpl { init_keywords: self.init_keywords.clone() };
+        let account_registry = dependency_analyzer.build_account_registry(idl_data, program)
+            .map_err(|e| SolifyError::DependencyAnalysisFailed(e.to_string()))?;
         let dependency_graph = dependency_analyzer.build_dependency_graph(
-            idl_data, 
-            execution_order, 
+            idl_data,
+            execution_order,
             &account_registry
         ).map_err(|e| SolifyError::DependencyAnalysisFailed(e.to_string()))?;
 
-        println!("Dependency graph built with {} nodes and {} edges", 
-             dependency_graph.nodes.len(), dependency_graph.edges.len());
-
-        println!("Dependency graph: {:#?}", dependency_graph);
-
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━-------------------");
-        println!("Account Order:");
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━--------------------");
-
         let account_order = AccountOrder;
         let account_dependencies = account_order.generate_account_dependencies(
-            &dependency_graph, 
+            &dependency_graph,
             &account_registry
-        ).unwrap();
-        println!("Generated {} account dependencies", account_dependencies.len());
-
-        println!("Account dependencies: {:#?}", account_dependencies);
-
-        account_order.validate_account_flow(&account_dependencies).unwrap();
-        println!("Account flow validation passed");
-
-        
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━-------------------");
-        println!("PDA Detection:");
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━--------------------");
+        )?;
+        account_order.validate_account_flow(&account_dependencies)?;
 
         let pda_detector = PdaDetector;
-        let program_id = program.clone(); 
-        let pda_init_sequence = pda_detector.detect_pdas(&account_registry, program_id).unwrap();
-        println!("Detected {} PDAs", pda_init_sequence.len());
-        println!("PDA init sequence: {:#?}", pda_init_sequence);
-
+        let pda_init_sequence = pda_detector.detect_pdas(&account_registry, program.to_string())?;
+        for pda_init in &pda_init_sequence {
+            pda_detector.validate_seed_order(pda_init, idl_data)?;
+        }
 
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━-------------------");
-        println!("Setup Generation:");
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━--------------------");
-
-        // // Generate setup requirements
         let setup_generator = SetupGenerator;
-        let setup_requirements = setup_generator.generate_setup_requirements(&account_dependencies).unwrap();
-        println!("Generated {} setup requirements", setup_requirements.len());
-        println!("Setup requirements: {:#?}", setup_requirements);
-
-        setup_generator.validate_setup_flow(&setup_requirements).unwrap();
-        println!("Setup flow validation passed");
-
-
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━-------------------");
-        println!("Test Case Generation:");
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━--------------------");
-
-        // // Generate test cases
-        let test_case_generator = TestCaseGenerator;
-        let test_cases = test_case_generator.generate_test_cases(idl_data, execution_order).unwrap();
-        println!("Test cases: {:#?}", test_cases);
-        
-        let total_positive_cases: usize = test_cases.iter().map(|tc| tc.positive_cases.len()).sum();
-        let total_negative_cases: usize = test_cases.iter().map(|tc| tc.negative_cases.len()).sum();
-        
-        println!("Generated {} test cases ({} positive, {} negative)", 
-             test_cases.len(), total_positive_cases, total_negative_cases);
+        let setup_requirements = setup_generator.generate_setup_requirements(&account_dependencies)?;
+        setup_generator.validate_setup_flow(&setup_requirements)?;
+
+        let mut test_case_generator = TestCaseGenerator::new();
+        if let Some(fuzz) = self.fuzz {
+            test_case_generator = test_case_generator.with_fuzz(fuzz);
+        }
+        let test_cases = test_case_generator.generate_test_cases_with_progress(
+            idl_data,
+            execution_order,
+            |progress| {
+                if let Some(on_progress) = &self.on_progress {
+                    on_progress(progress);
+                }
+            }
+        )?;
 
         Ok(TestMetadata {
             instruction_order: execution_order.to_vec(),
@@ -109,4 +137,43 @@ impl DependencyAnalyzer {
             test_cases,
         })
     }
+
+    /// [`DependencyAnalyzer::analyze`] inside an `analyze` span that records the
+    /// account and PDA counts and logs what was generated.
+    pub fn analyze_dependencies(
+        &self,
+        idl_data: &IdlData,
+        execution_order: &[String],
+        program: String,
+    ) -> Result<TestMetadata> {
+        let span = tracing::info_span!(
+            "analyze",
+            instructions = execution_order.len(),
+            accounts = tracing::field::Empty,
+            pdas = tracing::field::Empty,
+        );
+        let _guard = span.enter();
+
+        let metadata = self.analyze(idl_data, execution_order, &program)?;
+        span.record("accounts", metadata.account_dependencies.len());
+        span.record("pdas", metadata.pda_init_sequence.len());
+        for pda_init in &metadata.pda_init_sequence {
+            for warning in PdaDetector.seed_limit_warnings(pda_init) {
+                tracing::warn!("{}", warning);
+            }
+        }
+        tracing::debug!(sequence = ?metadata.pda_init_sequence, "PDAs detected");
+        tracing::debug!(setup_requirements = metadata.setup_requirements.len(), "setup flow validated");
+
+        let total_positive_cases: usize = metadata.test_cases.iter().map(|tc| tc.positive_cases.len()).sum();
+        let total_negative_cases: usize = metadata.test_cases.iter().map(|tc| tc.negative_cases.len()).sum();
+        tracing::info!(
+            positive = total_positive_cases,
+            negative = total_negative_cases,
+            "generated test cases for {} instructions",
+            metadata.test_cases.len()
+        );
+
+        Ok(metadata)
+    }
 }
\ No newline at end of file
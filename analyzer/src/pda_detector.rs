@@ -1,7 +1,13 @@
-use solify_common::types::{PdaInit, SeedComponent, SeedType as OutputSeedType};
+use solify_common::types::{IdlData, PdaInit, SeedComponent, SeedType as OutputSeedType};
 use solify_common::errors::{SolifyError, Result};
 use crate::dependency_analyzer::*;
 
+/// Longest seed `findProgramAddressSync` accepts, in bytes.
+pub const MAX_SEED_LEN: usize = 32;
+
+/// Most seeds a PDA can be derived from.
+pub const MAX_SEEDS: usize = 16;
+
 pub struct PdaDetector;
 
 impl PdaDetector {
@@ -10,13 +16,13 @@ impl PdaDetector {
 
         for account in &registry.accounts {
             if account.is_pda {
-                let pda_init = self.create_pda_init(account, program_id.clone()).unwrap();
+                let pda_init = self.create_pda_init(account, program_id.clone())?;
                 pda_inits.push(pda_init);
             }
         }
 
         // Sort PDAs by their dependencies
-        self.sort_pdas_by_dependencies(&mut pda_inits, registry).unwrap();
+        self.sort_pdas_by_dependencies(&mut pda_inits, registry)?;
 
         Ok(pda_inits)
     }
@@ -25,14 +31,8 @@ impl PdaDetector {
         let seeds = account.seeds
             .iter()
             .map(|seed_info| {
-                let seed_type = match seed_info.seed_type {
-                    SeedType::Static => OutputSeedType::Static,
-                    SeedType::AccountKey => OutputSeedType::AccountKey,
-                    SeedType::Argument => OutputSeedType::Argument,
-                };
-
                 SeedComponent {
-                    seed_type,
+                    seed_type: output_seed_type(&seed_info.seed_type),
                     value: seed_info.value.clone(),
                 }
             })
@@ -44,7 +44,7 @@ impl PdaDetector {
         Ok(PdaInit {
             account_name: account.name.clone(),
             seeds,
-            program_id: program_id.clone(),
+            program_id: account.program.clone().unwrap_or(program_id),
             space: Some(space),
         })
     }
@@ -70,22 +70,22 @@ impl PdaDetector {
             let account = registry.get_account(&pda_init.account_name)
                 .ok_or(SolifyError::DependencyAnalysisFailed(format!("Account not found: {}", pda_init.account_name)))?;
             
-            let mut deps = Vec::new();
-            for seed in &account.seeds {
-                if let SeedType::AccountKey = seed.seed_type {
-                    deps.push(seed.value.clone());
-                }
-            }
+            let deps = account.seeds
+                .iter()
+                .filter_map(|seed| registry.seed_dependency(seed))
+                .map(String::from)
+                .collect();
             dependencies.push((i, deps));
         }
 
         // Simple topological sort for PDAs
+        let pda_names: Vec<&str> = pda_inits.iter().map(|pda_init| pda_init.account_name.as_str()).collect();
         let mut sorted_indices = Vec::new();
         let mut visited = std::collections::HashSet::new();
 
         for i in 0..pda_inits.len() {
             if !visited.contains(&i) {
-                self.visit_pda(i, &dependencies, &mut visited, &mut sorted_indices)?;
+                self.visit_pda(i, &pda_names, &dependencies, &mut visited, &mut sorted_indices)?;
             }
         }
 
@@ -102,6 +102,7 @@ impl PdaDetector {
     fn visit_pda(
         &self,
         index: usize,
+        pda_names: &[&str],
         dependencies: &[(usize, Vec<String>)],
         visited: &mut std::collections::HashSet<usize>,
         sorted: &mut Vec<usize>,
@@ -110,11 +111,10 @@ impl PdaDetector {
 
         let (_, deps) = &dependencies[index];
         for dep_name in deps {
-            if let Some(dep_index) = dependencies.iter()
-                .position(|(_, d)| d.contains(dep_name))
-            {
+            // A seed naming another PDA puts that PDA first.
+            if let Some(dep_index) = pda_names.iter().position(|name| name == dep_name) {
                 if !visited.contains(&dep_index) {
-                    self.visit_pda(dep_index, dependencies, visited, sorted)?;
+                    self.visit_pda(dep_index, pda_names, dependencies, visited, sorted)?;
                 }
             }
         }
@@ -123,20 +123,96 @@ impl PdaDetector {
         Ok(())
     }
 
+    /// Checks that `pda_init` lists its seeds in the order the IDL declares them.
+    ///
+    /// PDA derivation is order-sensitive, so a reordered seed list derives a
+    /// different address than the program expects.
+    pub fn validate_seed_order(&self, pda_init: &PdaInit, idl_data: &IdlData) -> Result<()> {
+        let actual: Vec<(OutputSeedType, &str)> = pda_init.seeds
+            .iter()
+            .map(|seed| (seed.seed_type.clone(), seed.value.as_str()))
+            .collect();
+
+        let declarations: Vec<Vec<(OutputSeedType, String)>> = idl_data.instructions
+            .iter()
+            .flat_map(|instruction| instruction.accounts.iter())
+            .filter(|acc| acc.name == pda_init.account_name)
+            .filter_map(|acc| acc.pda.as_ref())
+            .map(|pda| {
+                pda.seeds
+                    .iter()
+                    .map(|idl_seed| {
+                        let seed_type = seed_type_for_kind(&idl_seed.kind).unwrap_or(SeedType::Static);
+                        let value = seed_value(idl_seed, &seed_type);
+                        (output_seed_type(&seed_type), value)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let matches_declaration = |declared: &Vec<(OutputSeedType, String)>| {
+            declared.len() == actual.len() &&
+                declared
+                    .iter()
+                    .zip(&actual)
+                    .all(|((ty, value), (actual_ty, actual_value))| ty == actual_ty && value == actual_value)
+        };
+
+        if declarations.is_empty() || declarations.iter().any(matches_declaration) {
+            return Ok(());
+        }
+
+        let expected: Vec<&str> = declarations[0].iter().map(|(_, value)| value.as_str()).collect();
+        let found: Vec<&str> = actual.iter().map(|(_, value)| *value).collect();
+        Err(SolifyError::DependencyAnalysisFailed(format!(
+            "Seed order for PDA '{}' does not match the IDL: expected [{}], found [{}]",
+            pda_init.account_name,
+            expected.join(", "),
+            found.join(", ")
+        )))
+    }
+
+    /// Describes each way `pda_init` breaks the runtime's seed limits, which would
+    /// make the generated `findProgramAddressSync` call throw.
+    pub fn seed_limit_warnings(&self, pda_init: &PdaInit) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if pda_init.seeds.len() > MAX_SEEDS {
+            warnings.push(format!(
+                "PDA '{}' has {} seeds, but at most {} are allowed",
+                pda_init.account_name,
+                pda_init.seeds.len(),
+                MAX_SEEDS
+            ));
+        }
+        for seed in pda_init.seeds.iter().filter(|seed| seed.seed_type == OutputSeedType::Static) {
+            let len = static_seed_len(&seed.value);
+            if len > MAX_SEED_LEN {
+                warnings.push(format!(
+                    "PDA '{}' has a {}-byte static seed \"{}\", but seeds are limited to {} bytes",
+                    pda_init.account_name,
+                    len,
+                    seed.value,
+                    MAX_SEED_LEN
+                ));
+            }
+        }
+        warnings
+    }
+
     pub fn validate_pda_seeds(&self, pda_init: &PdaInit, registry: &AccountRegistry) -> Result<bool> {
         for seed in &pda_init.seeds {
             match seed.seed_type {
                 OutputSeedType::AccountKey => {
                     // Check if the referenced account exists
                     if registry.get_account(&seed.value).is_none() {
-                        println!("Warning: PDA seed references unknown account: {}", seed.value);
+                        tracing::warn!("PDA seed references unknown account: {}", seed.value);
                         return Ok(false);
                     }
                 }
                 OutputSeedType::Argument => {
                     // Arguments will be validated during test execution
                     // For now, just log a message
-                    println!("PDA uses argument seed: {}", seed.value);
+                    tracing::debug!("PDA uses argument seed: {}", seed.value);
                 }
                 OutputSeedType::Static => {
                     // Static seeds are always valid
@@ -145,4 +221,21 @@ impl PdaDetector {
         }
         Ok(true)
     }
-}
\ No newline at end of file
+}
+
+fn output_seed_type(seed_type: &SeedType) -> OutputSeedType {
+    match seed_type {
+        SeedType::Static => OutputSeedType::Static,
+        SeedType::AccountKey => OutputSeedType::AccountKey,
+        SeedType::Argument => OutputSeedType::Argument,
+    }
+}
+
+/// Byte length of a static seed. The parser renders 32-byte constants as base58
+/// public keys, which are longer as text than the bytes they decode to.
+fn static_seed_len(value: &str) -> usize {
+    match bs58::decode(value).into_vec() {
+        Ok(bytes) if bytes.len() == MAX_SEED_LEN => bytes.len(),
+        _ => value.len(),
+    }
+}
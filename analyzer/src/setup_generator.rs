@@ -21,6 +21,7 @@ impl SetupGenerator {
                 requirement_type: SetupType::CreateKeypair,
                 description: format!("Create keypair for {}", signer.account_name),
                 dependencies: Vec::new(),
+                account_name: Some(signer.account_name.clone()),
             });
         }
 
@@ -30,6 +31,7 @@ impl SetupGenerator {
                 requirement_type: SetupType::FundAccount,
                 description: format!("Fund {} with SOL for transactions", signer.account_name),
                 dependencies: vec![signer.account_name.clone()],
+                account_name: Some(signer.account_name.clone()),
             });
         }
 
@@ -53,6 +55,7 @@ impl SetupGenerator {
                 requirement_type: SetupType::InitializePda,
                 description: format!("Initialize {} PDA", pda.account_name),
                 dependencies,
+                account_name: Some(pda.account_name.clone()),
             });
         }
 
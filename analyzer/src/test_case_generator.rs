@@ -1,36 +1,187 @@
-use solify_common::types::{IdlData, IdlInstruction, IdlField, InstructionTestCases, TestCase, TestCaseType, TestArgumentValue, TestValueType, ExpectedOutcome, ArgumentInfo, ArgumentType, ArgumentConstraint};
+use solify_common::types::{IdlData, IdlInstruction, IdlField, IdlTypeDef, InstructionTestCases, TestCase, TestCaseType, TestArgumentValue, TestValueType, ExpectedOutcome, ArgumentInfo, ArgumentType, ArgumentConstraint};
 use solify_common::errors::{SolifyError, Result};
+use solify_common::to_camel_case;
 
-pub struct TestCaseGenerator;
+use crate::fuzz::{fuzz_value, FuzzConfig};
+
+/// Supplies the argument values used in generated positive test cases.
+pub trait ArgumentValueProvider {
+    fn value_for(&self, arg: &ArgumentInfo) -> String;
+}
+
+/// Type-based placeholder values used when no custom provider is configured.
+pub struct DefaultValueProvider;
+
+impl ArgumentValueProvider for DefaultValueProvider {
+    fn value_for(&self, arg: &ArgumentInfo) -> String {
+        match &arg.arg_type {
+            // Small types get values inside their range (u8 max 255, i8 max 127).
+            ArgumentType::U8 => "100".to_string(),
+            | ArgumentType::U16
+            | ArgumentType::U32
+            | ArgumentType::U64
+            | ArgumentType::U128 => "1000".to_string(),
+            ArgumentType::I8 => "50".to_string(),
+            | ArgumentType::I16
+            | ArgumentType::I32
+            | ArgumentType::I64
+            | ArgumentType::I128 => "500".to_string(),
+            ArgumentType::Bool => "true".to_string(),
+            ArgumentType::String { max_length: Some(max_length) } => {
+                format!("\"a\".repeat({})", (max_length / 2).max(1))
+            }
+            ArgumentType::String { .. } => "\"test_value\"".to_string(),
+            ArgumentType::Pubkey => pubkey_value(&arg.name),
+            // The present case of an optional argument passes a value of the inner type.
+            ArgumentType::Option { inner_type } => self.value_for(&ArgumentInfo {
+                arg_type: (**inner_type).clone(),
+                ..arg.clone()
+            }),
+            _ => "/* valid value */".to_string(),
+        }
+    }
+}
+
+/// How deeply struct literals nest before generation falls back to the provider.
+const MAX_LITERAL_DEPTH: usize = 8;
+
+/// Argument names that refer to the account paying for and signing the test.
+const PAYER_ARGUMENT_NAMES: [&str; 3] = ["authority", "payer", "owner"];
+
+/// Payer-like `Pubkey` arguments get the payer's key. Any other gets a keypair of
+/// its own named after the argument, so `from` and `to` are distinct accounts;
+/// the generator declares every `<name>Keypair` a test references. Names must
+/// match exactly: `newOwner` is a different account from the payer.
+pub fn pubkey_value(arg_name: &str) -> String {
+    if PAYER_ARGUMENT_NAMES.iter().any(|name| arg_name.eq_ignore_ascii_case(name)) {
+        "authority.publicKey".to_string()
+    } else {
+        format!("{}Keypair.publicKey", arg_name)
+    }
+}
+
+/// Which instruction test case generation has reached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionProgress {
+    /// 1-based position in the execution order.
+    pub index: usize,
+    pub total: usize,
+    pub instruction: String,
+}
+
+impl InstructionProgress {
+    /// Share of the instructions finished before this one, in `0.0..1.0`.
+    pub fn ratio(&self) -> f64 {
+        (self.index - 1) as f64 / self.total as f64
+    }
+}
+
+impl std::fmt::Display for InstructionProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Analyzing {}/{}: {}", self.index, self.total, self.instruction)
+    }
+}
+
+/// Builds each instruction's test cases. Holds the value provider, so construct it
+/// with [`TestCaseGenerator::new`], `Default` or [`TestCaseGenerator::with_provider`].
+pub struct TestCaseGenerator {
+    value_provider: Box<dyn ArgumentValueProvider>,
+    fuzz: Option<FuzzConfig>,
+}
+
+impl Default for TestCaseGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl TestCaseGenerator {
+    pub fn new() -> Self {
+        Self::with_provider(DefaultValueProvider)
+    }
+
+    pub fn with_provider(provider: impl ArgumentValueProvider + 'static) -> Self {
+        Self {
+            value_provider: Box::new(provider),
+            fuzz: None,
+        }
+    }
+
+    /// Adds `fuzz.cases` positive cases per instruction with random values inside
+    /// each argument's range.
+    pub fn with_fuzz(mut self, fuzz: FuzzConfig) -> Self {
+        self.fuzz = Some(fuzz);
+        self
+    }
+
     pub fn generate_test_cases(
         &self,
         idl_data: &IdlData,
         execution_order: &[String]
+    ) -> Result<Vec<InstructionTestCases>> {
+        self.generate_test_cases_with_progress(idl_data, execution_order, |_| {})
+    }
+
+    /// [`TestCaseGenerator::generate_test_cases`], calling `on_progress` before
+    /// each instruction in `execution_order` is processed.
+    pub fn generate_test_cases_with_progress(
+        &self,
+        idl_data: &IdlData,
+        execution_order: &[String],
+        mut on_progress: impl FnMut(&InstructionProgress)
     ) -> Result<Vec<InstructionTestCases>> {
         let mut all_test_cases = Vec::new();
 
-        for instruction_name in execution_order {
+        for (i, instruction_name) in execution_order.iter().enumerate() {
+            on_progress(&InstructionProgress {
+                index: i + 1,
+                total: execution_order.len(),
+                instruction: instruction_name.clone(),
+            });
             let instruction = idl_data.instructions
                 .iter()
                 .find(|i| &i.name == instruction_name)
                 .ok_or(SolifyError::InvalidInstructionOrder(instruction_name.clone()))?;
 
-            let test_cases = self.generate_instruction_test_cases(instruction)?;
+            let test_cases = self.generate_instruction_test_cases(instruction, &idl_data.types)?;
             all_test_cases.push(test_cases);
         }
 
         Ok(all_test_cases)
     }
 
+    /// Regenerates the cases of every instruction in `test_cases` taking a newtype
+    /// over a primitive (e.g. `Amount(u64)`) from `idl_data`. Struct field types are
+    /// not stored on-chain, so metadata the program analyzed tests those as structs.
+    pub fn resolve_newtype_arguments(
+        &self,
+        test_cases: &mut [InstructionTestCases],
+        idl_data: &IdlData
+    ) -> Result<()> {
+        for instruction_cases in test_cases.iter_mut() {
+            let Some(instruction) = idl_data.instructions
+                .iter()
+                .find(|i| i.name == instruction_cases.instruction_name) else {
+                continue;
+            };
+            let takes_newtype = instruction.args
+                .iter()
+                .any(|arg| resolve_newtype(&arg.field_type, &idl_data.types) != arg.field_type);
+            if takes_newtype {
+                *instruction_cases = self.generate_instruction_test_cases(instruction, &idl_data.types)?;
+            }
+        }
+        Ok(())
+    }
+
     fn generate_instruction_test_cases(
         &self,
-        instruction: &IdlInstruction
+        instruction: &IdlInstruction,
+        types: &[IdlTypeDef]
     ) -> Result<InstructionTestCases> {
-        let arguments = self.parse_arguments(&instruction.args)?;
-        let positive_cases = self.generate_positive_cases(&instruction.name, &arguments)?;
-        let negative_cases = self.generate_negative_cases(&instruction.name, &arguments)?;
+        let arguments = self.parse_arguments(&instruction.args, types)?;
+        let positive_cases = self.generate_positive_cases(&instruction.name, &arguments, types)?;
+        let negative_cases = self.generate_negative_cases(&instruction.name, &arguments, types)?;
 
         Ok(InstructionTestCases {
             instruction_name: instruction.name.clone(),
@@ -40,25 +191,55 @@ impl TestCaseGenerator {
         })
     }
 
-    fn parse_arguments(&self, args: &[IdlField]) -> Result<Vec<ArgumentInfo>> {
+    fn parse_arguments(&self, args: &[IdlField], types: &[IdlTypeDef]) -> Result<Vec<ArgumentInfo>> {
         let mut argument_infos = Vec::new();
 
         for arg in args {
-            let arg_type = self.parse_argument_type(&arg)?;
-            let constraints = self.extract_constraints_from_docs(&arg)?;
+            // Newtypes over primitives are tested as the primitive they wrap
+            let resolved = IdlField {
+                name: arg.name.clone(),
+                field_type: resolve_newtype(&arg.field_type, types).to_string(),
+            };
+            let arg_type = self.parse_argument_type(&resolved, types)?;
+            let constraints = self.extract_constraints_from_docs(&resolved)?;
+            let is_optional = matches!(arg_type, ArgumentType::Option { .. });
 
             argument_infos.push(ArgumentInfo {
                 name: arg.name.clone(),
                 arg_type,
                 constraints,
-                is_optional: false, // Would need to parse from IDL
+                is_optional,
             });
         }
 
         Ok(argument_infos)
     }
 
-    fn parse_argument_type(&self, field_type: &IdlField) -> Result<ArgumentType> {
+    fn parse_argument_type(&self, field_type: &IdlField, types: &[IdlTypeDef]) -> Result<ArgumentType> {
+        if let Some(inner) = option_inner_type(&field_type.field_type) {
+            let inner = IdlField {
+                name: field_type.name.clone(),
+                field_type: resolve_newtype(inner, types).to_string(),
+            };
+            let inner_type = self.parse_argument_type(&inner, types)?;
+            return Ok(ArgumentType::Option { inner_type: Box::new(inner_type) });
+        }
+
+        if let Some(inner) = vec_inner_type(&field_type.field_type) {
+            let inner = IdlField {
+                name: field_type.name.clone(),
+                field_type: resolve_newtype(inner, types).to_string(),
+            };
+            let inner_type = self.parse_argument_type(&inner, types)?;
+            return Ok(ArgumentType::Vec { inner_type: Box::new(inner_type), max_length: None });
+        }
+
+        if let Some(type_def) = find_type_def(&field_type.field_type, types) {
+            if type_def.kind == "struct" {
+                return Ok(ArgumentType::Struct { name: type_def.name.clone() });
+            }
+        }
+
         match field_type.field_type.as_str() {
             "u8" => Ok(ArgumentType::U8),
             "u16" => Ok(ArgumentType::U16),
@@ -72,11 +253,97 @@ impl TestCaseGenerator {
             "i128" => Ok(ArgumentType::I128),
             "bool" => Ok(ArgumentType::Bool),
             "string" => Ok(ArgumentType::String { max_length: None }),
-            "publicKey" => Ok(ArgumentType::Pubkey),
+            "publicKey" | "pubkey" => Ok(ArgumentType::Pubkey),
             _ => Ok(ArgumentType::Vec { inner_type: Box::new(ArgumentType::U8), max_length: None }),
         }
     }
 
+    /// The provider's value for `arg`, except that structs and vectors of structs
+    /// are built from the struct's fields in `types`.
+    fn value_for(&self, arg: &ArgumentInfo, types: &[IdlTypeDef]) -> String {
+        self.composite_literal(&arg.arg_type, types, 0)
+            .unwrap_or_else(|| self.value_provider.value_for(arg))
+    }
+
+    /// `{ price: new anchor.BN("1000"), name: "test_value" }` for a struct and a
+    /// one-element array of it for a vector of structs. `None` for other types and
+    /// for structs with a field no literal can be written for.
+    fn composite_literal(&self, arg_type: &ArgumentType, types: &[IdlTypeDef], depth: usize) -> Option<String> {
+        if depth > MAX_LITERAL_DEPTH {
+            return None;
+        }
+        match arg_type {
+            ArgumentType::Struct { name } => self.struct_literal(name, types, depth),
+            ArgumentType::Vec { inner_type, .. } if matches!(**inner_type, ArgumentType::Struct { .. }) => {
+                Some(format!("[{}]", self.composite_literal(inner_type, types, depth + 1)?))
+            }
+            _ => None,
+        }
+    }
+
+    fn struct_literal(&self, name: &str, types: &[IdlTypeDef], depth: usize) -> Option<String> {
+        let type_def = find_type_def(name, types).filter(|type_def| type_def.kind == "struct")?;
+        let mut fields = Vec::new();
+        for (field, field_type) in type_def.fields.iter().zip(&type_def.field_types) {
+            // Tuple struct fields are positional, not object keys
+            if field.parse::<usize>().is_ok() {
+                return None;
+            }
+            let info = ArgumentInfo {
+                name: field.clone(),
+                arg_type: self.parse_argument_type(
+                    &IdlField { name: field.clone(), field_type: resolve_newtype(field_type, types).to_string() },
+                    types
+                ).ok()?,
+                constraints: vec![],
+                is_optional: false,
+            };
+            let value = self.field_literal(&info, field_type, types, depth + 1)?;
+            fields.push(format!("{}: {}", to_camel_case(field), value));
+        }
+        Some(format!("{{ {} }}", fields.join(", ")))
+    }
+
+    /// A struct field's value as TypeScript. Unlike top-level arguments, which the
+    /// generator converts, 64- and 128-bit integers are wrapped in `anchor.BN` here,
+    /// and pubkeys are fresh keys since no keypair is declared for a field.
+    fn field_literal(
+        &self,
+        field: &ArgumentInfo,
+        field_type: &str,
+        types: &[IdlTypeDef],
+        depth: usize
+    ) -> Option<String> {
+        let defined = find_type_def(resolve_newtype(field_type, types), types);
+        if defined.is_some_and(|type_def| type_def.kind != "struct") {
+            return None;
+        }
+        match &field.arg_type {
+            ArgumentType::Struct { .. } => self.composite_literal(&field.arg_type, types, depth),
+            ArgumentType::Vec { .. } if is_struct_vec(&field.arg_type) => {
+                self.composite_literal(&field.arg_type, types, depth)
+            }
+            ArgumentType::Vec { .. } if field_type == "bytes" || vec_inner_type(field_type).is_some() => {
+                Some("[]".to_string())
+            }
+            // Anything else parsed as a vector is a type with no literal (e.g. an array)
+            ArgumentType::Vec { .. } => None,
+            ArgumentType::Pubkey => Some("Keypair.generate().publicKey".to_string()),
+            | ArgumentType::U64
+            | ArgumentType::U128
+            | ArgumentType::I64
+            | ArgumentType::I128 => {
+                let value = self.value_provider.value_for(field);
+                if value.parse::<i128>().is_ok() || value.parse::<u128>().is_ok() {
+                    Some(format!("new anchor.BN(\"{}\")", value))
+                } else {
+                    Some(value)
+                }
+            }
+            _ => Some(self.value_provider.value_for(field)),
+        }
+    }
+
     fn extract_constraints_from_docs(&self, field_type: &IdlField) -> Result<Vec<ArgumentConstraint>> {
     let mut constraints = Vec::new();
 
@@ -100,12 +367,13 @@ impl TestCaseGenerator {
     fn generate_positive_cases(
         &self,
     instruction_name: &str,
-    arguments: &[ArgumentInfo]
+    arguments: &[ArgumentInfo],
+    types: &[IdlTypeDef]
 ) -> Result<Vec<TestCase>> {
     let mut positive_cases = Vec::new();
 
     // Generate basic positive case
-    let basic_case = self.create_basic_positive_case(instruction_name, arguments)?;
+    let basic_case = self.create_basic_positive_case(instruction_name, arguments, types)?;
     positive_cases.push(basic_case);
 
     // Generate boundary cases for numeric types
@@ -115,33 +383,98 @@ impl TestCaseGenerator {
         }
     }
 
+    // Optional arguments are also passed as `null`
+    for arg in arguments.iter().filter(|arg| arg.is_optional) {
+        positive_cases.push(self.create_replaced_argument_case(arguments, arg, "null", "omitted", "Optional argument omitted", types));
+    }
+
+    // Vectors of structs are also passed empty
+    for arg in arguments.iter().filter(|arg| is_struct_vec(&arg.arg_type)) {
+        positive_cases.push(self.create_replaced_argument_case(arguments, arg, "[]", "empty array", "Empty array accepted", types));
+    }
+
+    if let Some(fuzz) = &self.fuzz {
+        positive_cases.extend(self.create_fuzz_cases(instruction_name, arguments, fuzz, types));
+    }
+
     Ok(positive_cases)
 }
 
+fn create_fuzz_cases(
+    &self,
+    instruction_name: &str,
+    arguments: &[ArgumentInfo],
+    fuzz: &FuzzConfig,
+    types: &[IdlTypeDef]
+) -> Vec<TestCase> {
+    let mut rng = fuzz.rng(instruction_name);
+    (1..=fuzz.cases)
+        .map(|case| TestCase {
+            test_type: TestCaseType::Positive,
+            description: format!("{} - fuzz case {} (seed {})", instruction_name, case, fuzz.seed),
+            argument_values: arguments
+                .iter()
+                .map(|arg| TestArgumentValue {
+                    argument_name: arg.name.clone(),
+                    value_type: TestValueType::Valid {
+                        description: self
+                            .composite_literal(&arg.arg_type, types, 0)
+                            .unwrap_or_else(|| fuzz_value(arg, &mut rng, self.value_provider.as_ref())),
+                    },
+                })
+                .collect(),
+            expected_outcome: ExpectedOutcome::Success {
+                state_changes: vec!["Randomized valid inputs accepted".to_string()],
+            },
+        })
+        .collect()
+}
+
+/// The basic positive case with `replaced` passed as `value`, described as `label`.
+fn create_replaced_argument_case(
+    &self,
+    arguments: &[ArgumentInfo],
+    replaced: &ArgumentInfo,
+    value: &str,
+    label: &str,
+    state_change: &str,
+    types: &[IdlTypeDef]
+) -> TestCase {
+    let argument_values = arguments
+        .iter()
+        .map(|arg| {
+            let value = if arg.name == replaced.name {
+                value.to_string()
+            } else {
+                self.value_for(arg, types)
+            };
+            TestArgumentValue {
+                argument_name: arg.name.clone(),
+                value_type: TestValueType::Valid { description: value },
+            }
+        })
+        .collect();
+
+    TestCase {
+        test_type: TestCaseType::Positive,
+        description: format!("{} - {}", replaced.name, label),
+        argument_values,
+        expected_outcome: ExpectedOutcome::Success {
+            state_changes: vec![state_change.to_string()],
+        },
+    }
+}
+
 fn create_basic_positive_case(
     &self,
     instruction_name: &str,
-    arguments: &[ArgumentInfo]
+    arguments: &[ArgumentInfo],
+    types: &[IdlTypeDef]
 ) -> Result<TestCase> {
     let argument_values = arguments
         .iter()
         .map(|arg| {
-            let value = match &arg.arg_type {
-                | ArgumentType::U8
-                | ArgumentType::U16
-                | ArgumentType::U32
-                | ArgumentType::U64
-                | ArgumentType::U128 => "1000".to_string(),
-                | ArgumentType::I8
-                | ArgumentType::I16
-                | ArgumentType::I32
-                | ArgumentType::I64
-                | ArgumentType::I128 => "500".to_string(),
-                ArgumentType::Bool => "true".to_string(),
-                ArgumentType::String { .. } => "\"test_value\"".to_string(),
-                ArgumentType::Pubkey => "authority.publicKey".to_string(),
-                _ => "/* valid value */".to_string(),
-            };
+            let value = self.value_for(arg, types);
 
             TestArgumentValue {
                 argument_name: arg.name.clone(),
@@ -214,12 +547,16 @@ fn generate_boundary_cases(&self, argument: &ArgumentInfo) -> Result<Option<Vec<
     fn generate_negative_cases(
         &self,
     instruction_name: &str,
-    arguments: &[ArgumentInfo]
+    arguments: &[ArgumentInfo],
+    types: &[IdlTypeDef]
 ) -> Result<Vec<TestCase>> {
     let mut negative_cases = Vec::new();
 
     for arg in arguments {
         negative_cases.extend(self.generate_argument_negative_cases(instruction_name, arg)?);
+        if let Some(case) = self.create_too_long_vec_case(instruction_name, arg, types) {
+            negative_cases.push(case);
+        }
     }
 
     // Add combined negative case
@@ -230,7 +567,7 @@ fn generate_boundary_cases(&self, argument: &ArgumentInfo) -> Result<Option<Vec<
     Ok(negative_cases)
 }
 
-fn generate_argument_negative_cases(
+pub(crate) fn generate_argument_negative_cases(
     &self,
     instruction_name: &str,
     argument: &ArgumentInfo
@@ -280,14 +617,15 @@ fn create_constraint_violation_case(
     constraint: &ArgumentConstraint
 ) -> Result<Option<TestCase>> {
     let test_case = match constraint {
+        // Skip the case when the violating value doesn't fit in an i64.
         ArgumentConstraint::Min { value } =>
-            Some(TestCase {
+            value.checked_sub(1).map(|below| TestCase {
                 test_type: TestCaseType::NegativeBoundary,
                 description: format!("{} - {} below minimum", instruction_name, argument.name),
                 argument_values: vec![TestArgumentValue {
                     argument_name: argument.name.clone(),
                     value_type: TestValueType::Invalid {
-                        description: (value - 1).to_string(),
+                        description: below.to_string(),
                         reason: format!("Below minimum value of {}", value),
                     },
                 }],
@@ -297,13 +635,13 @@ fn create_constraint_violation_case(
                 },
             }),
         ArgumentConstraint::Max { value } =>
-            Some(TestCase {
+            value.checked_add(1).map(|above| TestCase {
                 test_type: TestCaseType::NegativeBoundary,
                 description: format!("{} - {} above maximum", instruction_name, argument.name),
                 argument_values: vec![TestArgumentValue {
                     argument_name: argument.name.clone(),
                     value_type: TestValueType::Invalid {
-                        description: (value + 1).to_string(),
+                        description: above.to_string(),
                         reason: format!("Above maximum value of {}", value),
                     },
                 }],
@@ -341,14 +679,19 @@ fn generate_numeric_negative_cases(
 ) -> Result<Vec<TestCase>> {
     let mut cases = Vec::new();
 
-    // Overflow case
+    // Overflow case; 128-bit arguments need a sentinel beyond u64
+    let overflow_value = match argument.arg_type {
+        ArgumentType::U128 => "u128::MAX",
+        ArgumentType::I128 => "i128::MAX",
+        _ => "u64::MAX",
+    };
     cases.push(TestCase {
         test_type: TestCaseType::NegativeOverflow,
         description: format!("{} - {} overflow", instruction_name, argument.name),
         argument_values: vec![TestArgumentValue {
             argument_name: argument.name.clone(),
             value_type: TestValueType::Invalid {
-                description: "u64::MAX".to_string(),
+                description: overflow_value.to_string(),
                 reason: "Potential arithmetic overflow".to_string(),
             },
         }],
@@ -378,7 +721,7 @@ fn generate_numeric_negative_cases(
     Ok(cases)
 }
 
-fn generate_string_negative_cases(
+pub(crate) fn generate_string_negative_cases(
     &self,
     instruction_name: &str,
     argument: &ArgumentInfo
@@ -409,7 +752,7 @@ fn generate_string_negative_cases(
         argument_values: vec![TestArgumentValue {
             argument_name: argument.name.clone(),
             value_type: TestValueType::Invalid {
-                description: "\"a\".repeat(1000)".to_string(),
+                description: format!("\"a\".repeat({})", too_long_string_length(&argument.arg_type)),
                 reason: "Exceeds maximum length".to_string(),
             },
         }],
@@ -422,6 +765,37 @@ fn generate_string_negative_cases(
     Ok(cases)
 }
 
+/// A vector of structs one element past its declared maximum, or 1000 elements
+/// when the maximum is unknown.
+fn create_too_long_vec_case(
+    &self,
+    instruction_name: &str,
+    argument: &ArgumentInfo,
+    types: &[IdlTypeDef]
+) -> Option<TestCase> {
+    let ArgumentType::Vec { inner_type, max_length } = &argument.arg_type else {
+        return None;
+    };
+    let element = self.composite_literal(inner_type, types, 0)?;
+    let length = max_length.map_or(1000, |max_length| u64::from(max_length) + 1);
+
+    Some(TestCase {
+        test_type: TestCaseType::NegativeBoundary,
+        description: format!("{} - {} too long", instruction_name, argument.name),
+        argument_values: vec![TestArgumentValue {
+            argument_name: argument.name.clone(),
+            value_type: TestValueType::Invalid {
+                description: format!("Array.from({{ length: {} }}, () => ({}))", length, element),
+                reason: "Exceeds maximum length".to_string(),
+            },
+        }],
+        expected_outcome: ExpectedOutcome::Failure {
+            error_code: None,
+            error_message: "Vector exceeds maximum length".to_string(),
+        },
+    })
+}
+
 fn generate_pubkey_negative_cases(
     &self,
     instruction_name: &str,
@@ -475,4 +849,52 @@ fn generate_pubkey_negative_cases(
         },
     })
 }
-}
\ No newline at end of file
+}
+
+/// Length of the "too long" string case: one past the declared `max_length`, or a
+/// generous 1000 characters when the limit is unknown.
+fn too_long_string_length(arg_type: &ArgumentType) -> u64 {
+    match arg_type {
+        ArgumentType::String { max_length: Some(max_length) } => u64::from(*max_length) + 1,
+        _ => 1000,
+    }
+}
+
+/// `Option<u64>` -> `u64`.
+fn option_inner_type(name: &str) -> Option<&str> {
+    name.strip_prefix("Option<")?.strip_suffix('>')
+}
+
+/// `Vec<Item>` -> `Item`.
+fn vec_inner_type(name: &str) -> Option<&str> {
+    name.strip_prefix("Vec<")?.strip_suffix('>')
+}
+
+fn is_struct_vec(arg_type: &ArgumentType) -> bool {
+    matches!(arg_type, ArgumentType::Vec { inner_type, .. } if matches!(**inner_type, ArgumentType::Struct { .. }))
+}
+
+fn find_type_def<'a>(name: &str, types: &'a [IdlTypeDef]) -> Option<&'a IdlTypeDef> {
+    types.iter().find(|type_def| type_def.name == name)
+}
+
+/// Unwraps a defined single-field struct over a primitive (e.g. `Amount(u64)`) to
+/// that primitive's name; any other type name is returned unchanged.
+fn resolve_newtype<'a>(name: &'a str, types: &'a [IdlTypeDef]) -> &'a str {
+    match find_type_def(name, types) {
+        Some(type_def) if type_def.kind == "struct" => match type_def.field_types.as_slice() {
+            [inner] if is_primitive_type(inner) => inner,
+            _ => name,
+        },
+        _ => name,
+    }
+}
+
+fn is_primitive_type(name: &str) -> bool {
+    matches!(
+        name,
+        "u8" | "u16" | "u32" | "u64" | "u128"
+            | "i8" | "i16" | "i32" | "i64" | "i128"
+            | "bool" | "string" | "publicKey" | "pubkey"
+    )
+}
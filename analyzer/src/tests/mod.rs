@@ -0,0 +1,828 @@
+use solana_sdk::pubkey::Pubkey;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use solify_common::types::{
+    ArgumentConstraint,
+    ArgumentInfo,
+    ArgumentType,
+    IdlAccountItem,
+    IdlData,
+    IdlField,
+    IdlInstruction,
+    IdlPda,
+    IdlSeed,
+    IdlTypeDef,
+    PdaInit,
+    SeedComponent,
+    TestCase,
+    TestValueType,
+};
+
+use crate::{
+    AccountInfo,
+    AccountRegistry,
+    ConstraintType,
+    ArgumentValueProvider,
+    DefaultValueProvider,
+    DependencyAnalyzer,
+    DependencyAnalyzerImpl,
+    DependencyEdge,
+    DependencyGraph,
+    DependencyType,
+    DEFAULT_INIT_KEYWORDS,
+    FuzzConfig,
+    InstructionNode,
+    PdaDetector,
+    SolifyError,
+    TestCaseGenerator,
+    is_initializing_instruction,
+};
+use crate::fuzz::fuzz_value;
+
+const PROGRAM_ID: &str = "7tvJ6jxJF81pozUSa2o8yPo6zsQCxG4GyF2b6JgaHqaa";
+const FOREIGN_PROGRAM_ID: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
+
+fn sample_idl() -> IdlData {
+    IdlData {
+        name: "vault".to_string(),
+        version: "0.1.0".to_string(),
+        instructions: vec![IdlInstruction {
+            name: "deposit".to_string(),
+            accounts: vec![],
+            args: vec![
+                IdlField { name: "amount".to_string(), field_type: "u64".to_string() },
+                IdlField { name: "memo".to_string(), field_type: "string".to_string() },
+            ],
+            docs: vec![],
+            returns: None,
+        }],
+        accounts: vec![],
+        types: vec![],
+        errors: vec![],
+        constants: vec![],
+        events: vec![],
+    }
+}
+
+fn valid_values(generator: &TestCaseGenerator) -> Vec<(String, String)> {
+    let test_cases = generator
+        .generate_test_cases(&sample_idl(), &["deposit".to_string()])
+        .unwrap();
+    test_cases[0].positive_cases[0]
+        .argument_values
+        .iter()
+        .map(|value| match &value.value_type {
+            TestValueType::Valid { description } => (value.argument_name.clone(), description.clone()),
+            other => panic!("Expected a valid value, got {:?}", other),
+        })
+        .collect()
+}
+
+struct TokenAmountProvider;
+
+impl ArgumentValueProvider for TokenAmountProvider {
+    fn value_for(&self, arg: &ArgumentInfo) -> String {
+        match arg.arg_type {
+            ArgumentType::U64 => "1_000_000_000".to_string(),
+            _ => "\"deposit memo\"".to_string(),
+        }
+    }
+}
+
+#[test]
+fn test_default_value_provider() {
+    let values = valid_values(&TestCaseGenerator::new());
+    assert_eq!(values, vec![
+        ("amount".to_string(), "1000".to_string()),
+        ("memo".to_string(), "\"test_value\"".to_string()),
+    ]);
+}
+
+#[test]
+fn test_default_values_fit_small_integer_types() {
+    for (arg_type, max) in [(ArgumentType::U8, u8::MAX as i64), (ArgumentType::I8, i8::MAX as i64)] {
+        let arg = ArgumentInfo {
+            name: "level".to_string(),
+            arg_type: arg_type.clone(),
+            constraints: vec![],
+            is_optional: false,
+        };
+        let value: i64 = DefaultValueProvider.value_for(&arg).parse().unwrap();
+        assert!((0..=max).contains(&value), "{:?} default {} is out of range", arg_type, value);
+    }
+}
+
+#[test]
+fn test_pubkey_arguments_get_distinct_keypairs() {
+    let mut idl = sample_idl();
+    idl.instructions[0].args = ["from", "to", "authority", "newOwner"]
+        .map(|name| IdlField { name: name.to_string(), field_type: "pubkey".to_string() })
+        .to_vec();
+    let test_cases = TestCaseGenerator::new()
+        .generate_test_cases(&idl, &["deposit".to_string()])
+        .unwrap();
+    let values: Vec<String> = test_cases[0].positive_cases[0]
+        .argument_values
+        .iter()
+        .map(|value| match &value.value_type {
+            TestValueType::Valid { description } => description.clone(),
+            other => panic!("Unexpected value: {:?}", other),
+        })
+        .collect();
+    assert_eq!(values, [
+        "fromKeypair.publicKey",
+        "toKeypair.publicKey",
+        "authority.publicKey",
+        "newOwnerKeypair.publicKey",
+    ]);
+}
+
+#[test]
+fn test_custom_value_provider() {
+    let values = valid_values(&TestCaseGenerator::with_provider(TokenAmountProvider));
+    assert_eq!(values, vec![
+        ("amount".to_string(), "1_000_000_000".to_string()),
+        ("memo".to_string(), "\"deposit memo\"".to_string()),
+    ]);
+}
+
+fn pda_account(name: &str, program: &str) -> IdlAccountItem {
+    IdlAccountItem {
+        name: name.to_string(),
+        is_mut: true,
+        is_signer: false,
+        is_optional: false,
+        docs: vec![],
+        pda: Some(IdlPda {
+            seeds: vec![
+                IdlSeed { kind: "const".to_string(), path: String::new(), value: name.to_string() },
+                IdlSeed { kind: "account".to_string(), path: "authority".to_string(), value: String::new() },
+            ],
+            program: program.to_string(),
+        }),
+        address: None,
+    }
+}
+
+#[test]
+fn test_foreign_pda_keeps_its_program_id() {
+    let mut idl = sample_idl();
+    idl.instructions[0].accounts = vec![
+        IdlAccountItem {
+            name: "authority".to_string(),
+            is_mut: true,
+            is_signer: true,
+            is_optional: false,
+            docs: vec![],
+            pda: None,
+            address: None,
+        },
+        pda_account("vault", ""),
+        pda_account("metadata", FOREIGN_PROGRAM_ID),
+    ];
+
+    let metadata = DependencyAnalyzer::new()
+        .analyze_dependencies(&idl, &["deposit".to_string()], PROGRAM_ID.to_string())
+        .unwrap();
+
+    let program_of = |name: &str| {
+        metadata.pda_init_sequence
+            .iter()
+            .find(|pda| pda.account_name == name)
+            .map(|pda| pda.program_id.clone())
+            .unwrap()
+    };
+    assert_eq!(program_of("vault"), PROGRAM_ID);
+    assert_eq!(program_of("metadata"), FOREIGN_PROGRAM_ID);
+}
+
+fn bounded_string_arg(max_length: Option<u32>) -> ArgumentInfo {
+    ArgumentInfo {
+        name: "title".to_string(),
+        arg_type: ArgumentType::String { max_length },
+        constraints: vec![],
+        is_optional: false,
+    }
+}
+
+fn too_long_value(arg: &ArgumentInfo) -> String {
+    let cases = TestCaseGenerator::new()
+        .generate_string_negative_cases("create_entry", arg)
+        .unwrap();
+    let too_long = cases
+        .iter()
+        .find(|case| case.description.ends_with("too long"))
+        .expect("missing too long case");
+    match &too_long.argument_values[0].value_type {
+        TestValueType::Invalid { description, .. } => description.clone(),
+        other => panic!("Expected an invalid value, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_string_cases_follow_declared_max_length() {
+    let arg = bounded_string_arg(Some(32));
+    assert_eq!(too_long_value(&arg), "\"a\".repeat(33)");
+    assert_eq!(DefaultValueProvider.value_for(&arg), "\"a\".repeat(16)");
+}
+
+#[test]
+fn test_string_cases_fall_back_without_max_length() {
+    let arg = bounded_string_arg(None);
+    assert_eq!(too_long_value(&arg), "\"a\".repeat(1000)");
+    assert_eq!(DefaultValueProvider.value_for(&arg), "\"test_value\"");
+}
+
+#[test]
+fn test_defined_newtype_over_u64_gets_numeric_values() {
+    let mut idl = sample_idl();
+    idl.instructions[0].args[0].field_type = "Amount".to_string();
+    idl.types = vec![IdlTypeDef {
+        name: "Amount".to_string(),
+        kind: "struct".to_string(),
+        fields: vec!["0".to_string()],
+        field_types: vec!["u64".to_string()],
+    }];
+
+    let test_cases = TestCaseGenerator::new()
+        .generate_test_cases(&idl, &["deposit".to_string()])
+        .unwrap();
+    let amount = &test_cases[0].arguments[0];
+    assert!(matches!(amount.arg_type, ArgumentType::U64), "Unexpected type: {:?}", amount.arg_type);
+
+    let positive = &test_cases[0].positive_cases[0].argument_values[0];
+    assert!(matches!(&positive.value_type, TestValueType::Valid { description } if description == "1000"));
+    assert!(test_cases[0].negative_cases.iter().any(|case| case.description == "deposit - amount overflow"));
+}
+
+#[test]
+fn test_newtype_arguments_are_resolved_in_metadata_analyzed_without_field_types() {
+    let mut idl = sample_idl();
+    idl.instructions[0].args[0].field_type = "Amount".to_string();
+    let amount_type = IdlTypeDef {
+        name: "Amount".to_string(),
+        kind: "struct".to_string(),
+        fields: vec!["0".to_string()],
+        field_types: vec!["u64".to_string()],
+    };
+    // As stored on-chain: the type is known, its field types are not.
+    idl.types = vec![IdlTypeDef { field_types: vec![], ..amount_type.clone() }];
+    let generator = TestCaseGenerator::new();
+    let mut test_cases = generator.generate_test_cases(&idl, &["deposit".to_string()]).unwrap();
+    assert!(matches!(test_cases[0].arguments[0].arg_type, ArgumentType::Struct { .. }));
+
+    idl.types = vec![amount_type];
+    generator.resolve_newtype_arguments(&mut test_cases, &idl).unwrap();
+    assert!(matches!(test_cases[0].arguments[0].arg_type, ArgumentType::U64));
+    let positive = &test_cases[0].positive_cases[0].argument_values[0];
+    assert!(matches!(&positive.value_type, TestValueType::Valid { description } if description == "1000"));
+}
+
+#[test]
+fn test_defined_struct_stays_a_struct() {
+    let mut idl = sample_idl();
+    idl.instructions[0].args[0].field_type = "Config".to_string();
+    idl.types = vec![IdlTypeDef {
+        name: "Config".to_string(),
+        kind: "struct".to_string(),
+        fields: vec!["fee".to_string(), "admin".to_string()],
+        field_types: vec!["u64".to_string(), "pubkey".to_string()],
+    }];
+
+    let test_cases = TestCaseGenerator::new()
+        .generate_test_cases(&idl, &["deposit".to_string()])
+        .unwrap();
+    assert!(matches!(&test_cases[0].arguments[0].arg_type, ArgumentType::Struct { name } if name == "Config"));
+}
+
+#[test]
+fn test_vec_of_structs_gets_an_array_of_object_literals() {
+    let mut idl = sample_idl();
+    idl.instructions[0].args[0] = IdlField { name: "items".to_string(), field_type: "Vec<Item>".to_string() };
+    idl.types = vec![IdlTypeDef {
+        name: "Item".to_string(),
+        kind: "struct".to_string(),
+        fields: vec!["price".to_string(), "name".to_string()],
+        field_types: vec!["u64".to_string(), "string".to_string()],
+    }];
+
+    let test_cases = TestCaseGenerator::new()
+        .generate_test_cases(&idl, &["deposit".to_string()])
+        .unwrap();
+    let items = &test_cases[0].arguments[0];
+    assert!(matches!(
+        &items.arg_type,
+        ArgumentType::Vec { inner_type, .. } if matches!(&**inner_type, ArgumentType::Struct { name } if name == "Item")
+    ));
+
+    let value_of = |case: &TestCase| match &case.argument_values[0].value_type {
+        TestValueType::Valid { description } | TestValueType::Invalid { description, .. } => description.clone(),
+    };
+    let positive = &test_cases[0].positive_cases;
+    assert_eq!(value_of(&positive[0]), "[{ price: new anchor.BN(\"1000\"), name: \"test_value\" }]");
+    let empty = positive.iter().find(|case| case.description == "items - empty array").unwrap();
+    assert_eq!(value_of(empty), "[]");
+
+    let too_long = test_cases[0].negative_cases
+        .iter()
+        .find(|case| case.description == "deposit - items too long")
+        .unwrap();
+    assert_eq!(
+        value_of(too_long),
+        "Array.from({ length: 1000 }, () => ({ price: new anchor.BN(\"1000\"), name: \"test_value\" }))"
+    );
+}
+
+/// Every argument value of the fuzz cases generated for `sample_idl` plus a `u8`.
+fn fuzz_values(seed: u64) -> Vec<Vec<String>> {
+    let mut idl = sample_idl();
+    idl.instructions[0].args.push(IdlField { name: "level".to_string(), field_type: "u8".to_string() });
+    let test_cases = TestCaseGenerator::new()
+        .with_fuzz(FuzzConfig { cases: 5, seed })
+        .generate_test_cases(&idl, &["deposit".to_string()])
+        .unwrap();
+    test_cases[0].positive_cases
+        .iter()
+        .filter(|case| case.description.contains("fuzz case"))
+        .map(|case| {
+            case.argument_values
+                .iter()
+                .map(|value| match &value.value_type {
+                    TestValueType::Valid { description } => description.clone(),
+                    other => panic!("Unexpected value: {:?}", other),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn test_fuzz_cases_are_reproducible_from_the_seed() {
+    let values = fuzz_values(7);
+    assert_eq!(values.len(), 5);
+    assert_eq!(values, fuzz_values(7));
+    assert_ne!(values, fuzz_values(8));
+
+    for case in &values {
+        let amount: u64 = case[0].parse().unwrap();
+        assert_ne!(amount, 0, "amount is NonZero");
+        let memo = case[1].trim_matches('"');
+        assert!((1..=100).contains(&memo.len()), "memo length {}", memo.len());
+        case[2].parse::<u8>().unwrap();
+    }
+}
+
+#[test]
+fn test_fuzz_strings_without_a_declared_minimum_are_not_empty() {
+    let label = ArgumentInfo {
+        name: "label".to_string(),
+        arg_type: ArgumentType::String { max_length: None },
+        constraints: vec![],
+        is_optional: false,
+    };
+    let mut rng = FuzzConfig { cases: 1, seed: 3 }.rng("deposit");
+    for _ in 0..50 {
+        assert_ne!(fuzz_value(&label, &mut rng, &DefaultValueProvider), "\"\"");
+    }
+
+    let capped = ArgumentInfo { constraints: vec![ArgumentConstraint::MaxLength { value: 0 }], ..label };
+    assert_eq!(fuzz_value(&capped, &mut rng, &DefaultValueProvider), "\"\"");
+}
+
+/// The `delegate` argument's value in the basic positive case and in its omitted case.
+fn optional_argument_values(field_type: &str) -> (String, String) {
+    let mut idl = sample_idl();
+    idl.instructions[0].args[1] = IdlField { name: "delegate".to_string(), field_type: field_type.to_string() };
+    let test_cases = TestCaseGenerator::new()
+        .generate_test_cases(&idl, &["deposit".to_string()])
+        .unwrap();
+    assert!(test_cases[0].arguments[1].is_optional);
+
+    let delegate_value = |case: &TestCase| match &case.argument_values[1].value_type {
+        TestValueType::Valid { description } => description.clone(),
+        other => panic!("Unexpected value: {:?}", other),
+    };
+    let positive = &test_cases[0].positive_cases;
+    let omitted = positive
+        .iter()
+        .find(|case| case.description == "delegate - omitted")
+        .expect("omitted case");
+    assert_eq!(omitted.argument_values.len(), 2);
+    (delegate_value(&positive[0]), delegate_value(omitted))
+}
+
+#[test]
+fn test_optional_pubkey_argument_is_present_or_null() {
+    let (present, omitted) = optional_argument_values("Option<pubkey>");
+    assert_eq!(present, "delegateKeypair.publicKey");
+    assert_eq!(omitted, "null");
+}
+
+#[test]
+fn test_optional_bool_argument_is_present_or_null() {
+    let (present, omitted) = optional_argument_values("Option<bool>");
+    assert_eq!(present, "true");
+    assert_eq!(omitted, "null");
+}
+
+#[test]
+fn test_registry_records_each_instruction_once() {
+    let vault = AccountInfo {
+        name: "vault".to_string(),
+        is_pda: false,
+        is_signer: false,
+        is_mut: true,
+        initialized_by: None,
+        seeds: vec![],
+        program: None,
+        used_in: vec!["deposit".to_string()],
+        constraints: vec![],
+    };
+    let mut registry = AccountRegistry::new();
+    registry.add_or_update_account(vault.clone());
+    registry.add_or_update_account(vault);
+
+    assert_eq!(registry.accounts.len(), 1);
+    assert_eq!(registry.get_account("vault").unwrap().used_in, ["deposit"]);
+}
+
+fn open_account_idl() -> IdlData {
+    let mut idl = sample_idl();
+    idl.instructions[0].name = "open_account".to_string();
+    idl.instructions[0].accounts = vec![pda_account("account", "")];
+    idl
+}
+
+#[test]
+fn test_open_instruction_initializes_its_accounts() {
+    let registry = DependencyAnalyzerImpl::default()
+        .build_account_registry(&open_account_idl(), PROGRAM_ID)
+        .unwrap();
+    let account = registry.get_account("account").unwrap();
+    assert_eq!(account.initialized_by.as_deref(), Some("open_account"));
+}
+
+#[test]
+fn test_init_replaces_mut_on_initialized_accounts() {
+    let idl = open_account_idl();
+    let order = vec!["open_account".to_string()];
+    let analyzer = DependencyAnalyzerImpl::default();
+    let registry = analyzer.build_account_registry(&idl, PROGRAM_ID).unwrap();
+
+    let account = registry.get_account("account").unwrap();
+    let types: Vec<&ConstraintType> = account.constraints.iter().map(|c| &c.constraint_type).collect();
+    assert_eq!(types, [&ConstraintType::Seeds, &ConstraintType::Init]);
+    assert!(account.is_mut);
+
+    let metadata = DependencyAnalyzer::new().analyze(&idl, &order, PROGRAM_ID).unwrap();
+    let dependencies: Vec<_> = metadata.account_dependencies.iter().filter(|dep| dep.account_name == "account").collect();
+    assert_eq!(dependencies.len(), 1);
+    assert!(dependencies[0].must_be_initialized);
+}
+
+#[test]
+fn test_init_keywords_are_configurable() {
+    let analyzer = DependencyAnalyzerImpl { init_keywords: vec!["mint".to_string()] };
+    let registry = analyzer.build_account_registry(&open_account_idl(), PROGRAM_ID).unwrap();
+    assert_eq!(registry.get_account("account").unwrap().initialized_by, None);
+    assert!(is_initializing_instruction("mint_tokens", &analyzer.init_keywords));
+    assert!(!is_initializing_instruction("deregister", &["register".to_string()]));
+}
+
+#[test]
+fn test_init_keywords_match_camel_case_words() {
+    let keywords: Vec<String> = DEFAULT_INIT_KEYWORDS.iter().map(|k| k.to_string()).collect();
+    for name in ["userInit", "initializeVault", "reinitialize", "createPool"] {
+        assert!(is_initializing_instruction(name, &keywords), "{name}");
+    }
+    for name in ["deregister", "withdrawFunds", "mintTokens"] {
+        assert!(!is_initializing_instruction(name, &keywords), "{name}");
+    }
+}
+
+#[test]
+fn test_documented_init_constraint_initializes_the_account() {
+    let mut idl = open_account_idl();
+    idl.instructions[0].name = "deposit".to_string();
+    idl.instructions[0].accounts[0].is_mut = false;
+    idl.instructions[0].accounts[0].docs = vec!["#[account(init, payer = user, space = 8 + 32)]".to_string()];
+
+    let registry = DependencyAnalyzerImpl::default().build_account_registry(&idl, PROGRAM_ID).unwrap();
+    let account = registry.get_account("account").unwrap();
+    assert_eq!(account.initialized_by.as_deref(), Some("deposit"));
+    assert!(account.constraints.iter().any(|c| c.constraint_type == ConstraintType::Init));
+}
+
+fn vault_lifecycle_idl(second_instruction: &str) -> IdlData {
+    let mut idl = open_account_idl();
+    let mut destination = pda_account("destination", "");
+    destination.pda = None;
+    idl.instructions.push(IdlInstruction {
+        name: second_instruction.to_string(),
+        accounts: vec![pda_account("account", ""), destination],
+        args: vec![],
+        docs: vec![],
+        returns: None,
+    });
+    idl
+}
+
+#[test]
+fn test_close_instruction_marks_the_accounts_it_closes() {
+    let registry = DependencyAnalyzerImpl::default()
+        .build_account_registry(&vault_lifecycle_idl("close_account"), PROGRAM_ID)
+        .unwrap();
+    let account = registry.get_account("account").unwrap();
+    assert_eq!(account.initialized_by.as_deref(), Some("open_account"));
+    assert_eq!(account.instructions_with(ConstraintType::Close), ["close_account"]);
+    assert!(account.instructions_with(ConstraintType::Realloc).is_empty());
+
+    let destination = registry.get_account("destination").unwrap();
+    assert!(destination.instructions_with(ConstraintType::Close).is_empty());
+}
+
+#[test]
+fn test_resize_instruction_marks_the_accounts_it_reallocates() {
+    let idl = vault_lifecycle_idl("resize_account");
+    let order = vec!["open_account".to_string(), "resize_account".to_string()];
+    let metadata = DependencyAnalyzer::new().analyze(&idl, &order, PROGRAM_ID).unwrap();
+    let account = metadata.account_dependencies.iter().find(|dep| dep.account_name == "account").unwrap();
+    assert_eq!(account.reallocated_by, ["resize_account"]);
+    assert!(account.closed_by.is_empty());
+}
+
+#[test]
+fn test_suggests_an_order_only_when_dependencies_are_out_of_order() {
+    let mut idl = sample_idl();
+    idl.instructions[0].accounts = vec![pda_account("vault", "")];
+    let mut initialize = idl.instructions[0].clone();
+    initialize.name = "initialize".to_string();
+    idl.instructions.push(initialize);
+
+    let analyzer = DependencyAnalyzer::new();
+    let order = |names: [&str; 2]| names.map(String::from).to_vec();
+
+    let suggestion = analyzer
+        .suggest_execution_order(&idl, &order(["deposit", "initialize"]), PROGRAM_ID)
+        .unwrap();
+    assert_eq!(suggestion, Some(order(["initialize", "deposit"])));
+
+    let suggestion = analyzer
+        .suggest_execution_order(&idl, &order(["initialize", "deposit"]), PROGRAM_ID)
+        .unwrap();
+    assert_eq!(suggestion, None);
+}
+
+#[test]
+fn test_custom_seed_naming_a_registered_account_creates_an_edge() {
+    let seed = |kind: &str, path: &str, value: &str| IdlSeed {
+        kind: kind.to_string(),
+        path: path.to_string(),
+        value: value.to_string(),
+    };
+    let mut idl = sample_idl();
+    let mut pool = pda_account("pool", "");
+    pool.pda.as_mut().unwrap().seeds = vec![seed("const", "", "pool")];
+    let mut position = pda_account("position", "");
+    // The constant spells an account name too, but only the account seed is a dependency.
+    position.pda.as_mut().unwrap().seeds = vec![seed("const", "", "pool"), seed("account", "pool", "")];
+    idl.instructions[0].name = "initialize_pool".to_string();
+    idl.instructions[0].accounts = vec![pool];
+    let mut create_position = idl.instructions[0].clone();
+    create_position.name = "create_position".to_string();
+    create_position.accounts = vec![position];
+    idl.instructions.push(create_position);
+
+    let analyzer = DependencyAnalyzerImpl::default();
+    let registry = analyzer.build_account_registry(&idl, PROGRAM_ID).unwrap();
+    let order = ["initialize_pool".to_string(), "create_position".to_string()];
+    let graph = analyzer.build_dependency_graph(&idl, &order, &registry).unwrap();
+
+    let seed_edges: Vec<_> = graph.edges
+        .iter()
+        .filter(|edge| matches!(edge.dependency_type, DependencyType::SeedDependency))
+        .collect();
+    assert_eq!(seed_edges.len(), 1, "{:?}", graph.edges);
+    assert_eq!(seed_edges[0].from, "initialize_pool");
+    assert_eq!(seed_edges[0].to, "create_position");
+    assert_eq!(seed_edges[0].account, "position");
+}
+
+#[test]
+fn test_pda_seeded_by_another_pda_initializes_after_it() {
+    let mut idl = sample_idl();
+    // The parent's name marks its seed as an authority, yet it is a PDA.
+    let mut pool_authority = pda_account("pool_authority", "");
+    pool_authority.pda.as_mut().unwrap().seeds.truncate(1);
+    let mut position = pda_account("position", "");
+    position.pda.as_mut().unwrap().seeds[1].path = "pool_authority".to_string();
+    idl.instructions[0].name = "create_position".to_string();
+    idl.instructions[0].accounts = vec![position];
+    let mut initialize_pool = idl.instructions[0].clone();
+    initialize_pool.name = "initialize_pool".to_string();
+    initialize_pool.accounts = vec![pool_authority];
+    idl.instructions.push(initialize_pool);
+
+    let analyzer = DependencyAnalyzerImpl::default();
+    let registry = analyzer.build_account_registry(&idl, PROGRAM_ID).unwrap();
+    let order = ["create_position".to_string(), "initialize_pool".to_string()];
+    let graph = analyzer.build_ordering_graph(&idl, &order, &registry).unwrap();
+
+    assert_eq!(graph.edges.len(), 1, "{:?}", graph.edges);
+    assert!(matches!(graph.edges[0].dependency_type, DependencyType::SeedDependency));
+    assert_eq!(graph.edges[0].from, "initialize_pool");
+    assert_eq!(graph.edges[0].to, "create_position");
+    assert_eq!(analyzer.topological_sort(&graph).unwrap(), ["initialize_pool", "create_position"]);
+
+    let pdas = PdaDetector.detect_pdas(&registry, PROGRAM_ID.to_string()).unwrap();
+    let names: Vec<&str> = pdas.iter().map(|pda| pda.account_name.as_str()).collect();
+    assert_eq!(names, ["pool_authority", "position"]);
+}
+
+#[test]
+fn test_edge_to_an_instruction_outside_the_graph_names_its_account() {
+    let graph = DependencyGraph {
+        nodes: vec![InstructionNode { name: "initialize".to_string(), initializes: vec![], requires: vec![] }],
+        edges: vec![DependencyEdge {
+            from: "initialize".to_string(),
+            to: "deposit".to_string(),
+            dependency_type: DependencyType::Initialization,
+            account: "vault".to_string(),
+        }],
+    };
+    let err = DependencyAnalyzerImpl::default().topological_sort(&graph).unwrap_err();
+    assert!(matches!(err, SolifyError::DependencyAnalysisFailed(_)), "{:?}", err);
+    assert!(err.to_string().contains("account vault leads to deposit"), "{}", err);
+}
+
+/// `alpha` is seeded by `beta` and `beta` by `alpha`.
+fn circular_seed_idl() -> IdlData {
+    let mut alpha = pda_account("alpha", "");
+    alpha.pda.as_mut().unwrap().seeds[1].path = "beta".to_string();
+    let mut beta = pda_account("beta", "");
+    beta.pda.as_mut().unwrap().seeds[1].path = "alpha".to_string();
+    let mut idl = sample_idl();
+    idl.instructions[0].name = "initialize".to_string();
+    idl.instructions[0].accounts = vec![alpha, beta];
+    idl
+}
+
+#[test]
+fn test_analyze_returns_an_error_for_circular_account_seeds() {
+    let result = DependencyAnalyzer::new().analyze(&circular_seed_idl(), &["initialize".to_string()], PROGRAM_ID);
+    assert!(matches!(result, Err(SolifyError::CircularDependency)), "{:?}", result.map(|_| ()));
+}
+
+#[test]
+fn test_analyze_dependencies_surfaces_circular_seeds_as_an_error() {
+    let err = DependencyAnalyzer::new()
+        .analyze_dependencies(&circular_seed_idl(), &["initialize".to_string()], PROGRAM_ID.to_string())
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Circular dependency detected");
+}
+
+#[test]
+fn test_overlong_static_seed_is_reported() {
+    let seed = |value: &str| SeedComponent {
+        seed_type: solify_common::types::SeedType::Static,
+        value: value.to_string(),
+    };
+    let mut pda_init = PdaInit {
+        account_name: "escrow".to_string(),
+        seeds: vec![seed("escrow"), seed(&"s".repeat(40))],
+        program_id: PROGRAM_ID.to_string(),
+        space: None,
+    };
+
+    let warnings = PdaDetector.seed_limit_warnings(&pda_init);
+    assert_eq!(warnings.len(), 1, "{:?}", warnings);
+    assert!(warnings[0].contains("'escrow'") && warnings[0].contains("40-byte"), "{}", warnings[0]);
+
+    // A 32-byte constant arrives as a base58 public key and fits.
+    pda_init.seeds = vec![seed(PROGRAM_ID); 17];
+    let warnings = PdaDetector.seed_limit_warnings(&pda_init);
+    assert_eq!(warnings, vec!["PDA 'escrow' has 17 seeds, but at most 16 are allowed".to_string()]);
+}
+
+#[test]
+fn test_seed_order_follows_the_idl() {
+    let mut idl = sample_idl();
+    idl.instructions[0].accounts = vec![pda_account("vault", "")];
+    let metadata = DependencyAnalyzer::new()
+        .analyze_dependencies(&idl, &["deposit".to_string()], PROGRAM_ID.to_string())
+        .unwrap();
+    let vault = metadata.pda_init_sequence[0].clone();
+    let detector = PdaDetector;
+    assert!(detector.validate_seed_order(&vault, &idl).is_ok());
+
+    let mut reversed = vault.clone();
+    reversed.seeds.reverse();
+    assert!(detector.validate_seed_order(&reversed, &idl).is_err());
+
+    // Derivation is order-sensitive, so the reversed seeds name a different account.
+    let program_id: Pubkey = PROGRAM_ID.parse().unwrap();
+    let authority = Pubkey::new_unique();
+    let (declared, _) = Pubkey::find_program_address(&[b"vault", authority.as_ref()], &program_id);
+    let (swapped, _) = Pubkey::find_program_address(&[authority.as_ref(), b"vault"], &program_id);
+    assert_ne!(declared, swapped);
+}
+
+#[test]
+fn test_boundary_cases_skip_values_outside_i64() {
+    let arg = ArgumentInfo {
+        name: "offset".to_string(),
+        arg_type: ArgumentType::I64,
+        constraints: vec![
+            ArgumentConstraint::Min { value: i64::MIN },
+            ArgumentConstraint::Max { value: i64::MAX },
+            ArgumentConstraint::NonZero,
+        ],
+        is_optional: false,
+    };
+    let cases = TestCaseGenerator::new()
+        .generate_argument_negative_cases("adjust", &arg)
+        .unwrap();
+    let descriptions: Vec<&str> = cases.iter().map(|case| case.description.as_str()).collect();
+
+    assert!(!descriptions.iter().any(|d| d.ends_with("below minimum")));
+    assert!(!descriptions.iter().any(|d| d.ends_with("above maximum")));
+    assert!(descriptions.contains(&"adjust - offset is zero"));
+}
+
+#[test]
+fn test_analyze_reports_each_instruction_in_order() {
+    let mut idl = sample_idl();
+    let mut withdraw = idl.instructions[0].clone();
+    withdraw.name = "withdraw".to_string();
+    idl.instructions.push(withdraw);
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+
+    DependencyAnalyzer::new()
+        .with_progress(move |progress| recorded.lock().unwrap().push(progress.to_string()))
+        .analyze(&idl, &["withdraw".to_string(), "deposit".to_string()], PROGRAM_ID)
+        .unwrap();
+
+    assert_eq!(*events.lock().unwrap(), ["Analyzing 1/2: withdraw", "Analyzing 2/2: deposit"]);
+}
+
+/// Records every `(span, field, value)` set on a span, at creation or later.
+#[derive(Clone, Default)]
+struct SpanFields(Arc<Mutex<Vec<(String, String, String)>>>);
+
+struct FieldVisitor<'a> {
+    span: &'a str,
+    fields: &'a SpanFields,
+}
+
+impl Visit for FieldVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.fields.0.lock().unwrap().push((
+            self.span.to_string(),
+            field.name().to_string(),
+            format!("{:?}", value),
+        ));
+    }
+}
+
+impl<S> Layer<S> for SpanFields
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+        attrs.record(&mut FieldVisitor { span: attrs.metadata().name(), fields: self });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            values.record(&mut FieldVisitor { span: span.name(), fields: self });
+        }
+    }
+}
+
+#[test]
+fn test_analyze_span_records_account_count() {
+    let mut idl = sample_idl();
+    idl.instructions[0].accounts = vec![pda_account("vault", ""), pda_account("escrow", "")];
+    let fields = SpanFields::default();
+    let subscriber = tracing_subscriber::registry().with(fields.clone());
+
+    tracing::subscriber::with_default(subscriber, || {
+        DependencyAnalyzer::new()
+            .analyze_dependencies(&idl, &["deposit".to_string()], PROGRAM_ID.to_string())
+            .unwrap();
+    });
+
+    let recorded = fields.0.lock().unwrap();
+    let analyze_field = |name: &str| {
+        recorded
+            .iter()
+            .find(|(span, field, _)| span == "analyze" && field == name)
+            .map(|(_, _, value)| value.clone())
+    };
+    assert_eq!(analyze_field("instructions").as_deref(), Some("1"));
+    assert_eq!(analyze_field("accounts").as_deref(), Some("2"));
+    assert_eq!(analyze_field("pdas").as_deref(), Some("2"));
+}
@@ -0,0 +1,91 @@
+use anyhow::{ Context, Result };
+use solify_common::{ ExpectedOutcome, TestCase, TestMetadata };
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+const HEADERS: [&str; 5] = ["Instruction", "Case type", "Description", "Expected outcome", "Error code"];
+
+/// Table format written by `--catalog`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatalogFormat {
+    Markdown,
+    Csv,
+}
+
+impl FromStr for CatalogFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "md" | "markdown" => Ok(Self::Markdown),
+            "csv" => Ok(Self::Csv),
+            other => anyhow::bail!("Unknown catalog format '{}', expected md or csv", other),
+        }
+    }
+}
+
+/// Renders one row per generated test case, positive cases first for each instruction.
+pub fn render_catalog(metadata: &TestMetadata, format: CatalogFormat) -> String {
+    let rows: Vec<[String; 5]> = metadata.test_cases
+        .iter()
+        .flat_map(|tc| {
+            tc.positive_cases
+                .iter()
+                .chain(tc.negative_cases.iter())
+                .map(|case| catalog_row(&tc.instruction_name, case))
+        })
+        .collect();
+
+    match format {
+        CatalogFormat::Markdown => {
+            let mut lines = vec![
+                format!("| {} |", HEADERS.join(" | ")),
+                format!("|{}", "---|".repeat(HEADERS.len())),
+            ];
+            for row in rows {
+                let cells: Vec<String> = row.iter().map(|cell| cell.replace('|', "\\|")).collect();
+                lines.push(format!("| {} |", cells.join(" | ")));
+            }
+            lines.join("\n") + "\n"
+        }
+        CatalogFormat::Csv => {
+            let mut lines = vec![HEADERS.join(",")];
+            for row in rows {
+                let cells: Vec<String> = row.iter().map(|cell| csv_field(cell)).collect();
+                lines.push(cells.join(","));
+            }
+            lines.join("\n") + "\n"
+        }
+    }
+}
+
+/// Writes the catalog for `metadata` to `path`.
+pub fn write_catalog(metadata: &TestMetadata, format: CatalogFormat, path: &Path) -> Result<()> {
+    fs::write(path, render_catalog(metadata, format))
+        .with_context(|| format!("Failed to write test catalog to {:?}", path))
+}
+
+fn catalog_row(instruction: &str, case: &TestCase) -> [String; 5] {
+    let (outcome, error_code) = match &case.expected_outcome {
+        ExpectedOutcome::Success { .. } => ("Success".to_string(), String::new()),
+        ExpectedOutcome::Failure { error_code, error_message } => {
+            (format!("Failure: {}", error_message), error_code.clone().unwrap_or_default())
+        }
+    };
+    [
+        instruction.to_string(),
+        format!("{:?}", case.test_type),
+        case.description.clone(),
+        outcome,
+        error_code,
+    ]
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,98 @@
+use anyhow::Result;
+use console::style;
+use solana_client::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+use solify_client::{ load_signer, SOLIFY_ID };
+use std::path::{ Path, PathBuf };
+
+use crate::RpcEndpoint;
+use crate::utils::{ format_sol, validate_pubkey };
+
+/// Wallet checked when neither `--wallet` nor the config file names one.
+const DEFAULT_WALLET_PATH: &str = "~/.config/solana/id.json";
+
+/// The outcome of one `solify doctor` check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+impl Check {
+    fn pass(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self { name: name.into(), passed: true, detail: detail.into() }
+    }
+
+    fn fail(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self { name: name.into(), passed: false, detail: detail.into() }
+    }
+}
+
+/// Checks that `rpc` answers, that `wallet` holds a funded keypair and that the
+/// Solify program, and `program` when given, are deployed on the cluster.
+pub fn run_checks(rpc: &RpcClient, wallet: &Path, program: Option<&str>) -> Vec<Check> {
+    let mut checks = vec![match rpc.get_health() {
+        Ok(()) => Check::pass("RPC", format!("{} is healthy", rpc.url())),
+        Err(e) => Check::fail("RPC", format!("{} is unreachable or unhealthy: {}", rpc.url(), e)),
+    }];
+
+    match load_signer(wallet) {
+        Ok(keypair) => {
+            checks.push(Check::pass("Wallet", format!("{} ({})", wallet.display(), keypair.pubkey())));
+            checks.push(match rpc.get_balance(&keypair.pubkey()) {
+                Ok(0) => Check::fail("Balance", format!("{}; fund the wallet, e.g. with `solana airdrop 2`", format_sol(0))),
+                Ok(lamports) => Check::pass("Balance", format_sol(lamports)),
+                Err(e) => Check::fail("Balance", format!("Could not fetch the balance: {}", e)),
+            });
+        }
+        Err(e) => {
+            checks.push(Check::fail("Wallet", format!("{:#}", e)));
+            checks.push(Check::fail("Balance", "Needs a valid wallet"));
+        }
+    }
+
+    checks.push(deployment_check("Solify program", &SOLIFY_ID, rpc));
+    if let Some(program) = program {
+        checks.push(match validate_pubkey(program) {
+            Ok(program_id) => deployment_check("Program", &program_id, rpc),
+            Err(e) => Check::fail("Program", format!("{}: {}", program, e)),
+        });
+    }
+    checks
+}
+
+fn deployment_check(name: &str, program_id: &Pubkey, rpc: &RpcClient) -> Check {
+    match rpc.get_account(program_id) {
+        Ok(account) if account.executable => Check::pass(name, format!("{} is deployed", program_id)),
+        Ok(_) => Check::fail(name, format!("{} is not an executable program", program_id)),
+        Err(e) => Check::fail(name, format!("{} is not deployed on this cluster: {}", program_id, e)),
+    }
+}
+
+/// The checklist line for `check`, without colors.
+pub fn check_line(check: &Check) -> String {
+    format!("{} {}: {}", if check.passed { "✓" } else { "✗" }, check.name, check.detail)
+}
+
+pub fn execute(wallet: Option<PathBuf>, program: Option<String>, rpc: &RpcEndpoint) -> Result<()> {
+    let wallet = wallet.unwrap_or_else(|| PathBuf::from(shellexpand::tilde(DEFAULT_WALLET_PATH).to_string()));
+    let client = rpc.client(CommitmentConfig::confirmed())?;
+    let checks = run_checks(&client, &wallet, program.as_deref());
+
+    for check in &checks {
+        let line = check_line(check);
+        if check.passed {
+            println!("{}", style(line).green());
+        } else {
+            println!("{}", style(line).red());
+        }
+    }
+    let failed = checks.iter().filter(|check| !check.passed).count();
+    if failed > 0 {
+        anyhow::bail!("{} of {} checks failed", failed, checks.len());
+    }
+    Ok(())
+}
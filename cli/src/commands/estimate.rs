@@ -0,0 +1,129 @@
+use anyhow::{ Context, Result };
+use solana_sdk::pubkey::Pubkey;
+use solify_analyzer::DependencyAnalyzer;
+use solify_client::{
+    serialized_test_cases_len,
+    serialized_test_metadata_len,
+    store_idl_instruction,
+    transaction_size,
+    MAX_TRANSACTION_SIZE,
+    TEST_METADATA_SPACE,
+};
+use solify_common::IdlData;
+use solify_parser::{ get_program_id, parse_idl };
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::commands::gen_test::resolve_idl_file;
+
+/// Size of one instruction's share of the test metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionEstimate {
+    pub name: String,
+    pub positive_cases: usize,
+    pub negative_cases: usize,
+    pub bytes: usize,
+}
+
+/// What on-chain processing of an IDL would upload and store, measured off-chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Estimate {
+    /// Signed size of the `store_idl_data` transaction.
+    pub idl_transaction_size: usize,
+    /// Borsh size of the metadata `generate_metadata` writes.
+    pub metadata_size: usize,
+    pub instructions: Vec<InstructionEstimate>,
+}
+
+impl Estimate {
+    pub fn idl_fits(&self) -> bool {
+        self.idl_transaction_size <= MAX_TRANSACTION_SIZE
+    }
+
+    pub fn metadata_fits(&self) -> bool {
+        self.metadata_size <= TEST_METADATA_SPACE
+    }
+
+    pub fn within_limits(&self) -> bool {
+        self.idl_fits() && self.metadata_fits()
+    }
+}
+
+/// Runs the off-chain analyzer over every instruction of `idl_data`, in IDL order,
+/// and sizes what on-chain processing would send and store.
+pub fn estimate(idl_data: &IdlData, program_id: &str) -> Result<Estimate> {
+    let program = Pubkey::from_str(program_id)
+        .with_context(|| format!("Invalid program ID: {}", program_id))?;
+    let order: Vec<String> = idl_data.instructions.iter().map(|i| i.name.clone()).collect();
+    let metadata = DependencyAnalyzer::new()
+        .analyze(idl_data, &order, program_id)
+        .map_err(|e| anyhow::anyhow!("Off-chain analysis failed: {}", e))?;
+
+    // The transaction size does not depend on which wallet signs it.
+    let instruction = store_idl_instruction(&Pubkey::default(), program, idl_data)?;
+    let instructions = metadata.test_cases
+        .iter()
+        .map(|tc| {
+            Ok(InstructionEstimate {
+                name: tc.instruction_name.clone(),
+                positive_cases: tc.positive_cases.len(),
+                negative_cases: tc.negative_cases.len(),
+                bytes: serialized_test_cases_len(tc)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Estimate {
+        idl_transaction_size: transaction_size(&[instruction], &Pubkey::default()),
+        metadata_size: serialized_test_metadata_len(&metadata)?,
+        instructions,
+    })
+}
+
+/// Builds the lines printed by `solify estimate`.
+pub fn estimate_lines(estimate: &Estimate) -> Vec<String> {
+    let verdict = |fits: bool| if fits { "ok" } else { "too large" };
+    let mut lines = vec![
+        format!(
+            "IDL upload transaction: {} / {} bytes ({})",
+            estimate.idl_transaction_size,
+            MAX_TRANSACTION_SIZE,
+            verdict(estimate.idl_fits())
+        ),
+        format!(
+            "Test metadata account: {} / {} bytes ({})",
+            estimate.metadata_size,
+            TEST_METADATA_SPACE,
+            verdict(estimate.metadata_fits())
+        ),
+        "Per instruction:".to_string(),
+    ];
+    for instruction in &estimate.instructions {
+        lines.push(format!(
+            "  {}: {} positive, {} negative, {} bytes",
+            instruction.name,
+            instruction.positive_cases,
+            instruction.negative_cases,
+            instruction.bytes
+        ));
+    }
+    lines.push(
+        if estimate.within_limits() {
+            "Result: within limits for on-chain processing".to_string()
+        } else {
+            "Result: too large for on-chain processing; use --off".to_string()
+        }
+    );
+    lines
+}
+
+pub fn execute(idl_path: PathBuf) -> Result<()> {
+    let idl_path = resolve_idl_file(idl_path)?;
+    let idl_data = parse_idl(&idl_path)
+        .with_context(|| format!("Failed to parse IDL file: {:?}", idl_path))?;
+    let program_id = get_program_id(&idl_path)?;
+    for line in estimate_lines(&estimate(&idl_data, &program_id)?) {
+        println!("{}", line);
+    }
+    Ok(())
+}
@@ -1,21 +1,33 @@
 use anyhow::{ Context, Result };
-use dialoguer::Input;
+#[cfg(feature = "tui")]
+use dialoguer::{ Confirm, Input };
+#[cfg(feature = "tui")]
 use dialoguer::theme::ColorfulTheme;
 use log::info;
+use serde::Serialize;
+#[cfg(feature = "tui")]
 use ratatui::layout::{ Constraint, Direction, Layout };
 use solana_sdk::pubkey::Pubkey;
-use solana_sdk::signature::Keypair;
-use solana_sdk::signer::Signer;
-use solify_client::SolifyClient;
+use solana_sdk::signer::{ keypair::Keypair, Signer };
+use solify_client::{ idl_hash, load_signer, ProgramTooLarge, SolifyClient };
 use solify_common::TestMetadata;
-use solify_parser::{ get_program_id, parse_idl };
+use solify_parser::{ anchor_idl_address, decode_idl_account_json, get_program_id, parse_idl };
+use std::collections::HashMap;
 use std::str::FromStr;
-use std::{ fs, path::PathBuf };
+use std::{ fs, path::{ Path, PathBuf } };
+use std::sync::Arc;
+#[cfg(feature = "tui")]
+use std::sync::Mutex;
 use std::time::Duration;
 use solana_commitment_config::CommitmentConfig;
-use solify_generator::generate_with_tera;
-use solify_analyzer::DependencyAnalyzer;
+use solify_generator::{ generate, generate_to, DeployedProgram, GenerateOptions, Generated, RemainingAccount };
+use solify_analyzer::{ DependencyAnalyzer, FuzzConfig, InstructionProgress, TestCaseGenerator };
+use tokio::sync::Semaphore;
 
+use crate::RpcEndpoint;
+use crate::commands::catalog::{ write_catalog, CatalogFormat };
+use crate::commands::summary::print_metadata_summary;
+#[cfg(feature = "tui")]
 use crate::tui::{
     AppEvent,
     EventHandler,
@@ -29,6 +41,7 @@ use crate::tui::{
     restore_terminal,
 };
 
+#[cfg(feature = "tui")]
 enum AppState {
     #[allow(dead_code)]
     SelectingInstructions,
@@ -37,7 +50,7 @@ enum AppState {
     Error(String),
 }
 
-fn resolve_idl_file(idl_path: PathBuf) -> Result<PathBuf> {
+pub(crate) fn resolve_idl_file(idl_path: PathBuf) -> Result<PathBuf> {
     if idl_path.is_dir() {
         let entries = fs::read_dir(&idl_path)
             .with_context(|| format!("Failed to read IDL directory: {:?}", idl_path))?;
@@ -56,24 +69,42 @@ fn resolve_idl_file(idl_path: PathBuf) -> Result<PathBuf> {
     }
 }
 
-pub async fn execute(idl_path: PathBuf, output: PathBuf, rpc_url: &str, off_chain: bool) -> Result<()> {
+pub async fn execute(
+    idl_path: PathBuf,
+    output: PathBuf,
+    rpc: &RpcEndpoint,
+    off_chain: Option<OffChainOptions>,
+    options: GenerateOptions,
+    report: ReportOptions,
+    on_chain: OnChainOptions
+) -> Result<()> {
     info!("Starting test generation process...");
 
+    if let (None, Some(paraphrase)) = (&off_chain, &on_chain.paraphrase) {
+        validate_paraphrase(paraphrase)?;
+    }
+
     let resolved_idl_path = resolve_idl_file(idl_path)?;
     info!("Using IDL file: {:?}", resolved_idl_path);
 
-    let idl_data = parse_idl(&resolved_idl_path).with_context(||
+    let mut idl_data = parse_idl(&resolved_idl_path).with_context(||
         format!("Failed to parse IDL file: {:?}", resolved_idl_path)
     )?;
+    if let Some(name) = &options.program_name {
+        idl_data.name = name.clone();
+    }
 
-    let program_id = get_program_id(&resolved_idl_path)?;
+    let program_id = match &options.deployed_program {
+        Some(deployed) => deployed.program_id.clone(),
+        None => get_program_id(&resolved_idl_path)?,
+    };
 
     let execution_order: Vec<String> = {
         let instruction_names: Vec<String> = idl_data.instructions
             .iter()
             .map(|i| i.name.clone())
             .collect();
-        select_instruction_order_interactive(&instruction_names)?
+        select_instruction_order(&instruction_names)?
     };
 
 
@@ -82,50 +113,487 @@ pub async fn execute(idl_path: PathBuf, output: PathBuf, rpc_url: &str, off_chai
             anyhow::bail!("Instruction '{}' not found in IDL", instr_name);
         }
     }
+    let execution_order = offer_suggested_order(&idl_data, execution_order, &program_id)?;
 
-    let wallet_path = {
-        let path: String = Input::with_theme(&ColorfulTheme::default())
-            .with_prompt("Enter path to your wallet keypair")
-            .default("~/.config/solana/id.json".to_string())
-            .interact_text()?;
-        PathBuf::from(shellexpand::tilde(&path).to_string())
-    };
+    let processing = resolve_processing(rpc, off_chain, on_chain)?;
+    let anchor_test_dir = detect_anchor_test_directory(&resolved_idl_path)?;
 
-    let paraphrase = {
-        let paraphrase: String = Input::with_theme(&ColorfulTheme::default())
-            .with_prompt("Enter paraphrase for test metadata")
-            .default("updated".to_string())
-            .interact_text()?;
-        paraphrase
+    // The TUI draws on stdout, so it is skipped when the tests are printed there.
+    #[cfg(feature = "tui")]
+    if !options.stdout {
+        return run_interactive_test_generation(
+            &idl_data,
+            &execution_order,
+            &program_id,
+            &output,
+            &anchor_test_dir,
+            processing,
+            &options,
+            &report
+        ).await;
+    }
+
+    let metadata = process_metadata(&idl_data, &execution_order, &program_id, &processing).await?;
+    let final_output = anchor_test_dir.unwrap_or(output);
+    let generated = generate_to(&metadata, &idl_data, &final_output, &options, &mut std::io::stdout().lock())
+        .context("Failed to generate tests")?;
+    if let Some(skipped) = skipped_summary(&generated.skipped) {
+        eprintln!("{}", skipped);
+    }
+    if !options.stdout {
+        println!("Test file: {}", generated.path.display());
+        write_reports(&report, &metadata, Some(&generated))?;
+    }
+    Ok(())
+}
+
+/// Generates tests for `idl_path` from `--metadata`, test metadata computed earlier
+/// and saved as JSON. Analysis and on-chain processing are skipped entirely: the
+/// analyzer never runs and no RPC request is made.
+pub fn execute_from_metadata(
+    idl_path: PathBuf,
+    metadata_path: &Path,
+    output: PathBuf,
+    options: GenerateOptions,
+    report: ReportOptions
+) -> Result<PathBuf> {
+    let resolved_idl_path = resolve_idl_file(idl_path)?;
+    let mut idl_data = parse_idl(&resolved_idl_path).with_context(||
+        format!("Failed to parse IDL file: {:?}", resolved_idl_path)
+    )?;
+    if let Some(name) = &options.program_name {
+        idl_data.name = name.clone();
+    }
+
+    let metadata = load_metadata(metadata_path)?;
+    for instr_name in &metadata.instruction_order {
+        if !idl_data.instructions.iter().any(|i| &i.name == instr_name) {
+            anyhow::bail!("Instruction '{}' in {:?} not found in IDL", instr_name, metadata_path);
+        }
+    }
+
+    let final_output = detect_anchor_test_directory(&resolved_idl_path)?.unwrap_or(output);
+    let generated = generate_to(&metadata, &idl_data, &final_output, &options, &mut std::io::stdout().lock())
+        .with_context(|| format!("Failed to generate test files in: {:?}", final_output))?;
+    if options.stdout {
+        if let Some(skipped) = skipped_summary(&generated.skipped) {
+            eprintln!("{}", skipped);
+        }
+    } else {
+        println!("✓ Test files generated: {}", generated.path.display());
+        write_reports(&report, &metadata, Some(&generated))?;
+    }
+    Ok(generated.path)
+}
+
+/// Reads test metadata serialized as JSON, e.g. by an earlier run or another tool.
+pub fn load_metadata(path: &Path) -> Result<TestMetadata> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read metadata file: {:?}", path))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse test metadata in {:?}", path))
+}
+
+/// Reads the `--accounts` file: a JSON object mapping `instruction.account` to the
+/// TypeScript expression passed for that account, e.g.
+/// `{ "transfer.recipient": "recipientKeypair.publicKey" }`.
+pub fn load_account_overrides(path: &Path) -> Result<HashMap<String, HashMap<String, String>>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read account overrides: {:?}", path))?;
+    let entries: HashMap<String, String> = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse account overrides: {:?}", path))?;
+
+    let mut overrides: HashMap<String, HashMap<String, String>> = HashMap::new();
+    for (key, expression) in entries {
+        let Some((instruction, account)) = key.split_once('.') else {
+            anyhow::bail!("Account override '{}' must be written as instruction.account", key);
+        };
+        overrides
+            .entry(instruction.to_string())
+            .or_default()
+            .insert(account.to_string(), expression);
+    }
+    Ok(overrides)
+}
+
+/// Reads the `--remaining-accounts` file: each instruction name maps to the
+/// accounts chained onto its call with `.remainingAccounts([...])`, e.g.
+/// `{ "settle": [{ "pubkey": "vault", "is_writable": true, "is_signer": false }] }`.
+pub fn load_remaining_accounts(path: &Path) -> Result<HashMap<String, Vec<RemainingAccount>>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read remaining accounts: {:?}", path))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse remaining accounts: {:?}", path))
+}
+
+/// Reads the IDL for `--program-id`, which the generated tests embed to build
+/// their client for the deployed program instead of using `anchor.workspace`.
+pub fn deployed_program(idl_path: PathBuf, program_id: &str) -> Result<DeployedProgram> {
+    Pubkey::from_str(program_id)
+        .with_context(|| format!("Invalid program id: {}", program_id))?;
+    let idl_path = resolve_idl_file(idl_path)?;
+    let contents = fs::read_to_string(&idl_path)
+        .with_context(|| format!("Failed to read IDL file: {:?}", idl_path))?;
+    let idl: serde_json::Value = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse IDL file: {:?}", idl_path))?;
+    Ok(DeployedProgram {
+        program_id: program_id.to_string(),
+        idl_json: serde_json::to_string_pretty(&idl)?,
+    })
+}
+
+/// Summary of a `--all` run over every IDL in a directory.
+#[derive(Debug, Default)]
+pub struct BatchSummary {
+    pub generated: Vec<GenerationReport>,
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+/// Settings of a `--all` run.
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    /// Path for the `--report-json` output.
+    pub report_json: Option<PathBuf>,
+    /// How many programs are processed at once.
+    pub concurrency: usize,
+}
+
+/// Where test metadata is computed for each program in a batch run.
+#[derive(Clone)]
+pub enum Processing {
+    OffChain(OffChainOptions),
+    OnChain {
+        rpc: RpcEndpoint,
+        wallet_path: PathBuf,
+        paraphrase: String,
+        additional_signers: Vec<PathBuf>,
+    },
+}
+
+/// Settings of `--off` processing, which runs the analyzer locally.
+#[derive(Debug, Clone, Default)]
+pub struct OffChainOptions {
+    /// Randomized positive cases to add per instruction (`--fuzz`, `--seed`).
+    pub fuzz: Option<FuzzConfig>,
+}
+
+/// Wallet and paraphrase given up front for on-chain processing; whichever is
+/// missing is prompted for.
+#[derive(Debug, Clone, Default)]
+pub struct OnChainOptions {
+    pub wallet: Option<PathBuf>,
+    pub paraphrase: Option<String>,
+    /// Keypair files that co-sign every transaction with the wallet.
+    pub additional_signers: Vec<PathBuf>,
+}
+
+/// Picks off-chain or on-chain processing. Only the on-chain path needs a wallet
+/// and a paraphrase, so off-chain runs never prompt for them.
+pub fn resolve_processing(
+    rpc: &RpcEndpoint,
+    off_chain: Option<OffChainOptions>,
+    on_chain: OnChainOptions
+) -> Result<Processing> {
+    if let Some(off_chain) = off_chain {
+        return Ok(Processing::OffChain(off_chain));
+    }
+    Ok(Processing::OnChain {
+        rpc: rpc.clone(),
+        wallet_path: match on_chain.wallet {
+            Some(wallet) => wallet,
+            None => prompt_wallet_path()?,
+        },
+        paraphrase: match on_chain.paraphrase {
+            Some(paraphrase) => paraphrase,
+            None => prompt_paraphrase()?,
+        },
+        additional_signers: on_chain.additional_signers,
+    })
+}
+
+pub async fn execute_all(
+    idl_dir: PathBuf,
+    output: PathBuf,
+    rpc: &RpcEndpoint,
+    off_chain: Option<OffChainOptions>,
+    options: GenerateOptions,
+    batch: BatchOptions,
+    on_chain: OnChainOptions
+) -> Result<()> {
+    info!("Starting batch test generation in {:?}", idl_dir);
+
+    if let (None, Some(paraphrase)) = (&off_chain, &on_chain.paraphrase) {
+        validate_paraphrase(paraphrase)?;
+    }
+
+    let processing = resolve_processing(rpc, off_chain, on_chain)?;
+
+    let summary = generate_batch(&idl_dir, &output, &processing, &options, batch.concurrency).await?;
+
+    println!("\n✅ Batch generation complete!");
+    for report in &summary.generated {
+        println!("   ✓ {}", report.output_file.display());
+    }
+    for (idl_path, err) in &summary.failed {
+        println!("   ✗ {}: {}", idl_path.display(), err);
+    }
+    println!(
+        "\nGenerated: {}, Failed: {}",
+        summary.generated.len(),
+        summary.failed.len()
+    );
+
+    if let Some(report_path) = batch.report_json {
+        let json = serde_json::to_string_pretty(&summary.generated)
+            .context("Failed to serialize report")?;
+        fs::write(&report_path, json)
+            .with_context(|| format!("Failed to write report: {:?}", report_path))?;
+        println!("Report: {}", report_path.display());
+    }
+
+    if summary.generated.is_empty() && !summary.failed.is_empty() {
+        anyhow::bail!("Test generation failed for every IDL in {:?}", idl_dir);
+    }
+
+    Ok(())
+}
+
+/// Runs the pipeline for every JSON IDL in `idl_dir` using each IDL's declared
+/// instruction order. A failing IDL is recorded and does not stop the others.
+/// Up to `concurrency` IDLs are processed at once.
+pub async fn generate_batch(
+    idl_dir: &Path,
+    output: &Path,
+    processing: &Processing,
+    options: &GenerateOptions,
+    concurrency: usize
+) -> Result<BatchSummary> {
+    let idl_files = collect_idl_files(idl_dir)?;
+    let output = Arc::new(output.to_path_buf());
+    let processing = Arc::new(processing.clone());
+    let options = Arc::new(options.clone());
+
+    let runtime = tokio::runtime::Handle::current();
+    let results = run_bounded(idl_files, concurrency, move |idl_path| {
+        let result = runtime.block_on(generate_for_idl(&idl_path, &output, &processing, &options));
+        (idl_path, result)
+    }).await?;
+
+    let mut summary = BatchSummary::default();
+    for (idl_path, result) in results {
+        match result {
+            Ok(report) => summary.generated.push(report),
+            Err(e) => summary.failed.push((idl_path, format!("{:#}", e))),
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Runs `task` for every item with at most `concurrency` running at once, so
+/// batch runs do not exhaust RPC rate limits. Results keep the order of `items`.
+/// `task` may block, e.g. on the RPC client, so each call gets a blocking thread.
+pub async fn run_bounded<I, F, O>(items: Vec<I>, concurrency: usize, task: F) -> Result<Vec<O>>
+where
+    I: Send + 'static,
+    F: Fn(I) -> O + Send + Sync + 'static,
+    O: Send + 'static,
+{
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+    let task = Arc::new(task);
+    let handles: Vec<_> = items
+        .into_iter()
+        .map(|item| {
+            let (semaphore, task) = (semaphore.clone(), task.clone());
+            tokio::spawn(async move {
+                let _permit = semaphore.acquire_owned().await?;
+                tokio::task::spawn_blocking(move || task(item)).await.context("Batch task failed")
+            })
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(handles.len());
+    for handle in handles {
+        results.push(handle.await.context("Batch task failed")??);
+    }
+    Ok(results)
+}
+
+async fn generate_for_idl(
+    idl_path: &Path,
+    output: &Path,
+    processing: &Processing,
+    options: &GenerateOptions
+) -> Result<GenerationReport> {
+    let idl_data = parse_idl(idl_path).with_context(||
+        format!("Failed to parse IDL file: {:?}", idl_path)
+    )?;
+    let program_id = get_program_id(idl_path)?;
+    let execution_order: Vec<String> = idl_data.instructions
+        .iter()
+        .map(|i| i.name.clone())
+        .collect();
+
+    let metadata = process_metadata(&idl_data, &execution_order, &program_id, processing).await?;
+
+    let final_output = detect_anchor_test_directory(idl_path)?.unwrap_or_else(|| output.to_path_buf());
+    let generated = generate(&metadata, &idl_data, &final_output, options).with_context(||
+        format!("Failed to generate test files in: {:?}", final_output)
+    )?;
+
+    Ok(GenerationReport::from_generation(&metadata, &generated))
+}
+
+/// Runs the analysis without the TUI or progress reporting.
+async fn process_metadata(
+    idl_data: &solify_common::IdlData,
+    execution_order: &[String],
+    program_id: &str,
+    processing: &Processing
+) -> Result<TestMetadata> {
+    match processing {
+        Processing::OffChain(off_chain) => {
+            process_offchain(idl_data, execution_order, program_id, off_chain, |_| {})
+        }
+        Processing::OnChain { rpc, wallet_path, paraphrase, additional_signers } => {
+            process_onchain(idl_data, execution_order, program_id, rpc, wallet_path, paraphrase, additional_signers).await
+        }
+    }
+}
+
+fn collect_idl_files(idl_dir: &Path) -> Result<Vec<PathBuf>> {
+    if !idl_dir.is_dir() {
+        anyhow::bail!("--all requires a directory of IDL files, got: {:?}", idl_dir);
+    }
+
+    let mut idl_files = Vec::new();
+    for entry in fs::read_dir(idl_dir)
+        .with_context(|| format!("Failed to read IDL directory: {:?}", idl_dir))? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("json") {
+            idl_files.push(path);
+        }
+    }
+    idl_files.sort();
+
+    if idl_files.is_empty() {
+        anyhow::bail!("No JSON IDL file found in directory: {:?}", idl_dir);
+    }
+    Ok(idl_files)
+}
+
+/// When `execution_order` runs an instruction before the one initializing its
+/// accounts, shows the dependency-respecting order and asks whether to use it.
+fn offer_suggested_order(
+    idl_data: &solify_common::IdlData,
+    execution_order: Vec<String>,
+    program_id: &str
+) -> Result<Vec<String>> {
+    let Some(suggested) = DependencyAnalyzer::new()
+        .suggest_execution_order(idl_data, &execution_order, program_id)? else {
+        return Ok(execution_order);
     };
 
-    let anchor_test_dir = detect_anchor_test_directory(&resolved_idl_path)?;
+    // Shown on stderr with the prompt, so `--stdout` output stays clean.
+    eprintln!("\nThe selected order runs some instructions before the ones that initialize their accounts.");
+    eprintln!("   Selected:  {}", execution_order.join(" → "));
+    eprintln!("   Suggested: {}", suggested.join(" → "));
+    Ok(if confirm_suggested_order()? { suggested } else { execution_order })
+}
 
-    run_interactive_test_generation(
-        &idl_data,
-        &execution_order,
-        &program_id,
-        &wallet_path,
-        &output,
-        &anchor_test_dir,
-        rpc_url,
-        &paraphrase,
-        off_chain
-    ).await?;
+#[cfg(feature = "tui")]
+fn confirm_suggested_order() -> Result<bool> {
+    Ok(Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Use the suggested order?")
+        .default(true)
+        .interact()?)
+}
+
+/// Without prompts the suggestion is taken, since it is the order that can succeed.
+#[cfg(not(feature = "tui"))]
+fn confirm_suggested_order() -> Result<bool> {
+    eprintln!("   Using the suggested order.");
+    Ok(true)
+}
+
+#[cfg(feature = "tui")]
+pub(crate) fn prompt_wallet_path() -> Result<PathBuf> {
+    let path: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Enter path to your wallet keypair")
+        .default("~/.config/solana/id.json".to_string())
+        .interact_text()?;
+    Ok(PathBuf::from(shellexpand::tilde(&path).to_string()))
+}
+
+#[cfg(not(feature = "tui"))]
+pub(crate) fn prompt_wallet_path() -> Result<PathBuf> {
+    anyhow::bail!("No wallet configured; pass --wallet or set wallet in the config file")
+}
+
+/// Downloads the IDL `program_id` published with `anchor idl init` into a
+/// temporary file, so it can go through the same pipeline as a local IDL.
+pub fn fetch_chain_idl(rpc: &RpcEndpoint, program_id: &str) -> Result<PathBuf> {
+    let program_id = Pubkey::from_str(program_id)
+        .with_context(|| format!("Invalid program id: {}", program_id))?;
+    let address = anchor_idl_address(&program_id)?;
+    let _span = tracing::info_span!("fetch", %program_id, %address).entered();
+    let client = rpc.client(CommitmentConfig::default())?;
+    let data = client
+        .get_account_data(&address)
+        .with_context(|| format!("No Anchor IDL account {} found for program {}", address, program_id))?;
+    let idl_json = decode_idl_account_json(&data)?;
+
+    let idl_path = std::env::temp_dir().join(format!("solify-{}.json", program_id));
+    fs::write(&idl_path, idl_json)
+        .with_context(|| format!("Failed to write fetched IDL to {:?}", idl_path))?;
+    info!("Fetched on-chain IDL for {} into {:?}", program_id, idl_path);
+    Ok(idl_path)
+}
+
+/// Longest paraphrase accepted, since it is used verbatim as a PDA seed.
+pub const MAX_PARAPHRASE_LEN: usize = solify_client::MAX_SEED_LEN;
 
+/// Rejects paraphrases that cannot be used as a single PDA seed.
+pub fn validate_paraphrase(paraphrase: &str) -> Result<()> {
+    if paraphrase.is_empty() {
+        anyhow::bail!("Paraphrase cannot be empty");
+    }
+    if paraphrase.len() > MAX_PARAPHRASE_LEN {
+        anyhow::bail!(
+            "Paraphrase is {} bytes long, but PDA seeds are limited to {} bytes",
+            paraphrase.len(),
+            MAX_PARAPHRASE_LEN
+        );
+    }
     Ok(())
 }
 
+#[cfg(feature = "tui")]
+fn prompt_paraphrase() -> Result<String> {
+    let paraphrase: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Enter paraphrase for test metadata")
+        .default("updated".to_string())
+        .validate_with(|input: &String| validate_paraphrase(input).map_err(|e| e.to_string()))
+        .interact_text()?;
+    Ok(paraphrase)
+}
+
+#[cfg(not(feature = "tui"))]
+fn prompt_paraphrase() -> Result<String> {
+    anyhow::bail!("No paraphrase given; pass --paraphrase")
+}
+
+#[cfg(feature = "tui")]
+#[allow(clippy::too_many_arguments)]
 async fn run_interactive_test_generation(
     idl_data: &solify_common::IdlData,
     execution_order: &[String],
     program: &str,
-    wallet_path: &PathBuf,
     output: &PathBuf,
     anchor_test_dir: &Option<PathBuf>,
-    rpc_url: &str,
-    paraphrase: &str,
-    off_chain: bool
+    processing: Processing,
+    options: &GenerateOptions,
+    report: &ReportOptions
 ) -> Result<()> {
     let mut terminal = init_terminal()?;
     let event_handler = EventHandler::new(Duration::from_millis(100));
@@ -135,35 +603,43 @@ async fn run_interactive_test_generation(
     let mut test_metadata: Option<TestMetadata> = None;
     let mut error_msg: Option<String> = None;
     let mut test_files_generated = false;
+    let mut generated_file: Option<Generated> = None;
 
     let idl_clone = idl_data.clone();
     let execution_order_clone = execution_order.to_vec();
     let program_clone = program.to_string();
-    let rpc_url_clone = rpc_url.to_string();
-    let wallet_clone = wallet_path.clone();
-    let paraphrase_clone = paraphrase.to_string();
+    let off_chain = matches!(processing, Processing::OffChain(_));
+    // The instruction off-chain analysis has reached; on-chain processing reports none.
+    let analysis_progress: Arc<Mutex<Option<InstructionProgress>>> = Arc::new(Mutex::new(None));
+    let progress_sink = analysis_progress.clone();
 
-    let mut onchain_handle = if off_chain {
+    let mut onchain_handle = match processing {
         // Use off-chain computation
-        Some(tokio::spawn(async move {
+        Processing::OffChain(off_chain) => Some(tokio::spawn(async move {
             process_offchain(
                 &idl_clone,
                 &execution_order_clone,
-                &program_clone
+                &program_clone,
+                &off_chain,
+                move |progress| {
+                    if let Ok(mut current) = progress_sink.lock() {
+                        *current = Some(progress.clone());
+                    }
+                }
             )
-        }))
-    } else {
+        })),
         // Use on-chain computation
-        Some(tokio::spawn(async move {
+        Processing::OnChain { rpc, wallet_path, paraphrase, additional_signers } => Some(tokio::spawn(async move {
             process_onchain(
                 &idl_clone,
                 &execution_order_clone,
                 &program_clone,
-                &rpc_url_clone,
-                &wallet_clone,
-                &paraphrase_clone
+                &rpc,
+                &wallet_path,
+                &paraphrase,
+                &additional_signers
             ).await
-        }))
+        })),
     };
 
     loop {
@@ -191,7 +667,13 @@ async fn run_interactive_test_generation(
                 Some(banner_msg)
             );
 
-            render_progress(f, chunks[1], "Generating Test Metadata", progress);
+            let current = analysis_progress.lock().ok().and_then(|current| current.clone());
+            match current {
+                Some(current) if matches!(state, AppState::Analyzing) => {
+                    render_progress(f, chunks[1], &current.to_string(), current.ratio());
+                }
+                _ => render_progress(f, chunks[1], "Generating Test Metadata", progress),
+            }
 
             match &state {
                 AppState::Complete => {
@@ -280,8 +762,9 @@ async fn run_interactive_test_generation(
                                         );
                                         state = AppState::Error(error_msg.as_ref().unwrap().clone());
                                     } else {
-                                        match generate_with_tera(&metadata, &idl_data, &final_output) {
-                                            Ok(_) => {
+                                        match generate(&metadata, idl_data, &final_output, options) {
+                                            Ok(generated) => {
+                                                generated_file = Some(generated);
                                                 info!("Test files generated successfully!");
                                             }
                                             Err(e) => {
@@ -339,7 +822,7 @@ async fn run_interactive_test_generation(
 
     restore_terminal(terminal)?;
 
-    if let Some(metadata) = test_metadata {
+    if let Some(metadata) = &test_metadata {
         println!("\n✅ On-chain processing complete!");
         if test_files_generated {
             let final_output = if let Some(anchor_dir) = anchor_test_dir {
@@ -370,12 +853,17 @@ async fn run_interactive_test_generation(
             println!("   Output directory: {}", final_output.display());
             println!("   IDL name: {}", idl_data.name);
 
-            generate_with_tera(&metadata, &idl_data, &final_output).with_context(||
+            let generated = generate(metadata, idl_data, &final_output, options).with_context(||
                 format!("Failed to generate test files in: {:?}", final_output)
             )?;
+            generated_file = Some(generated);
         }
     }
 
+    if let Some(metadata) = &test_metadata {
+        write_reports(report, metadata, generated_file.as_ref())?;
+    }
+
     if let Some(err) = error_msg {
         // Check if it's already a user-friendly message or if we need to check the error
         if err.contains("too large for on-chain processing") {
@@ -388,17 +876,123 @@ async fn run_interactive_test_generation(
     Ok(())
 }
 
+/// Lists what generation skipped, or `None` when it handled everything.
+pub fn skipped_summary(skipped: &[String]) -> Option<String> {
+    if skipped.is_empty() {
+        return None;
+    }
+    let mut summary = format!("⚠ Skipped or unsupported ({}):", skipped.len());
+    for note in skipped {
+        summary.push_str(&format!("\n   - {}", note));
+    }
+    Some(summary)
+}
+
+/// Prints what generation skipped, writes the `--report-json` and `--catalog`
+/// files and prints the `--summary` requested in `report`. The JSON report needs
+/// the test files that were written.
+fn write_reports(report: &ReportOptions, metadata: &TestMetadata, generated: Option<&Generated>) -> Result<()> {
+    if let Some(skipped) = generated.and_then(|generated| skipped_summary(&generated.skipped)) {
+        println!("\n{}", skipped);
+    }
+
+    if let (Some(report_path), Some(generated)) = (&report.json, generated) {
+        let json_report = GenerationReport::from_generation(metadata, generated);
+        write_report_json(&json_report, report_path)?;
+        println!("Report: {}", report_path.display());
+    }
+
+    if let Some((format, catalog_path)) = &report.catalog {
+        write_catalog(metadata, *format, catalog_path)?;
+        println!("Catalog: {}", catalog_path.display());
+    }
+
+    if report.summary {
+        println!();
+        print_metadata_summary(metadata);
+    }
+    Ok(())
+}
+
+/// What to report once a single-IDL gen-test run finishes.
+#[derive(Debug, Default)]
+pub struct ReportOptions {
+    /// Path for the `--report-json` output.
+    pub json: Option<PathBuf>,
+    /// Print the `--summary` tree to stdout.
+    pub summary: bool,
+    /// Format and path for the `--catalog` table of test cases.
+    pub catalog: Option<(CatalogFormat, PathBuf)>,
+}
+
+/// Machine-readable summary of a gen-test run, written with `--report-json`.
+#[derive(Debug, Serialize)]
+pub struct GenerationReport {
+    pub instruction_count: usize,
+    pub instructions: Vec<InstructionReport>,
+    pub pda_count: usize,
+    pub setup_requirement_count: usize,
+    pub output_file: PathBuf,
+    /// What generation left out or filled with a placeholder.
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InstructionReport {
+    pub name: String,
+    pub positive_cases: usize,
+    pub negative_cases: usize,
+}
+
+impl GenerationReport {
+    pub fn from_metadata(metadata: &TestMetadata, output_file: &Path) -> Self {
+        Self {
+            instruction_count: metadata.instruction_order.len(),
+            instructions: metadata.test_cases
+                .iter()
+                .map(|tc| InstructionReport {
+                    name: tc.instruction_name.clone(),
+                    positive_cases: tc.positive_cases.len(),
+                    negative_cases: tc.negative_cases.len(),
+                })
+                .collect(),
+            pda_count: metadata.pda_init_sequence.len(),
+            setup_requirement_count: metadata.setup_requirements.len(),
+            output_file: output_file.to_path_buf(),
+            skipped: Vec::new(),
+        }
+    }
+
+    pub fn from_generation(metadata: &TestMetadata, generated: &Generated) -> Self {
+        Self { skipped: generated.skipped.clone(), ..Self::from_metadata(metadata, &generated.path) }
+    }
+}
+
+pub fn write_report_json(report: &GenerationReport, path: &Path) -> Result<()> {
+    let json = serde_json::to_string_pretty(report).context("Failed to serialize report")?;
+    fs::write(path, json).with_context(|| format!("Failed to write report: {:?}", path))
+}
+
 fn process_offchain(
     idl_data: &solify_common::IdlData,
-    execution_order: &Vec<String>,
+    execution_order: &[String],
     program: &str,
+    options: &OffChainOptions,
+    on_progress: impl Fn(&InstructionProgress) + Send + Sync + 'static,
 ) -> Result<TestMetadata> {
-    let analyzer = DependencyAnalyzer::new();
+    let mut analyzer = DependencyAnalyzer::new().with_progress(on_progress);
+    if let Some(fuzz) = options.fuzz {
+        analyzer = analyzer.with_fuzz(fuzz);
+    }
     analyzer.analyze_dependencies(idl_data, execution_order, program.to_string())
         .map_err(|e| anyhow::anyhow!("Off-chain analysis failed: {}", e))
 }
 
 fn is_program_too_large_error(err: &anyhow::Error) -> bool {
+    if err.downcast_ref::<ProgramTooLarge>().is_some() {
+        return true;
+    }
+
     // Check the full error chain (including context messages)
     let err_str = err.to_string().to_lowercase();
     
@@ -440,15 +1034,83 @@ fn is_program_too_large_error(err: &anyhow::Error) -> bool {
     false
 }
 
+/// Regenerates on-chain metadata for a new instruction order when the stored IDL
+/// is already up to date, skipping the IDL upload.
+async fn reorder_onchain(
+    client: &SolifyClient,
+    wallet_keypair: &Keypair,
+    program_id: Pubkey,
+    execution_order: &[String],
+    paraphrase: &str
+) -> Result<TestMetadata> {
+    let update_result = client.update_execution_order(
+        wallet_keypair,
+        program_id,
+        execution_order.to_vec(),
+        paraphrase
+    ).with_context(|| "Failed to update execution order on-chain");
+
+    if let Err(ref e) = update_result {
+        if is_program_too_large_error(e) {
+            return Err(anyhow::anyhow!(
+                "Your Anchor program is too large for on-chain processing.\n\
+                The test metadata generation exceeds the available compute units or memory limits.\n\
+                Please wait for the next updates to generate tests for your program.\n\
+                \n\
+                Error details: {}",
+                e
+            ));
+        }
+    }
+
+    let _update_order_sig = update_result?;
+
+    tokio::time::sleep(Duration::from_secs(5)).await;
+
+    client.fetch_test_metadata(wallet_keypair.pubkey(), program_id, paraphrase)
+        .with_context(|| "Failed to fetch test metadata from on-chain account")?
+        .map(|account| account.test_metadata)
+        .ok_or_else(|| anyhow::anyhow!(
+            "Test metadata account not found after updating the execution order. \
+            Please check the transaction signature and verify the program is deployed correctly."
+        ))
+}
+
 async fn process_onchain(
     idl_data: &solify_common::IdlData,
-    execution_order: &Vec<String>,
+    execution_order: &[String],
     program: &str,
-    rpc_url: &str,
-    wallet_path: &PathBuf,
-    paraphrase: &str
+    rpc: &RpcEndpoint,
+    wallet_path: &Path,
+    paraphrase: &str,
+    additional_signers: &[PathBuf]
 ) -> Result<TestMetadata> {
-    let wallet_data = fs::read_to_string(&wallet_path)
+    let mut metadata = fetch_onchain_metadata(
+        idl_data,
+        execution_order,
+        program,
+        rpc,
+        wallet_path,
+        paraphrase,
+        additional_signers
+    ).await?;
+    TestCaseGenerator::new()
+        .resolve_newtype_arguments(&mut metadata.test_cases, idl_data)
+        .with_context(|| "Failed to resolve newtype arguments from the local IDL")?;
+    Ok(metadata)
+}
+
+/// Stores the IDL and generates the test metadata with the Solify program.
+async fn fetch_onchain_metadata(
+    idl_data: &solify_common::IdlData,
+    execution_order: &[String],
+    program: &str,
+    rpc: &RpcEndpoint,
+    wallet_path: &Path,
+    paraphrase: &str,
+    additional_signers: &[PathBuf]
+) -> Result<TestMetadata> {
+    let wallet_data = fs::read_to_string(wallet_path)
         .with_context(|| format!("Failed to read wallet file: {:?}", wallet_path))?;
     let wallet_bytes: Vec<u8> = serde_json::from_str(&wallet_data)
         .with_context(|| format!("Failed to parse wallet JSON: {:?}", wallet_path))?;
@@ -463,18 +1125,37 @@ async fn process_onchain(
     let mut secret_key = [0u8; 32];
     secret_key.copy_from_slice(&wallet_bytes[..32]);
     let wallet_keypair = Keypair::new_from_array(secret_key);
+    let additional_signers = additional_signers
+        .iter()
+        .map(load_signer)
+        .collect::<Result<Vec<_>>>()?;
 
     let user_pubkey = wallet_keypair.pubkey();
 
-    let program_id = Pubkey::from_str(&program)
+    let program_id = Pubkey::from_str(program)
         .with_context(|| format!("Invalid program ID: {}", program))?;
-    let client = SolifyClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed())
-        .with_context(|| format!("Failed to create Solify client for RPC: {}", rpc_url))?;
+    let commitment = rpc.commitment(CommitmentConfig::confirmed());
+    let client = SolifyClient::new_with_headers(&rpc.url, commitment, &rpc.headers)
+        .with_context(|| format!("Failed to create Solify client for RPC: {}", rpc.url))?
+        .with_additional_signers(additional_signers);
+    client.check_program_compatibility()?;
 
     let idl_storage = client.fetch_idl_storage(user_pubkey, program_id)
         .with_context(|| "Failed to fetch IDL storage account")?;
+    if let Some(stored) = &idl_storage {
+        let existing_metadata = client.fetch_test_metadata(user_pubkey, program_id, paraphrase)
+            .with_context(|| "Failed to check for existing test metadata")?;
+        if let Some(existing) = existing_metadata {
+            if idl_hash(&stored.idl_data)? == idl_hash(idl_data)? {
+                if existing.test_metadata.instruction_order == execution_order {
+                    return Ok(existing.test_metadata);
+                }
+                return reorder_onchain(&client, &wallet_keypair, program_id, execution_order, paraphrase).await;
+            }
+        }
+    }
     if idl_storage.is_some() {
-        let update_result = client.update_idl_data(&wallet_keypair, program_id, &idl_data)
+        let update_result = client.update_idl_data(&wallet_keypair, program_id, idl_data)
             .with_context(|| "Failed to update IDL data on-chain");
         
         if let Err(ref e) = update_result {
@@ -509,7 +1190,7 @@ async fn process_onchain(
             let generate_result = client.generate_metadata(
                 &wallet_keypair,
                 program_id,
-                execution_order.clone(),
+                execution_order.to_vec(),
                 paraphrase,
                 program.to_string()
             ).with_context(|| "Failed to generate test metadata on-chain");
@@ -532,7 +1213,7 @@ async fn process_onchain(
             let update_result = client.generate_metadata(
                 &wallet_keypair,
                 program_id,
-                execution_order.clone(),
+                execution_order.to_vec(),
                 paraphrase,
                 program.to_string()
             ).with_context(|| "Failed to update test metadata on-chain");
@@ -571,7 +1252,7 @@ async fn process_onchain(
             }
         }
     } else {
-        let store_result = client.store_idl_data(&wallet_keypair, program_id, &idl_data)
+        let store_result = client.store_idl_data(&wallet_keypair, program_id, idl_data)
             .with_context(|| "Failed to store IDL data on-chain");
         
         if let Err(ref e) = store_result {
@@ -593,7 +1274,7 @@ async fn process_onchain(
         let generate_result = client.generate_metadata(
             &wallet_keypair,
             program_id,
-            execution_order.clone(),
+            execution_order.to_vec(),
             paraphrase,
             program.to_string()
         ).with_context(|| "Failed to generate test metadata on-chain");
@@ -633,7 +1314,7 @@ async fn process_onchain(
     }
 }
 
-fn detect_anchor_test_directory(idl_path: &PathBuf) -> Result<Option<PathBuf>> {
+fn detect_anchor_test_directory(idl_path: &Path) -> Result<Option<PathBuf>> {
     let idl_parent = idl_path.parent();
     if let Some(parent) = idl_parent {
         let parent_str = parent.to_string_lossy();
@@ -658,6 +1339,7 @@ fn detect_anchor_test_directory(idl_path: &PathBuf) -> Result<Option<PathBuf>> {
     Ok(None)
 }
 
+#[cfg(feature = "tui")]
 fn sanitize_idl_name(name: &str) -> String {
     name.chars()
         .map(|c| {
@@ -669,7 +1351,15 @@ fn sanitize_idl_name(name: &str) -> String {
         .collect()
 }
 
-fn select_instruction_order_interactive(instructions: &[String]) -> Result<Vec<String>> {
+/// Without the TUI there is nothing to pick with, so instructions run in IDL order;
+/// [`offer_suggested_order`] still reorders them around their dependencies.
+#[cfg(not(feature = "tui"))]
+fn select_instruction_order(instructions: &[String]) -> Result<Vec<String>> {
+    Ok(instructions.to_vec())
+}
+
+#[cfg(feature = "tui")]
+fn select_instruction_order(instructions: &[String]) -> Result<Vec<String>> {
     let mut terminal = init_terminal()?;
     let event_handler = EventHandler::new(Duration::from_millis(100));
 
@@ -728,28 +1418,20 @@ fn select_instruction_order_interactive(instructions: &[String]) -> Result<Vec<S
                 anyhow::bail!("User cancelled");
             }
             AppEvent::Up => {
-                if cursor > 0 {
-                    cursor -= 1;
-                }
+                cursor = cursor.saturating_sub(1);
             }
-            AppEvent::Down => {
-                if cursor < available_instructions.len().saturating_sub(1) {
-                    cursor += 1;
-                }
+            AppEvent::Down if cursor < available_instructions.len().saturating_sub(1) => {
+                cursor += 1;
             }
-            AppEvent::Enter => {
-                if !available_instructions.is_empty() && cursor < available_instructions.len() {
-                    let selected = available_instructions.remove(cursor);
-                    selected_instructions.push(selected);
-                    if cursor >= available_instructions.len() && cursor > 0 {
-                        cursor -= 1;
-                    }
+            AppEvent::Enter if !available_instructions.is_empty() && cursor < available_instructions.len() => {
+                let selected = available_instructions.remove(cursor);
+                selected_instructions.push(selected);
+                if cursor >= available_instructions.len() && cursor > 0 {
+                    cursor -= 1;
                 }
             }
-            AppEvent::Char('d') | AppEvent::Char('D') => {
-                if !selected_instructions.is_empty() {
-                    break;
-                }
+            AppEvent::Char('d') | AppEvent::Char('D') if !selected_instructions.is_empty() => {
+                break;
             }
             _ => {}
         }
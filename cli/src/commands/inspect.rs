@@ -1,59 +1,218 @@
 use anyhow::Result;
+#[cfg(feature = "tui")]
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
+#[cfg(feature = "tui")]
 use std::time::Duration;
 use solana_client::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
 use solana_sdk::signature::Signature;
 use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta,
     EncodedTransaction,
-    UiInstruction,
     UiMessage,
-    UiParsedInstruction,
+    UiLoadedAddresses,
+    UiRawMessage,
     UiTransactionEncoding,
-    UiTransactionTokenBalance,
 };
+#[cfg(feature = "tui")]
+use solana_transaction_status::{ UiInstruction, UiParsedInstruction, UiTransactionTokenBalance };
 use solana_transaction_status::option_serializer::OptionSerializer;
+#[cfg(feature = "tui")]
 use std::collections::HashMap;
 use std::str::FromStr;
+use serde::Serialize;
+#[cfg(feature = "tui")]
 use serde_json::Value;
 
+use crate::RpcEndpoint;
+#[cfg(feature = "tui")]
 use crate::tui::{init_terminal, restore_terminal, EventHandler, AppEvent};
+#[cfg(feature = "tui")]
 use crate::tui::widgets::{
     render_banner, render_info_box, render_scrollable_info_box, render_status,
 };
 use log::info;
 
-pub async fn execute(signature: String, rpc_url: &str) -> Result<()> {
+/// How long the inspector waits for input before re-checking; the view is only
+/// redrawn when an event changes it, so this bounds latency rather than CPU.
+#[cfg(feature = "tui")]
+const INSPECTOR_POLL_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Caps applied when rendering parsed instruction info as indented JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JsonFormatLimits {
+    pub max_lines: usize,
+    pub max_depth: usize,
+}
+
+impl Default for JsonFormatLimits {
+    fn default() -> Self {
+        Self { max_lines: 16, max_depth: 4 }
+    }
+}
+
+impl JsonFormatLimits {
+    /// Limits used with `--detailed`, large enough for most parsed SPL instructions.
+    pub fn detailed() -> Self {
+        Self { max_lines: 256, max_depth: 16 }
+    }
+
+    /// Resolves the `--detailed` and `--depth N` flags; an explicit depth wins
+    /// over the depth implied by `--detailed`.
+    pub fn from_flags(detailed: bool, depth: Option<usize>) -> Self {
+        let mut limits = if detailed { Self::detailed() } else { Self::default() };
+        if let Some(depth) = depth {
+            limits.max_depth = depth;
+        }
+        limits
+    }
+}
+
+pub async fn execute(signature: String, rpc: &RpcEndpoint, limits: JsonFormatLimits, json: bool) -> Result<()> {
     info!("Inspecting transaction: {}", signature);
-    
-    match inspect_transaction_interactive(&signature, rpc_url).await {
-        Ok(_) => Ok(()),
-        Err(e) if e.to_string().contains("Device not configured") || 
-                  e.to_string().contains("not a terminal") => {
-            info!("Terminal not available, using simple output mode");
-            Ok(())
+
+    // Without the TUI the summary is the only view there is.
+    if json || !cfg!(feature = "tui") {
+        let client = rpc.client(CommitmentConfig::default())?;
+        let summary = fetch_transaction_summary(&client, &Signature::from_str(&signature)?)?;
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+        return Ok(());
+    }
+
+    #[cfg(feature = "tui")]
+    {
+        match inspect_transaction_interactive(&signature, rpc, limits).await {
+            Ok(_) => Ok(()),
+            Err(e) if e.to_string().contains("Device not configured") || 
+                      e.to_string().contains("not a terminal") => {
+                info!("Terminal not available, using simple output mode");
+                Ok(())
+            }
+            Err(e) => Err(e),
         }
-        Err(e) => Err(e),
     }
+    #[cfg(not(feature = "tui"))]
+    {
+        let _ = limits;
+        Ok(())
+    }
+}
+
+/// Typed overview of a confirmed transaction, shared by the inspector TUI and `--json`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TransactionSummary {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub fee: u64,
+    pub compute_units: Option<u64>,
+    pub status: std::result::Result<(), String>,
+    pub instruction_count: usize,
+    pub balance_deltas: Vec<BalanceDelta>,
+}
+
+/// Lamport balance of one transaction account before and after execution.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BalanceDelta {
+    pub pubkey: String,
+    pub pre_balance: u64,
+    pub post_balance: u64,
+    pub delta: i128,
+}
+
+/// Fetches `signature` and summarizes it without touching the terminal.
+pub fn fetch_transaction_summary(rpc: &RpcClient, signature: &Signature) -> Result<TransactionSummary> {
+    let tx = fetch_transaction(rpc, signature)?;
+    summarize_transaction(&signature.to_string(), &tx)
+}
+
+/// Builds the typed summary of an already fetched transaction.
+pub fn summarize_transaction(
+    signature: &str,
+    tx: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Result<TransactionSummary> {
+    let meta = tx.transaction.meta
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("Transaction metadata unavailable"))?;
+
+    let (account_keys, instruction_count) = match &tx.transaction.transaction {
+        EncodedTransaction::Json(json_tx) => match &json_tx.message {
+            UiMessage::Parsed(parsed_msg) => (
+                parsed_msg.account_keys.iter().map(|key| key.pubkey.clone()).collect(),
+                parsed_msg.instructions.len(),
+            ),
+            UiMessage::Raw(raw_msg) => {
+                let loaded_addresses = option_serializer_to_option(&meta.loaded_addresses);
+                let roles = raw_account_roles(raw_msg, loaded_addresses.as_ref());
+                (roles.into_iter().map(|role| role.pubkey).collect(), raw_msg.instructions.len())
+            }
+        },
+        encoded => match encoded.decode() {
+            Some(decoded) => (
+                decoded.message.static_account_keys().iter().map(|key| key.to_string()).collect(),
+                decoded.message.instructions().len(),
+            ),
+            None => (Vec::new(), 0),
+        },
+    };
+
+    let balance_deltas = account_keys
+        .into_iter()
+        .enumerate()
+        .map(|(idx, pubkey)| {
+            let pre_balance = meta.pre_balances.get(idx).copied().unwrap_or(0);
+            let post_balance = meta.post_balances.get(idx).copied().unwrap_or(0);
+            BalanceDelta {
+                pubkey,
+                pre_balance,
+                post_balance,
+                delta: i128::from(post_balance) - i128::from(pre_balance),
+            }
+        })
+        .collect();
+
+    Ok(TransactionSummary {
+        signature: signature.to_string(),
+        slot: tx.slot,
+        block_time: tx.block_time,
+        fee: meta.fee,
+        compute_units: option_serializer_to_option(&meta.compute_units_consumed),
+        status: meta.status.clone().map_err(|e| e.to_string()),
+        instruction_count,
+        balance_deltas,
+    })
 }
 
+fn fetch_transaction(
+    client: &RpcClient,
+    signature: &Signature,
+) -> solana_client::client_error::Result<EncodedConfirmedTransactionWithStatusMeta> {
+    client.get_transaction_with_config(
+        signature,
+        solana_client::rpc_config::RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::JsonParsed),
+            commitment: None,
+            max_supported_transaction_version: Some(0),
+        }
+    )
+}
+
+#[cfg(feature = "tui")]
 struct TransactionDetails {
-    signature: String,
-    slot: u64,
-    block_time: String,
-    status: String,
-    fee: u64,
+    summary: TransactionSummary,
     instructions: Vec<InstructionInfo>,
     accounts: Vec<AccountInfo>,
     logs: Vec<String>,
-    compute_units: Option<u64>,
     return_data: Option<ReturnDataInfo>,
 }
 
+#[cfg(feature = "tui")]
 struct InstructionInfo {
     program_title: String,
     instruction_summary: Vec<String>,
 }
 
+#[cfg(feature = "tui")]
 struct AccountInfo {
     pubkey: String,
     pre_balance: u64,
@@ -64,6 +223,70 @@ struct AccountInfo {
     token_balances: Vec<String>,
 }
 
+/// Where an account key of a transaction message comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AccountSource {
+    Transaction,
+    LookupTable,
+}
+
+/// Signer/writable role of an account referenced by a transaction message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AccountRole {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+    pub source: AccountSource,
+}
+
+/// Resolves account roles for a raw message: static keys from the message header,
+/// followed by writable then readonly addresses loaded from lookup tables.
+pub(crate) fn raw_account_roles(
+    raw_msg: &UiRawMessage,
+    loaded_addresses: Option<&UiLoadedAddresses>,
+) -> Vec<AccountRole> {
+    let num_signers = raw_msg.header.num_required_signatures as usize;
+    let num_readonly_signed = raw_msg.header.num_readonly_signed_accounts as usize;
+    let num_readonly_unsigned = raw_msg.header.num_readonly_unsigned_accounts as usize;
+    let total_static = raw_msg.account_keys.len();
+
+    let writable_signed_end = num_signers.saturating_sub(num_readonly_signed);
+    let writable_unsigned_end = total_static.saturating_sub(num_readonly_unsigned);
+
+    let mut roles: Vec<AccountRole> = raw_msg.account_keys
+        .iter()
+        .enumerate()
+        .map(|(idx, pubkey)| {
+            let is_signer = idx < num_signers;
+            let is_writable = if is_signer {
+                idx < writable_signed_end
+            } else {
+                idx < writable_unsigned_end
+            };
+            AccountRole {
+                pubkey: pubkey.clone(),
+                is_signer,
+                is_writable,
+                source: AccountSource::Transaction,
+            }
+        })
+        .collect();
+
+    if let Some(loaded) = loaded_addresses {
+        let lookup_role = |pubkey: &String, is_writable: bool| AccountRole {
+            pubkey: pubkey.clone(),
+            is_signer: false,
+            is_writable,
+            source: AccountSource::LookupTable,
+        };
+        roles.extend(loaded.writable.iter().map(|pubkey| lookup_role(pubkey, true)));
+        roles.extend(loaded.readonly.iter().map(|pubkey| lookup_role(pubkey, false)));
+    }
+
+    roles
+}
+
+#[cfg(feature = "tui")]
 struct ReturnDataInfo {
     program_id: String,
     data_base64: String,
@@ -76,6 +299,7 @@ fn option_serializer_to_option<T: Clone>(value: &OptionSerializer<T>) -> Option<
     }
 }
 
+#[cfg(feature = "tui")]
 fn option_serializer_to_vec<T: Clone>(value: &OptionSerializer<Vec<T>>) -> Vec<T> {
     match value {
         OptionSerializer::Some(items) => items.clone(),
@@ -83,6 +307,7 @@ fn option_serializer_to_vec<T: Clone>(value: &OptionSerializer<Vec<T>>) -> Vec<T
     }
 }
 
+#[cfg(feature = "tui")]
 fn format_simple_json_value(value: &Value) -> String {
     match value {
         Value::String(s) => s.clone(),
@@ -93,29 +318,38 @@ fn format_simple_json_value(value: &Value) -> String {
     }
 }
 
-fn format_json_value(value: &Value, indent: usize, max_lines: usize) -> Vec<String> {
-    fn helper(value: &Value, indent: usize, max_lines: usize, lines: &mut Vec<String>) {
-        if lines.len() >= max_lines {
+#[cfg(feature = "tui")]
+pub(crate) fn format_json_value(value: &Value, indent: usize, limits: JsonFormatLimits) -> Vec<String> {
+    fn collapsed(value: &Value) -> String {
+        match value {
+            Value::Object(map) => format!("{{{} fields}}", map.len()),
+            Value::Array(items) => format!("[{} items]", items.len()),
+            other => format_simple_json_value(other),
+        }
+    }
+
+    fn helper(value: &Value, indent: usize, depth: usize, limits: JsonFormatLimits, lines: &mut Vec<String>) {
+        if lines.len() >= limits.max_lines {
             return;
         }
         let pad = " ".repeat(indent);
         match value {
             Value::Object(map) => {
                 for (key, val) in map.iter() {
-                    if lines.len() >= max_lines {
+                    if lines.len() >= limits.max_lines {
                         break;
                     }
                     match val {
-                        Value::Object(_) | Value::Array(_) => {
+                        Value::Object(_) | Value::Array(_) if depth + 1 < limits.max_depth => {
                             lines.push(format!("{}{}:", pad, key));
-                            helper(val, indent + 2, max_lines, lines);
+                            helper(val, indent + 2, depth + 1, limits, lines);
                         }
                         _ => {
                             lines.push(format!(
                                 "{}{}: {}",
                                 pad,
                                 key,
-                                format_simple_json_value(val)
+                                collapsed(val)
                             ));
                         }
                     }
@@ -123,19 +357,19 @@ fn format_json_value(value: &Value, indent: usize, max_lines: usize) -> Vec<Stri
             }
             Value::Array(items) => {
                 for (index, item) in items.iter().enumerate() {
-                    if lines.len() >= max_lines {
+                    if lines.len() >= limits.max_lines {
                         break;
                     }
                     match item {
-                        Value::Object(_) | Value::Array(_) => {
+                        Value::Object(_) | Value::Array(_) if depth + 1 < limits.max_depth => {
                             lines.push(format!("{}[{}]:", pad, index));
-                            helper(item, indent + 2, max_lines, lines);
+                            helper(item, indent + 2, depth + 1, limits, lines);
                         }
                         _ => {
                             lines.push(format!(
                                 "{}- {}",
                                 pad,
-                                format_simple_json_value(item)
+                                collapsed(item)
                             ));
                         }
                     }
@@ -145,16 +379,22 @@ fn format_json_value(value: &Value, indent: usize, max_lines: usize) -> Vec<Stri
         }
     }
 
+    // Render one line past the cap so truncation can be detected.
+    let probe = JsonFormatLimits { max_lines: limits.max_lines + 1, ..limits };
     let mut lines = Vec::new();
-    helper(value, indent, max_lines, &mut lines);
-    if lines.len() > max_lines {
-        lines.truncate(max_lines);
+    helper(value, indent, 0, probe, &mut lines);
+    if lines.len() > limits.max_lines {
+        lines.truncate(limits.max_lines);
         lines.push(format!("{}...", " ".repeat(indent)));
     }
     lines
 }
 
-fn build_token_balance_map(
+/// Token balance changes keyed by `account_index`, which indexes the full account
+/// list the runtime loaded: static keys, then writable and readonly lookup table
+/// addresses. [`raw_account_roles`] and parsed `account_keys` use that order too.
+#[cfg(feature = "tui")]
+pub(crate) fn build_token_balance_map(
     pre: &[UiTransactionTokenBalance],
     post: &[UiTransactionTokenBalance],
 ) -> HashMap<u8, Vec<String>> {
@@ -213,10 +453,12 @@ fn build_token_balance_map(
     result
 }
 
+#[cfg(feature = "tui")]
 fn format_instruction_lines(
     instruction: &UiInstruction,
     accounts: &[AccountInfo],
     indent: usize,
+    limits: JsonFormatLimits,
 ) -> Vec<String> {
     let pad = " ".repeat(indent);
     match instruction {
@@ -234,9 +476,9 @@ fn format_instruction_lines(
                         }
                         if let Some(info) = obj.get("info") {
                             lines.push(format!("{}  Info:", pad));
-                            lines.extend(format_json_value(info, indent + 4, 16));
+                            lines.extend(format_json_value(info, indent + 4, limits));
                         } else {
-                            lines.extend(format_json_value(&parsed_instr.parsed, indent + 2, 16));
+                            lines.extend(format_json_value(&parsed_instr.parsed, indent + 2, limits));
                         }
                     }
                     value => {
@@ -297,37 +539,26 @@ fn format_instruction_lines(
     }
 }
 
+#[cfg(feature = "tui")]
 async fn inspect_transaction_interactive(
     signature_str: &str,
-    rpc_url: &str,
+    rpc: &RpcEndpoint,
+    limits: JsonFormatLimits,
 ) -> Result<()> {
     let mut terminal = init_terminal()?;
-    let event_handler = EventHandler::new(Duration::from_millis(100));
+    let event_handler = EventHandler::new(INSPECTOR_POLL_TIMEOUT);
 
-    let client = RpcClient::new(rpc_url.to_string());
+    let client = rpc.client(CommitmentConfig::default())?;
     let signature = Signature::from_str(signature_str)?;
 
     info!("Fetching transaction from RPC...");
 
 
-    let tx_result = client.get_transaction_with_config(
-        &signature,
-        solana_client::rpc_config::RpcTransactionConfig {
-            encoding: Some(UiTransactionEncoding::JsonParsed),
-            commitment: None,
-            max_supported_transaction_version: Some(0),
-        }
-    );
+    let tx_result = fetch_transaction(&client, &signature);
 
     let (tx_details, error_msg) = match tx_result {
         Ok(tx) => {
-            let slot = tx.slot;
-            let block_time = tx.block_time.map(|t| {
-                chrono::DateTime::from_timestamp(t, 0)
-                    .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
-                    .unwrap_or_else(|| "Unknown".to_string())
-            }).unwrap_or_else(|| "Unknown".to_string());
-            
+            let summary = summarize_transaction(signature_str, &tx)?;
             let meta = match tx.transaction.meta {
                 Some(ref meta) => meta,
                 _ => {
@@ -335,13 +566,6 @@ async fn inspect_transaction_interactive(
                 }
             };
 
-            let fee = meta.fee;
-            let status = if meta.status.is_ok() {
-                "✓ Success".to_string()
-            } else {
-                "✗ Failed".to_string()
-            };
-
             let mut instructions = Vec::new();
             let mut accounts = Vec::new();
 
@@ -386,7 +610,7 @@ async fn inspect_transaction_interactive(
 
                             for (idx, instruction) in parsed_msg.instructions.iter().enumerate() {
                                 let mut lines =
-                                    format_instruction_lines(instruction, &accounts, 0);
+                                    format_instruction_lines(instruction, &accounts, 0, limits);
                                 if lines.is_empty() {
                                     lines.push("Program: <unknown>".to_string());
                                 }
@@ -397,7 +621,7 @@ async fn inspect_transaction_interactive(
                                     summary.push("  Inner Instructions:".to_string());
                                     for (inner_idx, inner_ix) in inner_list.iter().enumerate() {
                                         let mut inner_lines =
-                                            format_instruction_lines(inner_ix, &accounts, 4);
+                                            format_instruction_lines(inner_ix, &accounts, 4, limits);
                                         if let Some(first) = inner_lines.first_mut() {
                                             *first = format!(
                                                 "    {}. {}",
@@ -417,40 +641,23 @@ async fn inspect_transaction_interactive(
                         }
                         UiMessage::Raw(raw_msg) => {
                             // Fallback for raw messages
-                            let num_signers = raw_msg.header.num_required_signatures as usize;
-                            let num_readonly_signed =
-                                raw_msg.header.num_readonly_signed_accounts as usize;
-                            let num_readonly_unsigned =
-                                raw_msg.header.num_readonly_unsigned_accounts as usize;
-                            let total_accounts = raw_msg.account_keys.len();
-
-                            let writable_signed_threshold =
-                                num_signers.saturating_sub(num_readonly_signed);
-                            let writable_unsigned_threshold = (total_accounts
-                                - num_signers)
-                                .saturating_sub(num_readonly_unsigned);
-
-                            for (idx, pubkey) in raw_msg.account_keys.iter().enumerate() {
-                                let is_signer = idx < num_signers;
-                                let is_writable = if is_signer {
-                                    idx < writable_signed_threshold
-                                } else {
-                                    let unsigned_index = idx - num_signers;
-                                    unsigned_index < writable_unsigned_threshold
-                                };
+                            let loaded_addresses =
+                                option_serializer_to_option(&meta.loaded_addresses);
+                            let roles = raw_account_roles(raw_msg, loaded_addresses.as_ref());
 
+                            for (idx, role) in roles.into_iter().enumerate() {
                                 let pre_balance =
                                     meta.pre_balances.get(idx).copied().unwrap_or(0);
                                 let post_balance =
                                     meta.post_balances.get(idx).copied().unwrap_or(0);
 
                                 accounts.push(AccountInfo {
-                                    pubkey: pubkey.clone(),
+                                    pubkey: role.pubkey,
                                     pre_balance,
                                     post_balance,
-                                    is_signer,
-                                    is_writable,
-                                    source: None,
+                                    is_signer: role.is_signer,
+                                    is_writable: role.is_writable,
+                                    source: Some(format!("{:?}", role.source)),
                                     token_balances: token_balance_map
                                         .remove(&(idx as u8))
                                         .unwrap_or_default(),
@@ -464,6 +671,7 @@ async fn inspect_transaction_interactive(
                                     &compiled_instruction,
                                     &accounts,
                                     0,
+                                    limits,
                                 );
                                 if lines.is_empty() {
                                     lines.push("Program: <compiled>".to_string());
@@ -474,7 +682,7 @@ async fn inspect_transaction_interactive(
                                     summary.push("  Inner Instructions:".to_string());
                                     for (inner_idx, inner_ix) in inner_list.iter().enumerate() {
                                         let mut inner_lines =
-                                            format_instruction_lines(inner_ix, &accounts, 4);
+                                            format_instruction_lines(inner_ix, &accounts, 4, limits);
                                         if let Some(first) = inner_lines.first_mut() {
                                             *first = format!(
                                                 "    {}. {}",
@@ -506,7 +714,6 @@ async fn inspect_transaction_interactive(
             }
 
             let logs = option_serializer_to_vec(&meta.log_messages);
-            let compute_units = option_serializer_to_option(&meta.compute_units_consumed);
             let return_data = option_serializer_to_option(&meta.return_data).map(|data| {
                 ReturnDataInfo {
                     program_id: data.program_id,
@@ -515,15 +722,10 @@ async fn inspect_transaction_interactive(
             });
 
             let details = TransactionDetails {
-                signature: signature_str.to_string(),
-                slot,
-                block_time,
-                status,
-                fee,
+                summary,
                 instructions,
                 accounts,
                 logs,
-                compute_units,
                 return_data,
             };
 
@@ -546,260 +748,272 @@ async fn inspect_transaction_interactive(
     let mut accounts_view_height: usize = 0;
     let mut logs_view_height: usize = 0;
     let mut logs_scroll: u16 = 0;
+    let mut redraw = true;
 
     loop {
-        terminal.draw(|f| {
-            let chunks = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints([
-                    Constraint::Length(3),
-                    Constraint::Length(8),
-                    Constraint::Min(10),
-                    Constraint::Length(3),
-                ])
-                .split(f.area());
-
-            render_banner(
-                f,
-                chunks[0],
-                "Solify Transaction Inspector",
-                Some("[q: quit | r: refresh]"),
-            );
-
-            if let Some(ref details) = tx_details {
-                let sig_short = if details.signature.len() > 20 {
-                    format!("{}...{}", &details.signature[..10], &details.signature[details.signature.len()-8..])
-                } else {
-                    details.signature.clone()
-                };
-
-                let fee_sol = details.fee as f64 / 1_000_000_000.0;
-                
-                let mut overview = vec![
-                    format!("Signature: {}", sig_short),
-                    format!("Status: {}", details.status),
-                    format!("Block: {}", details.slot),
-                    format!("Timestamp: {}", details.block_time),
-                    format!("Fee: {:.9} SOL", fee_sol),
-                    format!("Instructions: {}", details.instructions.len()),
-                    format!("Accounts: {}", details.accounts.len()),
-                ];
-                if let Some(cu) = details.compute_units {
-                    overview.push(format!("Compute Units: {}", cu));
-                }
-
-                render_info_box(f, chunks[1], "", overview);
-
-                let main_chunks = Layout::default()
-                    .direction(Direction::Horizontal)
-                    .constraints([
-                        Constraint::Percentage(50),
-                        Constraint::Percentage(50),
-                    ])
-                    .split(chunks[2]);
-
-                let left_chunks = Layout::default()
-                    .direction(Direction::Vertical)
-                    .constraints([
-                        Constraint::Percentage(60),
-                        Constraint::Percentage(40),
-                    ])
-                    .split(main_chunks[0]);
-
-                let right_chunks = Layout::default()
+        if redraw {
+            terminal.draw(|f| {
+                let chunks = Layout::default()
                     .direction(Direction::Vertical)
                     .constraints([
-                        Constraint::Percentage(60),
-                        Constraint::Percentage(40),
+                        Constraint::Length(3),
+                        Constraint::Length(8),
+                        Constraint::Min(10),
+                        Constraint::Length(3),
                     ])
-                    .split(main_chunks[1]);
+                    .split(f.area());
 
-                let mut instruction_lines = Vec::new();
-                instruction_lines.push(String::new());
-                if details.instructions.is_empty() {
-                    instruction_lines.push("No instructions parsed".to_string());
-                } else {
-                    for info in &details.instructions {
-                        instruction_lines.push(info.program_title.clone());
-                        instruction_lines.extend(info.instruction_summary.clone());
-                        instruction_lines.push(String::new());
-                    }
-                }
-                instructions_content_len = instruction_lines.len();
-                instructions_view_height = left_chunks[0].height.saturating_sub(2) as usize;
-                clamp_scroll(&mut instructions_scroll, instructions_content_len, instructions_view_height);
-                render_scrollable_info_box(
+                render_banner(
                     f,
-                    left_chunks[0],
-                    "Instructions",
-                    instruction_lines,
-                    instructions_scroll,
+                    chunks[0],
+                    "Solify Transaction Inspector",
+                    Some("[q: quit | r: refresh]"),
                 );
-                instructions_area = Some(left_chunks[0]);
-
-                let log_lines = if details.logs.is_empty() {
-                    vec![
-                        "LOGS".to_string(),
-                        String::new(),
-                        "No logs available".to_string(),
-                        "".to_string(),
-                        "Logs may not be available or were not stored.".to_string(),
-                    ]
-                } else {
-                    let mut lines = vec![String::new()];
-                    lines.extend(details.logs.iter().cloned());
-                    lines
-                };
-                logs_content_len = log_lines.len();
-                logs_view_height = left_chunks[1].height.saturating_sub(2) as usize;
-                clamp_scroll(&mut logs_scroll, logs_content_len, logs_view_height);
-                render_scrollable_info_box(
-                    f,
-                    left_chunks[1],
-                    "Logs",
-                    log_lines,
-                    logs_scroll,
-                );
-                logs_area = Some(left_chunks[1]);
 
-                let mut account_lines = Vec::new();
-                account_lines.push(String::new());
-                if details.accounts.is_empty() {
-                    account_lines.push("No account metadata available".to_string());
-                } else {
-                    for (idx, account) in details.accounts.iter().enumerate() {
-                        account_lines.push(format!("{}. {}", idx + 1, account.pubkey));
+                if let Some(ref details) = tx_details {
+                    let summary = &details.summary;
+                    let sig_short = if summary.signature.len() > 20 {
+                        format!("{}...{}", &summary.signature[..10], &summary.signature[summary.signature.len()-8..])
+                    } else {
+                        summary.signature.clone()
+                    };
+
+                    let fee_sol = summary.fee as f64 / 1_000_000_000.0;
+                    let status = if summary.status.is_ok() { "✓ Success" } else { "✗ Failed" };
+                    let block_time = summary.block_time.map(|t| {
+                        chrono::DateTime::from_timestamp(t, 0)
+                            .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+                            .unwrap_or_else(|| "Unknown".to_string())
+                    }).unwrap_or_else(|| "Unknown".to_string());
+                    
+                    let mut overview = vec![
+                        format!("Signature: {}", sig_short),
+                        format!("Status: {}", status),
+                        format!("Block: {}", summary.slot),
+                        format!("Timestamp: {}", block_time),
+                        format!("Fee: {:.9} SOL", fee_sol),
+                        format!("Instructions: {}", summary.instruction_count),
+                        format!("Accounts: {}", details.accounts.len()),
+                    ];
+                    if let Some(cu) = summary.compute_units {
+                        overview.push(format!("Compute Units: {}", cu));
+                    }
 
-                        let mut flags = Vec::new();
-                        if account.is_signer {
-                            flags.push("Signer");
-                        }
-                        if account.is_writable {
-                            flags.push("Writable");
-                        }
-                        if !flags.is_empty() {
-                            account_lines.push(format!("   {}", flags.join(" | ")));
-                        }
-                        if let Some(source) = &account.source {
-                            account_lines.push(format!("   Source: {}", source));
+                    render_info_box(f, chunks[1], "", overview);
+
+                    let main_chunks = Layout::default()
+                        .direction(Direction::Horizontal)
+                        .constraints([
+                            Constraint::Percentage(50),
+                            Constraint::Percentage(50),
+                        ])
+                        .split(chunks[2]);
+
+                    let left_chunks = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([
+                            Constraint::Percentage(60),
+                            Constraint::Percentage(40),
+                        ])
+                        .split(main_chunks[0]);
+
+                    let right_chunks = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([
+                            Constraint::Percentage(60),
+                            Constraint::Percentage(40),
+                        ])
+                        .split(main_chunks[1]);
+
+                    let mut instruction_lines = Vec::new();
+                    instruction_lines.push(String::new());
+                    if details.instructions.is_empty() {
+                        instruction_lines.push("No instructions parsed".to_string());
+                    } else {
+                        for info in &details.instructions {
+                            instruction_lines.push(info.program_title.clone());
+                            instruction_lines.extend(info.instruction_summary.clone());
+                            instruction_lines.push(String::new());
                         }
+                    }
+                    instructions_content_len = instruction_lines.len();
+                    instructions_view_height = left_chunks[0].height.saturating_sub(2) as usize;
+                    clamp_scroll(&mut instructions_scroll, instructions_content_len, instructions_view_height);
+                    render_scrollable_info_box(
+                        f,
+                        left_chunks[0],
+                        "Instructions",
+                        instruction_lines,
+                        instructions_scroll,
+                    );
+                    instructions_area = Some(left_chunks[0]);
+
+                    let log_lines = if details.logs.is_empty() {
+                        vec![
+                            "LOGS".to_string(),
+                            String::new(),
+                            "No logs available".to_string(),
+                            "".to_string(),
+                            "Logs may not be available or were not stored.".to_string(),
+                        ]
+                    } else {
+                        let mut lines = vec![String::new()];
+                        lines.extend(details.logs.iter().cloned());
+                        lines
+                    };
+                    logs_content_len = log_lines.len();
+                    logs_view_height = left_chunks[1].height.saturating_sub(2) as usize;
+                    clamp_scroll(&mut logs_scroll, logs_content_len, logs_view_height);
+                    render_scrollable_info_box(
+                        f,
+                        left_chunks[1],
+                        "Logs",
+                        log_lines,
+                        logs_scroll,
+                    );
+                    logs_area = Some(left_chunks[1]);
+
+                    let mut account_lines = Vec::new();
+                    account_lines.push(String::new());
+                    if details.accounts.is_empty() {
+                        account_lines.push("No account metadata available".to_string());
+                    } else {
+                        for (idx, account) in details.accounts.iter().enumerate() {
+                            account_lines.push(format!("{}. {}", idx + 1, account.pubkey));
+
+                            let mut flags = Vec::new();
+                            if account.is_signer {
+                                flags.push("Signer");
+                            }
+                            if account.is_writable {
+                                flags.push("Writable");
+                            }
+                            if !flags.is_empty() {
+                                account_lines.push(format!("   {}", flags.join(" | ")));
+                            }
+                            if let Some(source) = &account.source {
+                                account_lines.push(format!("   Source: {}", source));
+                            }
 
-                        let balance_change = account.post_balance as i64 - account.pre_balance as i64;
-                        if balance_change != 0 {
+                            let balance_change = account.post_balance as i64 - account.pre_balance as i64;
+                            if balance_change != 0 {
+                                account_lines.push(format!(
+                                    "   ΔBalance: {}{:.9} SOL",
+                                    if balance_change > 0 { "+" } else { "" },
+                                    balance_change as f64 / 1_000_000_000.0
+                                ));
+                            }
                             account_lines.push(format!(
-                                "   ΔBalance: {}{:.9} SOL",
-                                if balance_change > 0 { "+" } else { "" },
-                                balance_change as f64 / 1_000_000_000.0
+                                "   Balance: {:.9} SOL",
+                                account.post_balance as f64 / 1_000_000_000.0
                             ));
+
+                            for token_line in &account.token_balances {
+                                account_lines.push(format!("   {}", token_line));
+                            }
+
+                            account_lines.push(String::new());
                         }
-                        account_lines.push(format!(
-                            "   Balance: {:.9} SOL",
-                            account.post_balance as f64 / 1_000_000_000.0
+                    }
+                    accounts_content_len = account_lines.len();
+                    accounts_view_height = right_chunks[0].height.saturating_sub(2) as usize;
+                    clamp_scroll(&mut accounts_scroll, accounts_content_len, accounts_view_height);
+                    render_scrollable_info_box(
+                        f,
+                        right_chunks[0],
+                        "Accounts",
+                        account_lines,
+                        accounts_scroll,
+                    );
+                    accounts_area = Some(right_chunks[0]);
+
+                    let mut return_lines = Vec::new();
+                    if let Some(return_data) = &details.return_data {
+                        return_lines.push("RETURN DATA".to_string());
+                        return_lines.push(String::new());
+                        return_lines.push(format!("Program: {}", return_data.program_id));
+                        let preview_len = return_data
+                            .data_base64
+                            .len()
+                            .min(80);
+                        return_lines.push(format!(
+                            "Data (base64, {} chars): {}{}",
+                            return_data.data_base64.len(),
+                            &return_data.data_base64[..preview_len],
+                            if return_data.data_base64.len() > preview_len {
+                                "..."
+                            } else {
+                                ""
+                            }
                         ));
+                    } else {
+                        return_lines.push("Return data not present".to_string());
+                        return_lines.push(String::new());
+                        return_lines.push("Tip: Run with --detailed to view more RPC fields.".to_string());
+                    }
 
-                        for token_line in &account.token_balances {
-                            account_lines.push(format!("   {}", token_line));
-                        }
+                    render_info_box(f, right_chunks[1], "Additional Info", return_lines);
+
+                    let status_msg =
+                        "Mouse wheel: scroll instructions/accounts/logs | r: refresh | q: quit";
+                    render_status(f, chunks[3], status_msg, false);
+                } else if let Some(ref err) = error_msg {
+                    instructions_area = None;
+                    accounts_area = None;
+                    logs_area = None;
+                    instructions_content_len = 0;
+                    accounts_content_len = 0;
+                    logs_content_len = 0;
+                    instructions_view_height = 0;
+                    accounts_view_height = 0;
+                    logs_view_height = 0;
+                    instructions_scroll = 0;
+                    accounts_scroll = 0;
+                    logs_scroll = 0;
+                    render_info_box(
+                        f,
+                        chunks[1],
+                        "Error",
+                        vec![
+                            "Failed to fetch transaction".to_string(),
+                            format!("Error: {}", err),
+                            "".to_string(),
+                            "Note: This may be because:".to_string(),
+                            "- The transaction doesn't exist".to_string(),
+                            "- The RPC node doesn't have this transaction".to_string(),
+                            "- The signature is invalid".to_string(),
+                        ],
+                    );
 
-                        account_lines.push(String::new());
-                    }
-                }
-                accounts_content_len = account_lines.len();
-                accounts_view_height = right_chunks[0].height.saturating_sub(2) as usize;
-                clamp_scroll(&mut accounts_scroll, accounts_content_len, accounts_view_height);
-                render_scrollable_info_box(
-                    f,
-                    right_chunks[0],
-                    "Accounts",
-                    account_lines,
-                    accounts_scroll,
-                );
-                accounts_area = Some(right_chunks[0]);
-
-                let mut return_lines = Vec::new();
-                if let Some(return_data) = &details.return_data {
-                    return_lines.push("RETURN DATA".to_string());
-                    return_lines.push(String::new());
-                    return_lines.push(format!("Program: {}", return_data.program_id));
-                    let preview_len = return_data
-                        .data_base64
-                        .len()
-                        .min(80);
-                    return_lines.push(format!(
-                        "Data (base64, {} chars): {}{}",
-                        return_data.data_base64.len(),
-                        &return_data.data_base64[..preview_len],
-                        if return_data.data_base64.len() > preview_len {
-                            "..."
-                        } else {
-                            ""
-                        }
-                    ));
+                    render_status(f, chunks[3], "Transaction not found", true);
                 } else {
-                    return_lines.push("Return data not present".to_string());
-                    return_lines.push(String::new());
-                    return_lines.push("Tip: Run with --detailed to view more RPC fields.".to_string());
+                    instructions_area = None;
+                    accounts_area = None;
+                    logs_area = None;
+                    instructions_content_len = 0;
+                    accounts_content_len = 0;
+                    logs_content_len = 0;
+                    instructions_view_height = 0;
+                    accounts_view_height = 0;
+                    logs_view_height = 0;
+                    instructions_scroll = 0;
+                    accounts_scroll = 0;
+                    logs_scroll = 0;
+                    render_info_box(
+                        f,
+                        chunks[1],
+                        "Loading",
+                        vec!["Fetching transaction data...".to_string()],
+                    );
+
+                    render_status(f, chunks[3], "Loading...", false);
                 }
+            })?;
+        }
 
-                render_info_box(f, right_chunks[1], "Additional Info", return_lines);
-
-                let status_msg =
-                    "Mouse wheel: scroll instructions/accounts/logs | r: refresh | q: quit";
-                render_status(f, chunks[3], status_msg, false);
-            } else if let Some(ref err) = error_msg {
-                instructions_area = None;
-                accounts_area = None;
-                logs_area = None;
-                instructions_content_len = 0;
-                accounts_content_len = 0;
-                logs_content_len = 0;
-                instructions_view_height = 0;
-                accounts_view_height = 0;
-                logs_view_height = 0;
-                instructions_scroll = 0;
-                accounts_scroll = 0;
-                logs_scroll = 0;
-                render_info_box(
-                    f,
-                    chunks[1],
-                    "Error",
-                    vec![
-                        "Failed to fetch transaction".to_string(),
-                        format!("Error: {}", err),
-                        "".to_string(),
-                        "Note: This may be because:".to_string(),
-                        "- The transaction doesn't exist".to_string(),
-                        "- The RPC node doesn't have this transaction".to_string(),
-                        "- The signature is invalid".to_string(),
-                    ],
-                );
-
-                render_status(f, chunks[3], "Transaction not found", true);
-            } else {
-                instructions_area = None;
-                accounts_area = None;
-                logs_area = None;
-                instructions_content_len = 0;
-                accounts_content_len = 0;
-                logs_content_len = 0;
-                instructions_view_height = 0;
-                accounts_view_height = 0;
-                logs_view_height = 0;
-                instructions_scroll = 0;
-                accounts_scroll = 0;
-                logs_scroll = 0;
-                render_info_box(
-                    f,
-                    chunks[1],
-                    "Loading",
-                    vec!["Fetching transaction data...".to_string()],
-                );
-
-                render_status(f, chunks[3], "Loading...", false);
-            }
-        })?;
-
-        match event_handler.next()? {
+        let event = event_handler.next()?;
+        let scroll_before = [instructions_scroll, accounts_scroll, logs_scroll];
+        match event {
             AppEvent::Quit => break,
             AppEvent::Char('r') | AppEvent::Char('R') => {
                 info!("Refreshing transaction data...");
@@ -845,10 +1059,15 @@ async fn inspect_transaction_interactive(
             }
             _ => {}
         }
+        redraw = needs_redraw(
+            &event,
+            scroll_before,
+            [instructions_scroll, accounts_scroll, logs_scroll],
+        );
     }
 
     restore_terminal(terminal)?;
-    if let Some(_) = tx_details {
+    if tx_details.is_some() {
         println!("\n✓ Transaction inspection complete");
         println!("  Signature: {}", signature_str);
     } else if let Some(err) = error_msg {
@@ -859,6 +1078,18 @@ async fn inspect_transaction_interactive(
     Ok(())
 }
 
+/// The inspector only redraws when an event changed what is on screen, so an
+/// idle view blocks in `EventHandler::next` instead of repainting every tick.
+#[cfg(feature = "tui")]
+pub(crate) fn needs_redraw(event: &AppEvent, scroll_before: [u16; 3], scroll_after: [u16; 3]) -> bool {
+    match event {
+        AppEvent::Resize => true,
+        AppEvent::Tick => false,
+        _ => scroll_before != scroll_after,
+    }
+}
+
+#[cfg(feature = "tui")]
 fn point_in_rect(rect: Rect, column: u16, row: u16) -> bool {
     column >= rect.x
         && column < rect.x.saturating_add(rect.width)
@@ -866,6 +1097,7 @@ fn point_in_rect(rect: Rect, column: u16, row: u16) -> bool {
         && row < rect.y.saturating_add(rect.height)
 }
 
+#[cfg(feature = "tui")]
 fn compute_max_scroll(content_len: usize, view_height: usize) -> u16 {
     if view_height == 0 || content_len <= view_height {
         0
@@ -874,7 +1106,8 @@ fn compute_max_scroll(content_len: usize, view_height: usize) -> u16 {
     }
 }
 
-fn clamp_scroll(scroll: &mut u16, content_len: usize, view_height: usize) {
+#[cfg(feature = "tui")]
+pub(crate) fn clamp_scroll(scroll: &mut u16, content_len: usize, view_height: usize) {
     let max_scroll = compute_max_scroll(content_len, view_height);
     if max_scroll == 0 {
         *scroll = 0;
@@ -883,7 +1116,8 @@ fn clamp_scroll(scroll: &mut u16, content_len: usize, view_height: usize) {
     }
 }
 
-fn adjust_scroll(
+#[cfg(feature = "tui")]
+pub(crate) fn adjust_scroll(
     scroll: &mut u16,
     up: bool,
     content_len: usize,
@@ -1,2 +1,7 @@
 pub mod inspect;
 pub mod gen_test;
+pub mod summary;
+pub mod catalog;
+pub mod estimate;
+pub mod show_metadata;
+pub mod doctor;
@@ -0,0 +1,203 @@
+use anyhow::{ Context, Result };
+#[cfg(feature = "tui")]
+use ratatui::layout::{ Constraint, Direction, Layout };
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+use solify_client::{ derive_test_metadata_config_address, load_signer, SolifyClient, TestMetadataAccount };
+use std::path::PathBuf;
+use std::str::FromStr;
+#[cfg(feature = "tui")]
+use std::time::Duration;
+
+use crate::RpcEndpoint;
+use crate::commands::gen_test::{ prompt_wallet_path, validate_paraphrase };
+#[cfg(feature = "tui")]
+use crate::commands::inspect::{ adjust_scroll, clamp_scroll };
+use crate::commands::summary::format_seed;
+#[cfg(feature = "tui")]
+use crate::tui::{
+    init_terminal,
+    render_banner,
+    render_scrollable_info_box,
+    render_status,
+    restore_terminal,
+    AppEvent,
+    EventHandler,
+};
+
+/// The view only changes on input, so this bounds latency rather than CPU.
+#[cfg(feature = "tui")]
+const POLL_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Fetches the test metadata stored on-chain for `program` under `paraphrase`
+/// and shows it in a scrollable view. The account is derived from `authority`,
+/// or from the wallet's pubkey when no authority is given.
+pub async fn execute(
+    program: String,
+    paraphrase: String,
+    authority: Option<String>,
+    wallet: Option<PathBuf>,
+    rpc: &RpcEndpoint
+) -> Result<()> {
+    validate_paraphrase(&paraphrase)?;
+    let program_id = Pubkey::from_str(&program)
+        .with_context(|| format!("Invalid program ID: {}", program))?;
+    let authority = match authority {
+        Some(authority) => Pubkey::from_str(&authority)
+            .with_context(|| format!("Invalid authority: {}", authority))?,
+        None => {
+            let wallet = match wallet {
+                Some(wallet) => wallet,
+                None => prompt_wallet_path()?,
+            };
+            load_signer(&wallet)?.pubkey()
+        }
+    };
+
+    let client = SolifyClient::new_with_headers(&rpc.url, rpc.commitment(CommitmentConfig::confirmed()), &rpc.headers)
+        .with_context(|| format!("Failed to create Solify client for RPC: {}", rpc.url))?;
+    let Some(account) = client.fetch_test_metadata(authority, program_id, &paraphrase)? else {
+        let (address, _) = derive_test_metadata_config_address(&program_id, &authority, &paraphrase)?;
+        anyhow::bail!(
+            "No test metadata found at {} for program {} and authority {}",
+            address,
+            program_id,
+            authority
+        );
+    };
+
+    show_metadata_interactive(&account)
+}
+
+/// Prints the lines the scrollable view would show.
+#[cfg(not(feature = "tui"))]
+fn show_metadata_interactive(account: &TestMetadataAccount) -> Result<()> {
+    println!("Test metadata: {}", account.program_name);
+    for line in metadata_account_lines(account) {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "tui")]
+fn show_metadata_interactive(account: &TestMetadataAccount) -> Result<()> {
+    let lines = metadata_account_lines(account);
+    let title = format!("Test metadata: {}", account.program_name);
+    let mut terminal = init_terminal()?;
+    let event_handler = EventHandler::new(POLL_TIMEOUT);
+    let mut scroll: u16 = 0;
+    let mut view_height: usize = 0;
+    let mut redraw = true;
+
+    loop {
+        if redraw {
+            terminal.draw(|f| {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(3), Constraint::Min(5), Constraint::Length(3)])
+                    .split(f.area());
+
+                render_banner(f, chunks[0], "Solify Test Metadata", Some("[↑/↓ or mouse wheel: scroll | q: quit]"));
+                // The box border takes a row above and below the content.
+                view_height = chunks[1].height.saturating_sub(2) as usize;
+                clamp_scroll(&mut scroll, lines.len(), view_height);
+                render_scrollable_info_box(f, chunks[1], &title, lines.clone(), scroll);
+                render_status(f, chunks[2], &format!("{} lines", lines.len()), false);
+            })?;
+        }
+
+        let event = event_handler.next()?;
+        let scroll_before = scroll;
+        match event {
+            AppEvent::Quit => break,
+            AppEvent::Up | AppEvent::MouseScroll { up: true, .. } => {
+                adjust_scroll(&mut scroll, true, lines.len(), view_height);
+            }
+            AppEvent::Down | AppEvent::MouseScroll { up: false, .. } => {
+                adjust_scroll(&mut scroll, false, lines.len(), view_height);
+            }
+            _ => {}
+        }
+        redraw = matches!(event, AppEvent::Resize) || scroll != scroll_before;
+    }
+
+    restore_terminal(terminal)
+}
+
+/// The lines shown by `show-metadata`: where the metadata is stored, then its
+/// account dependencies, PDA sequence, setup steps and test cases.
+pub fn metadata_account_lines(account: &TestMetadataAccount) -> Vec<String> {
+    let metadata = &account.test_metadata;
+    let updated = chrono::DateTime::from_timestamp(account.timestamp, 0)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| "Unknown".to_string());
+    let mut lines = vec![
+        format!("Program:   {} ({})", account.program_name, account.program_id),
+        format!("Authority: {}", account.authority),
+        format!("Account:   {}", account.address),
+        format!("Updated:   {}", updated),
+        format!("Order:     {}", metadata.instruction_order.join(" → ")),
+    ];
+
+    let dependencies: Vec<String> = metadata.account_dependencies
+        .iter()
+        .enumerate()
+        .map(|(i, dep)| {
+            let flags: Vec<&str> = [
+                (dep.is_pda, "pda"),
+                (dep.is_signer, "signer"),
+                (dep.is_mut, "mut"),
+                (dep.must_be_initialized, "initialized"),
+            ]
+                .into_iter()
+                .filter_map(|(set, flag)| set.then_some(flag))
+                .collect();
+            let mut line = format!("{}. {} [{}]", i + 1, dep.account_name, flags.join(", "));
+            if !dep.depends_on.is_empty() {
+                line.push_str(&format!(" ← {}", dep.depends_on.join(", ")));
+            }
+            line
+        })
+        .collect();
+    push_section(&mut lines, "Account dependencies", dependencies);
+
+    let pdas: Vec<String> = metadata.pda_init_sequence
+        .iter()
+        .enumerate()
+        .map(|(i, pda)| {
+            let seeds: Vec<String> = pda.seeds.iter().map(format_seed).collect();
+            format!("{}. {} [{}]", i + 1, pda.account_name, seeds.join(", "))
+        })
+        .collect();
+    push_section(&mut lines, "PDA sequence", pdas);
+
+    let setup: Vec<String> = metadata.setup_requirements
+        .iter()
+        .enumerate()
+        .map(|(i, req)| format!("{}. {:?}: {}", i + 1, req.requirement_type, req.description))
+        .collect();
+    push_section(&mut lines, "Setup steps", setup);
+
+    let mut cases = Vec::new();
+    for tc in &metadata.test_cases {
+        cases.push(tc.instruction_name.clone());
+        cases.extend(tc.positive_cases.iter().map(|case| format!("  + {}", case.description)));
+        cases.extend(tc.negative_cases.iter().map(|case| format!("  - {}", case.description)));
+    }
+    let count: usize = metadata.test_cases
+        .iter()
+        .map(|tc| tc.positive_cases.len() + tc.negative_cases.len())
+        .sum();
+    lines.push(String::new());
+    lines.push(format!("Test cases ({})", count));
+    lines.extend(cases.into_iter().map(|case| format!("  {}", case)));
+
+    lines
+}
+
+fn push_section(lines: &mut Vec<String>, title: &str, items: Vec<String>) {
+    lines.push(String::new());
+    lines.push(format!("{} ({})", title, items.len()));
+    lines.extend(items.into_iter().map(|item| format!("  {}", item)));
+}
@@ -0,0 +1,73 @@
+use solify_common::{ SeedComponent, SeedType, TestMetadata };
+
+use crate::utils::truncate_str;
+
+const MAX_SEED_VALUE_LEN: usize = 24;
+
+/// Prints a human-readable tree of the generated test metadata.
+pub fn print_metadata_summary(metadata: &TestMetadata) {
+    for line in metadata_summary_lines(metadata) {
+        println!("{}", line);
+    }
+}
+
+/// Builds the lines printed by [`print_metadata_summary`].
+pub fn metadata_summary_lines(metadata: &TestMetadata) -> Vec<String> {
+    let mut lines = vec!["Test metadata summary".to_string()];
+
+    let order: Vec<String> = metadata.instruction_order
+        .iter()
+        .enumerate()
+        .map(|(i, name)| format!("{}. {}", i + 1, name))
+        .collect();
+    push_section(&mut lines, "Instruction order", &order, false);
+
+    let cases: Vec<String> = metadata.test_cases
+        .iter()
+        .map(|tc| {
+            format!(
+                "{}: {} positive, {} negative",
+                tc.instruction_name,
+                tc.positive_cases.len(),
+                tc.negative_cases.len()
+            )
+        })
+        .collect();
+    push_section(&mut lines, "Test cases", &cases, false);
+
+    let pdas: Vec<String> = metadata.pda_init_sequence
+        .iter()
+        .map(|pda| {
+            let seeds: Vec<String> = pda.seeds.iter().map(format_seed).collect();
+            format!("{} [{}]", pda.account_name, seeds.join(", "))
+        })
+        .collect();
+    push_section(&mut lines, "PDAs", &pdas, false);
+
+    let setup: Vec<String> = metadata.setup_requirements
+        .iter()
+        .enumerate()
+        .map(|(i, req)| format!("{}. {:?}: {}", i + 1, req.requirement_type, req.description))
+        .collect();
+    push_section(&mut lines, "Setup steps", &setup, true);
+
+    lines
+}
+
+fn push_section(lines: &mut Vec<String>, title: &str, items: &[String], last: bool) {
+    let (branch, indent) = if last { ("└─", "   ") } else { ("├─", "│  ") };
+    lines.push(format!("{} {} ({})", branch, title, items.len()));
+    for (i, item) in items.iter().enumerate() {
+        let item_branch = if i + 1 == items.len() { "└─" } else { "├─" };
+        lines.push(format!("{}{} {}", indent, item_branch, item));
+    }
+}
+
+pub(crate) fn format_seed(seed: &SeedComponent) -> String {
+    let value = truncate_str(&seed.value, MAX_SEED_VALUE_LEN);
+    match seed.seed_type {
+        SeedType::Static => format!("\"{}\"", value),
+        SeedType::AccountKey => format!("account {}", value),
+        SeedType::Argument => format!("arg {}", value),
+    }
+}
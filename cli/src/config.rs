@@ -0,0 +1,115 @@
+use anyhow::{ Context, Result };
+use serde::Deserialize;
+use solana_commitment_config::{ CommitmentConfig, CommitmentLevel };
+use std::fs;
+use std::path::{ Path, PathBuf };
+use std::str::FromStr;
+
+/// Read from the working directory at startup unless `--config` names another file.
+pub const CONFIG_FILE_NAME: &str = "solify.toml";
+
+/// Output directory used when neither `--output` nor the config file sets one.
+pub const DEFAULT_OUTPUT_DIR: &str = "tests";
+
+/// The only test framework the generator emits.
+pub const DEFAULT_FRAMEWORK: &str = "mocha";
+
+/// Written by `solify init-config`. Every setting is commented out, so the file
+/// changes nothing until the user opts in.
+pub const DEFAULT_CONFIG_TEMPLATE: &str = r#"# Solify configuration. Command-line flags override every value here.
+
+# RPC endpoint used for on-chain processing and `inspect`.
+# rpc_url = "https://api.devnet.solana.com"
+
+# Commitment for RPC requests: "processed", "confirmed" or "finalized".
+# commitment = "confirmed"
+
+# Directory generated test files are written to (--output).
+# output = "tests"
+
+# Test framework of the generated files. Only "mocha" (Anchor's default) is supported.
+# framework = "mocha"
+
+# Keypair that signs on-chain processing, instead of prompting for it (--wallet).
+# wallet = "~/.config/solana/id.json"
+"#;
+
+/// Settings from `solify.toml`. Unset values fall back to the CLI defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    pub rpc_url: Option<String>,
+    pub commitment: Option<String>,
+    pub output: Option<PathBuf>,
+    pub framework: Option<String>,
+    pub wallet: Option<PathBuf>,
+}
+
+impl ConfigFile {
+    /// Parses the config at `path`, or returns the empty config when the file
+    /// does not exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {:?}", path))?;
+        Self::parse(&contents).with_context(|| format!("Invalid config file {:?}", path))
+    }
+
+    pub fn parse(contents: &str) -> Result<Self> {
+        let config: Self = toml::from_str(contents)?;
+        config.commitment()?;
+        config.framework()?;
+        Ok(config)
+    }
+
+    pub fn commitment(&self) -> Result<Option<CommitmentConfig>> {
+        self.commitment.as_deref().map(parse_commitment).transpose()
+    }
+
+    pub fn framework(&self) -> Result<&str> {
+        match self.framework.as_deref() {
+            None => Ok(DEFAULT_FRAMEWORK),
+            Some(DEFAULT_FRAMEWORK) => Ok(DEFAULT_FRAMEWORK),
+            Some(other) => anyhow::bail!(
+                "Unsupported framework '{}'; solify only generates {} tests",
+                other,
+                DEFAULT_FRAMEWORK
+            ),
+        }
+    }
+
+    /// `--output` wins over the config file, which wins over [`DEFAULT_OUTPUT_DIR`].
+    pub fn output_dir(&self, flag: Option<PathBuf>) -> PathBuf {
+        flag.or_else(|| self.output.clone()).unwrap_or_else(|| PathBuf::from(DEFAULT_OUTPUT_DIR))
+    }
+
+    /// `--wallet` wins over the config file; `~` is expanded in either.
+    pub fn wallet_path(&self, flag: Option<PathBuf>) -> Option<PathBuf> {
+        flag.or_else(|| self.wallet.clone())
+            .map(|path| PathBuf::from(shellexpand::tilde(&path.to_string_lossy()).to_string()))
+    }
+}
+
+/// Parses a commitment level, as written in the config file or passed to `--commitment`.
+pub fn parse_commitment(level: &str) -> Result<CommitmentConfig> {
+    CommitmentLevel::from_str(level)
+        .map(|commitment| CommitmentConfig { commitment })
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "Unknown commitment '{}', expected processed, confirmed or finalized",
+                level
+            )
+        })
+}
+
+/// Writes [`DEFAULT_CONFIG_TEMPLATE`] to `path`, refusing to replace an existing
+/// config unless `force` is set.
+pub fn init_config(path: &Path, force: bool) -> Result<()> {
+    if path.exists() && !force {
+        anyhow::bail!("{} already exists; pass --force to replace it", path.display());
+    }
+    fs::write(path, DEFAULT_CONFIG_TEMPLATE)
+        .with_context(|| format!("Failed to write config file {:?}", path))
+}
@@ -1,15 +1,50 @@
+#[cfg(feature = "tui")]
 pub mod tui;
 pub mod commands;
+pub mod config;
 pub mod utils;
 
+#[cfg(test)]
+mod tests;
+
 pub use utils::*;
 
+use solana_client::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
+use solify_client::rpc_client_with_headers;
+
+use crate::config::ConfigFile;
+
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const DEFAULT_RPC_URL: &str = "https://api.devnet.solana.com";
 
+/// Well-known Solana clusters selectable with `--network`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Network {
+    Devnet,
+    Mainnet,
+    Testnet,
+    Localnet,
+}
+
+impl Network {
+    pub fn rpc_url(&self) -> &'static str {
+        match self {
+            Network::Devnet => "https://api.devnet.solana.com",
+            Network::Mainnet => "https://api.mainnet-beta.solana.com",
+            Network::Testnet => "https://api.testnet.solana.com",
+            Network::Localnet => "http://127.0.0.1:8899",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CliConfig {
     pub rpc_url: String,
+    /// Extra HTTP headers from `--rpc-header`, sent with every RPC request.
+    pub rpc_headers: Vec<(String, String)>,
+    /// Commitment from `--commitment` or `solify.toml`; each command picks its own otherwise.
+    pub commitment: Option<CommitmentConfig>,
     pub verbose: bool,
 }
 
@@ -17,6 +52,8 @@ impl Default for CliConfig {
     fn default() -> Self {
         Self {
             rpc_url: DEFAULT_RPC_URL.to_string(),
+            rpc_headers: Vec::new(),
+            commitment: None,
             verbose: false,
         }
     }
@@ -24,7 +61,81 @@ impl Default for CliConfig {
 
 impl CliConfig {
     pub fn new(rpc_url: String, verbose: bool) -> Self {
-        Self { rpc_url, verbose }
+        Self { rpc_url, rpc_headers: Vec::new(), commitment: None, verbose }
+    }
+
+    /// Adds the `--rpc-header` values to the config.
+    pub fn with_rpc_headers(mut self, rpc_headers: Vec<(String, String)>) -> Self {
+        self.rpc_headers = rpc_headers;
+        self
+    }
+
+    /// The endpoint commands connect to.
+    pub fn rpc(&self) -> RpcEndpoint {
+        RpcEndpoint {
+            url: self.rpc_url.clone(),
+            headers: self.rpc_headers.clone(),
+            commitment: self.commitment,
+        }
+    }
+
+    /// Resolves the RPC URL: an explicit `--rpc-url` wins over `--network`,
+    /// which wins over the default cluster.
+    pub fn from_args(rpc_url: Option<String>, network: Option<Network>, verbose: bool) -> Self {
+        let rpc_url = rpc_url
+            .or_else(|| network.map(|network| network.rpc_url().to_string()))
+            .unwrap_or_else(|| DEFAULT_RPC_URL.to_string());
+        Self::new(rpc_url, verbose)
+    }
+
+    /// Like [`CliConfig::from_args`], but falls back to `solify.toml` before the
+    /// built-in defaults. Flags always win over the config file.
+    pub fn resolve(
+        rpc_url: Option<String>,
+        network: Option<Network>,
+        commitment: Option<CommitmentConfig>,
+        verbose: bool,
+        file: &ConfigFile
+    ) -> anyhow::Result<Self> {
+        let rpc_url = rpc_url
+            .or_else(|| network.map(|network| network.rpc_url().to_string()))
+            .or_else(|| file.rpc_url.clone());
+        let mut config = Self::from_args(rpc_url, None, verbose);
+        config.commitment = match commitment {
+            Some(commitment) => Some(commitment),
+            None => file.commitment()?,
+        };
+        Ok(config)
+    }
+}
+
+/// An RPC URL together with the extra headers to send to it, such as the API key
+/// of a private endpoint.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RpcEndpoint {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    /// Overrides the commitment a command would otherwise use.
+    pub commitment: Option<CommitmentConfig>,
+}
+
+impl RpcEndpoint {
+    /// Connects with the configured commitment, or `default` when none is set.
+    pub fn client(&self, default: CommitmentConfig) -> anyhow::Result<RpcClient> {
+        rpc_client_with_headers(&self.url, self.commitment(default), &self.headers)
+    }
+
+    pub fn commitment(&self, default: CommitmentConfig) -> CommitmentConfig {
+        self.commitment.unwrap_or(default)
     }
 }
 
+/// Parses a `--rpc-header KEY=VALUE` argument.
+pub fn parse_rpc_header(arg: &str) -> Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.trim().to_string()))
+        }
+        _ => Err(format!("expected KEY=VALUE, got '{}'", arg)),
+    }
+}
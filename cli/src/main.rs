@@ -1,9 +1,16 @@
 use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use tracing_subscriber::EnvFilter;
 
-use solify::commands::{gen_test, inspect};
+use solify::commands::{doctor, estimate, gen_test, inspect, show_metadata};
+use solify::config::{init_config, parse_commitment, ConfigFile, CONFIG_FILE_NAME};
+use solify::{parse_rpc_header, CliConfig, Network};
+use solana_commitment_config::CommitmentConfig;
+use solify_analyzer::FuzzConfig;
+use solify_generator::{GenerateOptions, GroupBy, OverwritePolicy};
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 const ABOUT: &str = "Solify - A CLI tool to generate anchor program tests";
@@ -19,44 +26,285 @@ struct Cli {
     #[arg(short, long, global = true)]
     verbose: bool,
 
-    #[arg(long, global = true, default_value = "https://api.devnet.solana.com")]
-    rpc_url: String,
+    #[arg(long, global = true, help = "RPC endpoint URL (overrides --network) [default: devnet]")]
+    rpc_url: Option<String>,
+
+    #[arg(long, global = true, value_enum, help = "Cluster to connect to")]
+    network: Option<Network>,
+
+    #[arg(long = "rpc-header", global = true, value_name = "KEY=VALUE", value_parser = parse_rpc_header, help = "Extra HTTP header sent with every RPC request, e.g. an API key (repeatable)")]
+    rpc_headers: Vec<(String, String)>,
+
+    #[arg(long, global = true, value_name = "LEVEL", value_parser = parse_commitment_arg, help = "Commitment for RPC requests: processed, confirmed or finalized")]
+    commitment: Option<CommitmentConfig>,
+
+    #[arg(long, global = true, value_name = "PATH", default_value = CONFIG_FILE_NAME, help = "Config file supplying defaults for these flags")]
+    config: PathBuf,
+}
+
+fn parse_commitment_arg(level: &str) -> Result<CommitmentConfig, String> {
+    parse_commitment(level).map_err(|e| e.to_string())
 }
 
+// Parsed once per run, so the size of the GenTest variant does not matter.
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 enum Commands {
     Inspect {
         signature: String,
+        #[arg(long, help = "Show more of each parsed instruction's info")]
+        detailed: bool,
+        #[arg(long, value_name = "N", help = "Maximum nesting depth rendered for parsed instruction info")]
+        depth: Option<usize>,
+        #[arg(long, help = "Print a JSON transaction summary instead of opening the inspector")]
+        json: bool,
     },
     GenTest {
         #[arg(short, long, default_value = "target/idl", help = "Path to IDL file or directory containing IDL files")]
         idl: PathBuf,
-        #[arg(short = 'o', long, default_value = "tests", help = "Output directory for generated test files")]
-        output: PathBuf,
+        #[arg(short = 'o', long, help = "Output directory for generated test files [default: tests]")]
+        output: Option<PathBuf>,
         #[arg(long, help = "Use off-chain computation instead of on-chain processing")]
         off: bool,
-    }
+        #[arg(long, value_name = "N", requires = "off", help = "Add N positive cases per instruction with random values inside each argument's range")]
+        fuzz: Option<usize>,
+        #[arg(long, requires = "fuzz", help = "Seed for --fuzz values; the same seed generates the same tests [default: random]")]
+        seed: Option<u64>,
+        #[arg(long, help = "Skip generation of negative test cases")]
+        no_negative: bool,
+        #[arg(long, help = "Write shared helpers/setup files and one test file per instruction")]
+        split: bool,
+        #[arg(long, help = "Only emit keypair, airdrop and PDA setup, without instruction tests")]
+        init_only: bool,
+        #[arg(long = "view", value_name = "INSTRUCTION", help = "Call INSTRUCTION with .view() and assert on its return value (repeatable; getters are detected automatically)")]
+        view_instructions: Vec<String>,
+        #[arg(long, help = "Overwrite test files that already exist")]
+        force: bool,
+        #[arg(long, conflicts_with = "force", help = "Rename test files that already exist to <file>.bak before writing")]
+        backup: bool,
+        #[arg(long, value_name = "PATH", help = "Write a JSON summary of the generated tests to PATH")]
+        report_json: Option<PathBuf>,
+        #[arg(long, help = "Generate tests for every IDL file in the --idl directory")]
+        all: bool,
+        #[arg(long, value_name = "N", default_value_t = 4, requires = "all", help = "Number of programs --all processes at once")]
+        concurrency: usize,
+        #[arg(long, help = "Paraphrase used to derive the on-chain test metadata account (max 32 bytes)")]
+        paraphrase: Option<String>,
+        #[arg(long, value_name = "PATH", help = "Wallet keypair that signs on-chain processing, instead of prompting for it")]
+        wallet: Option<PathBuf>,
+        #[arg(long = "additional-signer", value_name = "PATH", conflicts_with_all = ["off", "metadata"], help = "Keypair that co-signs on-chain processing with the wallet; repeat for several")]
+        additional_signers: Vec<PathBuf>,
+        #[arg(long, conflicts_with = "all", help = "Print a summary tree of the generated test metadata")]
+        summary: bool,
+        #[arg(long, num_args = 2, value_names = ["md|csv", "PATH"], conflicts_with = "all", help = "Write a table of every generated test case")]
+        catalog: Option<Vec<String>>,
+        #[arg(long, requires = "program", conflicts_with = "all", help = "Fetch the IDL the program published on-chain instead of reading --idl")]
+        from_chain: bool,
+        #[arg(long, value_name = "PROGRAM_ID", requires = "from_chain", help = "Program whose on-chain IDL --from-chain fetches")]
+        program: Option<String>,
+        #[arg(long, value_name = "PROGRAM_ID", conflicts_with = "all", help = "Generate tests that call the program deployed at PROGRAM_ID, embedding the IDL instead of using anchor.workspace")]
+        program_id: Option<String>,
+        #[arg(long, value_name = "FILE", help = "JSON file mapping instruction.account to the TypeScript expression passed for it")]
+        accounts: Option<PathBuf>,
+        #[arg(long, value_name = "FILE", help = "JSON file mapping instruction names to the remaining accounts passed to them")]
+        remaining_accounts: Option<PathBuf>,
+        #[arg(long, conflicts_with = "all", help = "Program name for the test files, for IDLs without metadata.name")]
+        name: Option<String>,
+        #[arg(long, conflicts_with_all = ["all", "summary", "report_json", "catalog"], help = "Print the generated tests to stdout instead of writing files")]
+        stdout: bool,
+        #[arg(long, help = "Declare the IDL's constants in the tests and use them for matching PDA seeds")]
+        constants: bool,
+        #[arg(long, help = "Declare the IDL's defined types as TypeScript interfaces and union types in the tests")]
+        emit_types: bool,
+        #[arg(long, help = "Add a test checking each setup PDA against the seeds the program's IDL declares")]
+        assert_pdas: bool,
+        #[arg(long, help = "Fail the tests up front when the deployed program's IDL version differs from the generating IDL's")]
+        check_version: bool,
+        #[arg(long, help = "Assert in positive tests that each IDL event named in an instruction's docs is emitted")]
+        assert_events: bool,
+        #[arg(long, help = "Create the tests' keypairs from fixed seeds derived from their names, for reproducible runs (test keys only)")]
+        deterministic_keys: bool,
+        #[arg(long, value_enum, value_name = "GROUPING", default_value_t = GroupBy::None, help = "Group the tests of the single test file: `instruction` nests a describe per instruction, `none` keeps them flat")]
+        group_by: GroupBy,
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["all", "off", "paraphrase", "wallet"], help = "Generate from test metadata saved as JSON, skipping analysis and on-chain processing")]
+        metadata: Option<PathBuf>,
+    },
+    /// Check whether an IDL fits the limits of on-chain processing
+    Estimate {
+        #[arg(short, long, default_value = "target/idl", help = "Path to IDL file or directory containing IDL files")]
+        idl: PathBuf,
+    },
+    /// Show the test metadata stored on-chain for a program
+    ShowMetadata {
+        #[arg(long, value_name = "PROGRAM_ID", help = "Program the metadata was generated for")]
+        program: String,
+        #[arg(long, help = "Paraphrase the metadata was stored under")]
+        paraphrase: String,
+        #[arg(long, value_name = "PUBKEY", help = "Wallet that stored the metadata [default: the --wallet pubkey]")]
+        authority: Option<String>,
+        #[arg(long, value_name = "PATH", conflicts_with = "authority", help = "Wallet keypair whose pubkey stored the metadata")]
+        wallet: Option<PathBuf>,
+    },
+    /// Check the RPC endpoint, wallet and program deployments solify relies on
+    Doctor {
+        #[arg(long, value_name = "PATH", help = "Wallet keypair to check [default: ~/.config/solana/id.json]")]
+        wallet: Option<PathBuf>,
+        #[arg(long, value_name = "PROGRAM_ID", help = "Also check that PROGRAM_ID is deployed")]
+        program: Option<String>,
+    },
+    /// Write a commented default solify.toml
+    InitConfig {
+        #[arg(default_value = CONFIG_FILE_NAME, help = "Where to write the config file")]
+        path: PathBuf,
+        #[arg(long, help = "Replace an existing config file")]
+        force: bool,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
+    let cli = Cli::parse();
+    let default_filter = if cli.verbose { "debug" } else { "info" };
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_filter))
+        )
+        .with_writer(std::io::stderr)
         .init();
 
-    let cli = Cli::parse();
-    if cli.verbose {
+    if let Commands::InitConfig { path, force } = &cli.command {
+        init_config(path, *force)?;
+        println!("Wrote {}", path.display());
+        return Ok(());
+    }
+
+    let file = ConfigFile::load(&cli.config)?;
+    file.framework()?;
+    let config = CliConfig::resolve(cli.rpc_url, cli.network, cli.commitment, cli.verbose, &file)?
+        .with_rpc_headers(cli.rpc_headers);
+    let rpc = config.rpc();
+    if config.verbose {
         log::info!("Verbose mode enabled");
     }
 
     match cli.command {
         Commands::Inspect {
             signature,
+            detailed,
+            depth,
+            json,
         } => {
-            inspect::execute(signature, &cli.rpc_url).await?;
+            let limits = inspect::JsonFormatLimits::from_flags(detailed, depth);
+            inspect::execute(signature, &rpc, limits, json).await?;
+        }
+        Commands::GenTest {
+            idl,
+            output,
+            off,
+            fuzz,
+            seed,
+            no_negative,
+            split,
+            init_only,
+            view_instructions,
+            force,
+            backup,
+            report_json,
+            all,
+            concurrency,
+            paraphrase,
+            wallet,
+            additional_signers,
+            summary,
+            catalog,
+            from_chain,
+            program,
+            program_id,
+            accounts,
+            remaining_accounts,
+            name,
+            stdout,
+            constants,
+            emit_types,
+            assert_pdas,
+            check_version,
+            assert_events,
+            deterministic_keys,
+            group_by,
+            metadata,
+        } => {
+            let overwrite = match (force, backup) {
+                (true, _) => OverwritePolicy::Force,
+                (_, true) => OverwritePolicy::Backup,
+                _ => OverwritePolicy::Refuse,
+            };
+            let mut options = GenerateOptions {
+                negative_cases: !no_negative,
+                split,
+                init_only,
+                view_instructions,
+                overwrite,
+                program_name: name,
+                stdout,
+                constants,
+                emit_types,
+                assert_pdas,
+                check_idl_version: check_version,
+                group_by,
+                assert_events,
+                deterministic_keys,
+                ..Default::default()
+            };
+            let output = file.output_dir(output);
+            let off_chain = off.then(|| gen_test::OffChainOptions {
+                fuzz: fuzz.map(|cases| {
+                    let seed = seed.unwrap_or_else(|| {
+                        SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_nanos() as u64)
+                    });
+                    eprintln!("Fuzzing with --seed {}", seed);
+                    FuzzConfig { cases, seed }
+                }),
+            });
+            let on_chain = gen_test::OnChainOptions { wallet: file.wallet_path(wallet), paraphrase, additional_signers };
+            let idl = match program {
+                Some(program_id) if from_chain => gen_test::fetch_chain_idl(&rpc, &program_id)?,
+                _ => idl,
+            };
+            if let Some(accounts) = accounts {
+                options.account_overrides = gen_test::load_account_overrides(&accounts)?;
+            }
+            if let Some(remaining_accounts) = remaining_accounts {
+                options.remaining_accounts = gen_test::load_remaining_accounts(&remaining_accounts)?;
+            }
+            if let Some(program_id) = program_id {
+                options.deployed_program = Some(gen_test::deployed_program(idl.clone(), &program_id)?);
+            }
+            if all {
+                let batch = gen_test::BatchOptions { report_json, concurrency };
+                gen_test::execute_all(idl, output, &rpc, off_chain, options, batch, on_chain).await?;
+            } else {
+                let catalog = match catalog.as_deref() {
+                    Some([format, path]) => Some((format.parse()?, PathBuf::from(path))),
+                    _ => None,
+                };
+                let report = gen_test::ReportOptions { json: report_json, summary, catalog };
+                match metadata {
+                    Some(metadata) => {
+                        gen_test::execute_from_metadata(idl, &metadata, output, options, report)?;
+                    }
+                    None => gen_test::execute(idl, output, &rpc, off_chain, options, report, on_chain).await?,
+                }
+            }
+        }
+        Commands::Estimate { idl } => estimate::execute(idl)?,
+        Commands::ShowMetadata { program, paraphrase, authority, wallet } => {
+            let wallet = file.wallet_path(wallet);
+            show_metadata::execute(program, paraphrase, authority, wallet, &rpc).await?;
         }
-        Commands::GenTest { idl, output, off } => {
-            gen_test::execute(idl, output, &cli.rpc_url, off).await?;
+        Commands::Doctor { wallet, program } => {
+            doctor::execute(file.wallet_path(wallet), program, &rpc)?;
         }
+        Commands::InitConfig { .. } => unreachable!("handled before loading the config"),
     }
     Ok(())
 }
@@ -0,0 +1,1074 @@
+use std::path::{Path, PathBuf};
+
+use solify_analyzer::DependencyAnalyzer;
+use solify_parser::{get_program_id, parse_idl};
+
+use solify_generator::GenerateOptions;
+
+use crate::commands::gen_test::{
+    generate_batch, resolve_processing, run_bounded, write_report_json, GenerationReport,
+    OffChainOptions, OnChainOptions, Processing,
+};
+use crate::RpcEndpoint;
+
+fn sample_idl(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../parser/idls").join(name)
+}
+
+#[test]
+fn test_report_json_counts() {
+    let idl_path = sample_idl("counter_program.json");
+    let idl_data = parse_idl(&idl_path).unwrap();
+    let program_id = get_program_id(&idl_path).unwrap();
+    let execution_order: Vec<String> = idl_data.instructions.iter().map(|i| i.name.clone()).collect();
+    let metadata = DependencyAnalyzer::new()
+        .analyze_dependencies(&idl_data, &execution_order, program_id)
+        .unwrap();
+
+    let out_dir = tempfile::tempdir().unwrap();
+    let report_path = out_dir.path().join("report.json");
+    let report = GenerationReport::from_metadata(&metadata, Path::new("tests/counter.ts"));
+    write_report_json(&report, &report_path).unwrap();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&report_path).unwrap()).unwrap();
+    assert_eq!(json["instruction_count"], execution_order.len());
+    assert_eq!(json["pda_count"], metadata.pda_init_sequence.len());
+    assert_eq!(json["setup_requirement_count"], metadata.setup_requirements.len());
+    assert_eq!(json["output_file"], "tests/counter.ts");
+    assert_eq!(json["skipped"], serde_json::json!([]));
+
+    let instructions = json["instructions"].as_array().unwrap();
+    assert_eq!(instructions.len(), metadata.test_cases.len());
+    for (entry, test_case) in instructions.iter().zip(&metadata.test_cases) {
+        assert_eq!(entry["name"], test_case.instruction_name.as_str());
+        assert_eq!(entry["positive_cases"], test_case.positive_cases.len());
+        assert_eq!(entry["negative_cases"], test_case.negative_cases.len());
+    }
+}
+
+#[tokio::test]
+async fn test_generate_batch_writes_one_file_per_idl() {
+    let idl_dir = tempfile::tempdir().unwrap();
+    for name in ["counter_program.json", "journal.json"] {
+        std::fs::copy(sample_idl(name), idl_dir.path().join(name)).unwrap();
+    }
+    std::fs::write(idl_dir.path().join("broken.json"), "{ not an idl").unwrap();
+
+    let out_dir = tempfile::tempdir().unwrap();
+    let summary = generate_batch(
+        idl_dir.path(),
+        out_dir.path(),
+        &Processing::OffChain(OffChainOptions::default()),
+        &GenerateOptions::default(),
+        2,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(summary.generated.len(), 2);
+    assert_eq!(summary.failed.len(), 1);
+    assert!(summary.failed[0].0.ends_with("broken.json"));
+    for report in &summary.generated {
+        assert!(report.output_file.exists(), "Missing {:?}", report.output_file);
+    }
+    let written = std::fs::read_dir(out_dir.path()).unwrap().count();
+    assert_eq!(written, 2);
+}
+
+#[tokio::test]
+async fn test_off_chain_generation_needs_no_wallet_or_rpc() {
+    // Nothing listens on this endpoint and no wallet is configured; a prompt for
+    // either would fail without a terminal.
+    let rpc = RpcEndpoint { url: "http://127.0.0.1:1".to_string(), headers: Vec::new(), commitment: None };
+    let processing = resolve_processing(&rpc, Some(OffChainOptions::default()), OnChainOptions::default()).unwrap();
+    assert!(matches!(processing, Processing::OffChain(_)));
+
+    let idl_dir = tempfile::tempdir().unwrap();
+    std::fs::copy(sample_idl("counter_program.json"), idl_dir.path().join("counter_program.json")).unwrap();
+    let out_dir = tempfile::tempdir().unwrap();
+    let summary = generate_batch(idl_dir.path(), out_dir.path(), &processing, &GenerateOptions::default(), 1)
+        .await
+        .unwrap();
+
+    assert!(summary.failed.is_empty(), "Unexpected failures: {:?}", summary.failed);
+    assert!(summary.generated[0].output_file.exists());
+}
+
+#[tokio::test]
+async fn test_run_bounded_limits_concurrent_tasks() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    // Each task stands in for an RPC round trip that takes a while to answer.
+    let active = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let (active_in_task, peak_in_task) = (active.clone(), peak.clone());
+    let results = run_bounded((0..8).collect(), 3, move |i: usize| {
+        let running = active_in_task.fetch_add(1, Ordering::SeqCst) + 1;
+        peak_in_task.fetch_max(running, Ordering::SeqCst);
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        active_in_task.fetch_sub(1, Ordering::SeqCst);
+        i * 10
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(results, [0, 10, 20, 30, 40, 50, 60, 70]);
+    assert_eq!(peak.load(Ordering::SeqCst), 3);
+}
+
+#[cfg(feature = "tui")]
+mod inspect {
+    use serde_json::json;
+    use solana_transaction_status::{UiLoadedAddresses, UiRawMessage, UiTransactionTokenBalance};
+
+    use crate::commands::inspect::{
+        build_token_balance_map,
+        format_json_value,
+        needs_redraw,
+        raw_account_roles,
+        summarize_transaction,
+        AccountSource,
+        JsonFormatLimits,
+    };
+    use crate::tui::AppEvent;
+
+    fn v0_message_with_lookup_table() -> (UiRawMessage, UiLoadedAddresses) {
+        let message = serde_json::from_value(json!({
+            "header": {
+                "numRequiredSignatures": 2,
+                "numReadonlySignedAccounts": 1,
+                "numReadonlyUnsignedAccounts": 1
+            },
+            "accountKeys": ["payer", "cosigner", "vault", "program"],
+            "recentBlockhash": "11111111111111111111111111111111",
+            "instructions": [],
+            "addressTableLookups": [{
+                "accountKey": "table",
+                "writableIndexes": [0],
+                "readonlyIndexes": [1, 2]
+            }]
+        }))
+        .unwrap();
+        let loaded = serde_json::from_value(json!({
+            "writable": ["lut_writable"],
+            "readonly": ["lut_readonly_a", "lut_readonly_b"]
+        }))
+        .unwrap();
+        (message, loaded)
+    }
+
+    #[test]
+    fn test_raw_account_roles_include_lookup_table_addresses() {
+        let (message, loaded) = v0_message_with_lookup_table();
+        let roles = raw_account_roles(&message, Some(&loaded));
+
+        let flags: Vec<(&str, bool, bool, AccountSource)> = roles
+            .iter()
+            .map(|role| (role.pubkey.as_str(), role.is_signer, role.is_writable, role.source))
+            .collect();
+        assert_eq!(flags, vec![
+            ("payer", true, true, AccountSource::Transaction),
+            ("cosigner", true, false, AccountSource::Transaction),
+            ("vault", false, true, AccountSource::Transaction),
+            ("program", false, false, AccountSource::Transaction),
+            ("lut_writable", false, true, AccountSource::LookupTable),
+            ("lut_readonly_a", false, false, AccountSource::LookupTable),
+            ("lut_readonly_b", false, false, AccountSource::LookupTable),
+        ]);
+    }
+
+    #[test]
+    fn test_token_balances_attach_to_raw_accounts_by_runtime_index() {
+        let (message, loaded) = v0_message_with_lookup_table();
+        let roles = raw_account_roles(&message, Some(&loaded));
+        let balance = |index: u8, amount: &str, ui_amount: f64| json!({
+            "accountIndex": index,
+            "mint": "mint",
+            "uiTokenAmount": { "uiAmount": ui_amount, "decimals": 0, "amount": amount, "uiAmountString": amount }
+        });
+        // A static account and a lookup table account, listed out of order.
+        let pre: Vec<UiTransactionTokenBalance> = serde_json::from_value(json!([balance(4, "10", 10.0), balance(2, "5", 5.0)])).unwrap();
+        let post: Vec<UiTransactionTokenBalance> = serde_json::from_value(json!([balance(2, "8", 8.0), balance(4, "7", 7.0)])).unwrap();
+
+        let mut map = build_token_balance_map(&pre, &post);
+        let attributed: Vec<(&str, Vec<String>)> = roles
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, role)| Some((role.pubkey.as_str(), map.remove(&(idx as u8))?)))
+            .collect();
+        assert_eq!(attributed, vec![
+            ("vault", vec!["Token mint: 5 → 8 (Δ +3)".to_string()]),
+            ("lut_writable", vec!["Token mint: 10 → 7 (Δ -3)".to_string()]),
+        ]);
+    }
+
+    #[test]
+    fn test_raw_account_roles_without_lookup_tables() {
+        let (message, _) = v0_message_with_lookup_table();
+        let roles = raw_account_roles(&message, None);
+        assert_eq!(roles.len(), 4);
+        assert!(roles.iter().all(|role| role.source == AccountSource::Transaction));
+    }
+
+    fn large_instruction_info() -> serde_json::Value {
+        let accounts: Vec<_> = (0..30).map(|i| json!({ "pubkey": format!("account{}", i) })).collect();
+        json!({ "accounts": accounts, "authority": { "multisig": { "signers": ["a", "b"] } } })
+    }
+
+    #[test]
+    fn test_format_json_value_default_cap_truncates() {
+        let lines = format_json_value(&large_instruction_info(), 0, JsonFormatLimits::default());
+        assert_eq!(lines.len(), 17);
+        assert_eq!(lines.last().unwrap(), "...");
+    }
+
+    #[test]
+    fn test_format_json_value_respects_larger_cap() {
+        let lines = format_json_value(&large_instruction_info(), 0, JsonFormatLimits::detailed());
+        assert!(!lines.iter().any(|line| line == "..."));
+        assert!(lines.iter().any(|line| line.trim() == "pubkey: account29"));
+        assert!(lines.iter().any(|line| line.trim() == "- a"));
+    }
+
+    #[test]
+    fn test_format_json_value_collapses_below_depth() {
+        let limits = JsonFormatLimits::from_flags(true, Some(2));
+        let lines = format_json_value(&large_instruction_info(), 0, limits);
+        assert!(lines.iter().any(|line| line.trim() == "multisig: {1 fields}"));
+        assert!(!lines.iter().any(|line| line.trim() == "- a"));
+    }
+
+    #[test]
+    fn test_needs_redraw_only_when_state_changes() {
+        let scroll = [0, 3, 0];
+        assert!(!needs_redraw(&AppEvent::Tick, scroll, scroll));
+        assert!(!needs_redraw(&AppEvent::Char('r'), scroll, scroll));
+        assert!(!needs_redraw(&AppEvent::MouseScroll { up: true, column: 0, row: 0 }, scroll, scroll));
+        assert!(needs_redraw(&AppEvent::MouseScroll { up: false, column: 0, row: 0 }, scroll, [0, 4, 0]));
+        assert!(needs_redraw(&AppEvent::Resize, scroll, scroll));
+    }
+
+    fn confirmed_transaction_fixture() -> serde_json::Value {
+        let (message, loaded) = v0_message_with_lookup_table();
+        let mut message = serde_json::to_value(message).unwrap();
+        message["instructions"] = json!([
+            { "programIdIndex": 3, "accounts": [0, 2], "data": "3Bxs4h24hBtQy9rw", "stackHeight": null }
+        ]);
+        json!({
+            "slot": 42,
+            "blockTime": 1_700_000_000,
+            "transaction": {
+                "signatures": ["sig"],
+                "message": message
+            },
+            "meta": {
+                "err": null,
+                "status": { "Ok": null },
+                "fee": 5000,
+                "preBalances": [1_000_000, 0, 500, 1, 0, 0, 0],
+                "postBalances": [989_000, 0, 6_500, 1, 0, 0, 0],
+                "loadedAddresses": loaded,
+                "computeUnitsConsumed": 1234
+            },
+            "version": 0
+        })
+    }
+
+    #[test]
+    fn test_summarize_transaction_exposes_typed_fields() {
+        let tx = serde_json::from_value(confirmed_transaction_fixture()).unwrap();
+        let summary = summarize_transaction("sig", &tx).unwrap();
+
+        assert_eq!(summary.signature, "sig");
+        assert_eq!(summary.slot, 42);
+        assert_eq!(summary.block_time, Some(1_700_000_000));
+        assert_eq!(summary.fee, 5000);
+        assert_eq!(summary.compute_units, Some(1234));
+        assert_eq!(summary.status, Ok(()));
+        assert_eq!(summary.instruction_count, 1);
+        assert_eq!(summary.balance_deltas.len(), 7);
+        assert_eq!(summary.balance_deltas[0].pubkey, "payer");
+        assert_eq!(summary.balance_deltas[0].delta, -11_000);
+        assert_eq!(summary.balance_deltas[2].delta, 6_000);
+        assert_eq!(summary.balance_deltas[4].pubkey, "lut_writable");
+    }
+}
+
+mod network {
+    use crate::{parse_rpc_header, CliConfig, Network, RpcEndpoint, DEFAULT_RPC_URL};
+
+    #[test]
+    fn test_network_maps_to_cluster_url() {
+        let cases = [
+            (Network::Devnet, "https://api.devnet.solana.com"),
+            (Network::Mainnet, "https://api.mainnet-beta.solana.com"),
+            (Network::Testnet, "https://api.testnet.solana.com"),
+            (Network::Localnet, "http://127.0.0.1:8899"),
+        ];
+        for (network, url) in cases {
+            assert_eq!(CliConfig::from_args(None, Some(network), false).rpc_url, url);
+        }
+    }
+
+    #[test]
+    fn test_rpc_url_overrides_network() {
+        let config = CliConfig::from_args(
+            Some("https://my-rpc.example.com".to_string()),
+            Some(Network::Mainnet),
+            false,
+        );
+        assert_eq!(config.rpc_url, "https://my-rpc.example.com");
+    }
+
+    #[test]
+    fn test_default_rpc_url() {
+        assert_eq!(CliConfig::from_args(None, None, false).rpc_url, DEFAULT_RPC_URL);
+    }
+
+    #[test]
+    fn test_rpc_headers_reach_the_endpoint() {
+        let header = parse_rpc_header("x-api-key=abc=123").unwrap();
+        assert_eq!(header, ("x-api-key".to_string(), "abc=123".to_string()));
+        assert!(parse_rpc_header("x-api-key").is_err());
+        assert!(parse_rpc_header("=value").is_err());
+
+        let config = CliConfig::from_args(Some("https://private.example.com".to_string()), None, false)
+            .with_rpc_headers(vec![header.clone()]);
+        assert_eq!(
+            config.rpc(),
+            RpcEndpoint { url: "https://private.example.com".to_string(), headers: vec![header], commitment: None }
+        );
+    }
+}
+
+mod config {
+    use std::path::PathBuf;
+
+    use solana_commitment_config::CommitmentConfig;
+
+    use crate::config::{init_config, ConfigFile, DEFAULT_CONFIG_TEMPLATE};
+    use crate::{CliConfig, Network};
+
+    const CONFIG: &str = r#"
+rpc_url = "http://127.0.0.1:8899"
+commitment = "finalized"
+output = "generated-tests"
+framework = "mocha"
+wallet = "keys/dev.json"
+"#;
+
+    #[test]
+    fn test_config_values_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("solify.toml");
+        std::fs::write(&path, CONFIG).unwrap();
+        let file = ConfigFile::load(&path).unwrap();
+
+        let config = CliConfig::resolve(None, None, None, false, &file).unwrap();
+        assert_eq!(config.rpc_url, "http://127.0.0.1:8899");
+        assert_eq!(config.rpc().commitment, Some(CommitmentConfig::finalized()));
+        assert_eq!(file.output_dir(None), PathBuf::from("generated-tests"));
+        assert_eq!(file.wallet_path(None), Some(PathBuf::from("keys/dev.json")));
+        assert_eq!(file.framework().unwrap(), "mocha");
+    }
+
+    #[test]
+    fn test_cli_flags_take_precedence_over_config() {
+        let file = ConfigFile::parse(CONFIG).unwrap();
+
+        let config = CliConfig::resolve(
+            Some("https://private.example.com".to_string()),
+            None,
+            Some(CommitmentConfig::processed()),
+            false,
+            &file,
+        )
+        .unwrap();
+        assert_eq!(config.rpc_url, "https://private.example.com");
+        assert_eq!(config.commitment, Some(CommitmentConfig::processed()));
+
+        let config = CliConfig::resolve(None, Some(Network::Mainnet), None, false, &file).unwrap();
+        assert_eq!(config.rpc_url, Network::Mainnet.rpc_url());
+
+        assert_eq!(file.output_dir(Some(PathBuf::from("out"))), PathBuf::from("out"));
+        assert_eq!(
+            file.wallet_path(Some(PathBuf::from("other.json"))),
+            Some(PathBuf::from("other.json"))
+        );
+    }
+
+    #[test]
+    fn test_missing_or_scaffolded_config_changes_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("solify.toml");
+        assert_eq!(ConfigFile::load(&path).unwrap(), ConfigFile::default());
+
+        init_config(&path, false).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), DEFAULT_CONFIG_TEMPLATE);
+        assert_eq!(ConfigFile::load(&path).unwrap(), ConfigFile::default());
+        assert!(init_config(&path, false).is_err());
+        assert_eq!(ConfigFile::default().output_dir(None), PathBuf::from("tests"));
+    }
+
+    #[test]
+    fn test_invalid_config_values_are_rejected() {
+        assert!(ConfigFile::parse("commitment = \"eventually\"").is_err());
+        assert!(ConfigFile::parse("framework = \"jest\"").is_err());
+        assert!(ConfigFile::parse("rpc = \"http://localhost\"").is_err());
+    }
+}
+
+mod pipeline {
+    use solify_analyzer::DependencyAnalyzer;
+    use solify_generator::generate_with_tera;
+    use solify_parser::{get_program_id, parse_idl};
+
+    use super::sample_idl;
+
+    /// Sample IDL, PDAs the analyzer should schedule for initialization. The remaining
+    /// samples use the pre-0.30 IDL layout, which keeps `name` outside `metadata`.
+    const SAMPLES: [(&str, usize); 3] = [
+        ("counter_program.json", 1),
+        ("journal.json", 1),
+        ("voting_dapp.json", 2),
+    ];
+
+    /// Checks that brackets balance outside of string literals and comments, and that
+    /// no template tags leaked into the output.
+    fn assert_ts_shaped(name: &str, source: &str) {
+        assert!(!source.contains("{{") && !source.contains("{%"), "{}: unrendered template tags", name);
+
+        let mut stack = Vec::new();
+        for (number, line) in source.lines().enumerate() {
+            let mut chars = line.chars().peekable();
+            let mut quote = None;
+            while let Some(c) = chars.next() {
+                match quote {
+                    Some(q) => {
+                        if c == '\\' {
+                            chars.next();
+                        } else if c == q {
+                            quote = None;
+                        }
+                    }
+                    None => match c {
+                        '"' | '\'' | '`' => quote = Some(c),
+                        '/' if chars.peek() == Some(&'/') => break,
+                        '(' | '[' | '{' => stack.push(c),
+                        ')' | ']' | '}' => {
+                            let open = stack.pop();
+                            let expected = match c {
+                                ')' => '(',
+                                ']' => '[',
+                                _ => '{',
+                            };
+                            assert_eq!(open, Some(expected), "{}: unbalanced '{}' on line {}", name, c, number + 1);
+                        }
+                        _ => {}
+                    },
+                }
+            }
+        }
+        assert!(stack.is_empty(), "{}: unclosed {:?}", name, stack);
+    }
+
+    #[test]
+    fn test_sample_idls_generate_well_formed_tests() {
+        for (name, expected_pdas) in SAMPLES {
+            let idl_path = sample_idl(name);
+            let idl_data = parse_idl(&idl_path).unwrap();
+            let program_id = get_program_id(&idl_path).unwrap();
+            let execution_order: Vec<String> =
+                idl_data.instructions.iter().map(|i| i.name.clone()).collect();
+
+            let metadata = DependencyAnalyzer::new()
+                .analyze_dependencies(&idl_data, &execution_order, program_id)
+                .unwrap_or_else(|e| panic!("{}: analysis failed: {}", name, e));
+            assert_eq!(metadata.pda_init_sequence.len(), expected_pdas, "{}: PDA count", name);
+            assert_eq!(metadata.test_cases.len(), execution_order.len(), "{}: instruction count", name);
+
+            let out_dir = tempfile::tempdir().unwrap();
+            generate_with_tera(&metadata, &idl_data, out_dir.path())
+                .unwrap_or_else(|e| panic!("{}: generation failed: {}", name, e));
+
+            let files: Vec<_> = std::fs::read_dir(out_dir.path()).unwrap().map(|e| e.unwrap().path()).collect();
+            assert_eq!(files.len(), 1, "{}: expected a single test file, got {:?}", name, files);
+            assert_eq!(files[0].extension().unwrap(), "ts");
+            let rendered = std::fs::read_to_string(&files[0]).unwrap();
+            assert!(!rendered.trim().is_empty(), "{}: empty test file", name);
+
+            // Setup derives most PDAs once; argument-seeded ones are derived in each test.
+            let derived: std::collections::HashSet<&str> = rendered
+                .lines()
+                .filter(|line| line.contains("PublicKey.findProgramAddressSync("))
+                .filter_map(|line| line.split("[pda").nth(1))
+                .filter_map(|rest| rest.split(|c: char| !c.is_ascii_digit()).next())
+                .collect();
+            assert_eq!(derived.len(), expected_pdas, "{}: PDA derivations", name);
+            for test_case in &metadata.test_cases {
+                for positive in &test_case.positive_cases {
+                    let block = format!("it(\"{}\"", positive.description);
+                    assert!(rendered.contains(&block), "{}: missing {}", name, block);
+                }
+            }
+            assert_ts_shaped(name, &rendered);
+        }
+    }
+
+    #[test]
+    fn test_tests_follow_the_instruction_order() {
+        let idl_path = sample_idl("mini_escrow.json");
+        let idl_data = parse_idl(&idl_path).unwrap();
+        let execution_order = vec!["initEscrow".to_string(), "cancelEscrow".to_string()];
+        let mut metadata = DependencyAnalyzer::new()
+            .analyze_dependencies(&idl_data, &execution_order, get_program_id(&idl_path).unwrap())
+            .unwrap();
+        // Metadata assembled elsewhere, e.g. on-chain, may list the cases in any order.
+        metadata.test_cases.reverse();
+
+        let out_dir = tempfile::tempdir().unwrap();
+        generate_with_tera(&metadata, &idl_data, out_dir.path()).unwrap();
+        let test_file = std::fs::read_dir(out_dir.path()).unwrap().next().unwrap().unwrap().path();
+        let rendered = std::fs::read_to_string(test_file).unwrap();
+
+        let first_test = |instruction: &str| {
+            let cases = metadata.test_cases.iter().find(|tc| tc.instruction_name == instruction).unwrap();
+            let block = format!("it(\"{}\"", cases.positive_cases[0].description);
+            rendered.find(&block).unwrap_or_else(|| panic!("missing {}", block))
+        };
+        assert!(
+            first_test("initEscrow") < first_test("cancelEscrow"),
+            "initEscrow tests must run before cancelEscrow tests"
+        );
+    }
+}
+
+mod paraphrase {
+    use std::path::PathBuf;
+
+    use solify_generator::GenerateOptions;
+
+    use crate::commands::gen_test::{execute, validate_paraphrase, OnChainOptions, ReportOptions};
+    use crate::RpcEndpoint;
+
+    #[test]
+    fn test_validate_paraphrase_limits() {
+        assert!(validate_paraphrase("updated").is_ok());
+        assert!(validate_paraphrase(&"a".repeat(32)).is_ok());
+        assert!(validate_paraphrase("").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_long_paraphrase_rejected_before_derivation() {
+        // The IDL path does not exist, so reaching any later step would fail differently.
+        let err = execute(
+            PathBuf::from("does/not/exist.json"),
+            PathBuf::from("tests"),
+            &RpcEndpoint { url: "http://127.0.0.1:8899".to_string(), headers: Vec::new(), commitment: None },
+            None,
+            GenerateOptions::default(),
+            ReportOptions::default(),
+            OnChainOptions { paraphrase: Some("a".repeat(40)), ..Default::default() },
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("limited to 32 bytes"), "Unexpected error: {}", err);
+    }
+}
+
+mod account_overrides {
+    use crate::commands::gen_test::{ load_account_overrides, load_remaining_accounts };
+
+    #[test]
+    fn test_load_account_overrides_groups_by_instruction() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("accounts.json");
+        std::fs::write(
+            &path,
+            r#"{ "transfer.recipient": "recipientKeypair.publicKey", "transfer.mint": "mint" }"#,
+        )
+        .unwrap();
+
+        let overrides = load_account_overrides(&path).unwrap();
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(overrides["transfer"]["recipient"], "recipientKeypair.publicKey");
+        assert_eq!(overrides["transfer"]["mint"], "mint");
+
+        std::fs::write(&path, r#"{ "recipient": "recipientKeypair.publicKey" }"#).unwrap();
+        assert!(load_account_overrides(&path).is_err());
+    }
+
+    #[test]
+    fn test_load_remaining_accounts_by_instruction() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("remaining.json");
+        std::fs::write(
+            &path,
+            r#"{ "settle": [{ "pubkey": "vaultKeypair.publicKey", "is_writable": true, "is_signer": false }] }"#,
+        )
+        .unwrap();
+
+        let remaining = load_remaining_accounts(&path).unwrap();
+        assert_eq!(remaining["settle"].len(), 1);
+        assert_eq!(remaining["settle"][0].pubkey, "vaultKeypair.publicKey");
+        assert!(remaining["settle"][0].is_writable);
+        assert!(!remaining["settle"][0].is_signer);
+
+        std::fs::write(&path, r#"{ "settle": ["vaultKeypair.publicKey"] }"#).unwrap();
+        assert!(load_remaining_accounts(&path).is_err());
+    }
+}
+
+mod summary {
+    use solify_common::{
+        ExpectedOutcome,
+        InstructionTestCases,
+        PdaInit,
+        SeedComponent,
+        SeedType,
+        SetupRequirement,
+        SetupType,
+        TestCase,
+        TestCaseType,
+        TestMetadata,
+    };
+
+    use crate::commands::summary::metadata_summary_lines;
+
+    fn case(description: &str, test_type: TestCaseType) -> TestCase {
+        TestCase {
+            test_type,
+            description: description.to_string(),
+            argument_values: vec![],
+            expected_outcome: ExpectedOutcome::Success { state_changes: vec![] },
+        }
+    }
+
+    pub(super) fn journal_metadata() -> TestMetadata {
+        TestMetadata {
+            instruction_order: vec!["create_entry".to_string(), "delete_entry".to_string()],
+            account_dependencies: vec![],
+            pda_init_sequence: vec![PdaInit {
+                account_name: "journal_entry".to_string(),
+                seeds: vec![
+                    SeedComponent { seed_type: SeedType::Static, value: "journal".to_string() },
+                    SeedComponent { seed_type: SeedType::Argument, value: "title".to_string() },
+                    SeedComponent { seed_type: SeedType::AccountKey, value: "owner".to_string() },
+                ],
+                program_id: "7tvJ6jxJF81pozUSa2o8yPo6zsQCxG4GyF2b6JgaHqaa".to_string(),
+                space: None,
+            }],
+            setup_requirements: vec![
+                SetupRequirement {
+                    requirement_type: SetupType::CreateKeypair,
+                    description: "Create owner keypair".to_string(),
+                    dependencies: vec![],
+                    account_name: None,
+                },
+                SetupRequirement {
+                    requirement_type: SetupType::InitializePda,
+                    description: "Initialize journal_entry".to_string(),
+                    dependencies: vec!["owner".to_string()],
+                    account_name: Some("journal_entry".to_string()),
+                },
+            ],
+            test_cases: vec![
+                InstructionTestCases {
+                    instruction_name: "create_entry".to_string(),
+                    arguments: vec![],
+                    positive_cases: vec![case("create works", TestCaseType::Positive)],
+                    negative_cases: vec![
+                        case("empty title", TestCaseType::NegativeNull),
+                        case("long title", TestCaseType::NegativeBoundary),
+                    ],
+                },
+                InstructionTestCases {
+                    instruction_name: "delete_entry".to_string(),
+                    arguments: vec![],
+                    positive_cases: vec![case("delete works", TestCaseType::Positive)],
+                    negative_cases: vec![],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_metadata_summary_lines() {
+        let expected = [
+            "Test metadata summary",
+            "├─ Instruction order (2)",
+            "│  ├─ 1. create_entry",
+            "│  └─ 2. delete_entry",
+            "├─ Test cases (2)",
+            "│  ├─ create_entry: 1 positive, 2 negative",
+            "│  └─ delete_entry: 1 positive, 0 negative",
+            "├─ PDAs (1)",
+            "│  └─ journal_entry [\"journal\", arg title, account owner]",
+            "└─ Setup steps (2)",
+            "   ├─ 1. CreateKeypair: Create owner keypair",
+            "   └─ 2. InitializePda: Initialize journal_entry",
+        ];
+        assert_eq!(metadata_summary_lines(&journal_metadata()), expected);
+    }
+}
+
+mod show_metadata {
+    use solana_sdk::pubkey::Pubkey;
+    use solify_client::TestMetadataAccount;
+    use solify_common::AccountDependency;
+
+    use crate::commands::show_metadata::metadata_account_lines;
+
+    #[test]
+    fn test_metadata_account_lines() {
+        let mut test_metadata = super::summary::journal_metadata();
+        test_metadata.account_dependencies = vec![AccountDependency {
+            account_name: "journal_entry".to_string(),
+            depends_on: vec!["owner".to_string()],
+            is_pda: true,
+            is_signer: false,
+            is_mut: true,
+            must_be_initialized: true,
+            initialization_order: 1,
+            closed_by: vec![],
+            reallocated_by: vec![],
+        }];
+        let account = TestMetadataAccount {
+            address: Pubkey::new_from_array([3; 32]),
+            authority: Pubkey::new_from_array([2; 32]),
+            program_id: Pubkey::new_from_array([1; 32]),
+            program_name: "journal".to_string(),
+            test_metadata,
+            timestamp: 1_700_000_000,
+        };
+
+        let expected = [
+            "Program:   journal (4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi)",
+            "Authority: 8qbHbw2BbbTHBW1sbeqakYXVKRQM8Ne7pLK7m6CVfeR",
+            "Account:   CktRuQ2mttgRGkXJtyksdKHjUdc2C4TgDzyB98oEzy8",
+            "Updated:   2023-11-14 22:13:20 UTC",
+            "Order:     create_entry → delete_entry",
+            "",
+            "Account dependencies (1)",
+            "  1. journal_entry [pda, mut, initialized] ← owner",
+            "",
+            "PDA sequence (1)",
+            "  1. journal_entry [\"journal\", arg title, account owner]",
+            "",
+            "Setup steps (2)",
+            "  1. CreateKeypair: Create owner keypair",
+            "  2. InitializePda: Initialize journal_entry",
+            "",
+            "Test cases (4)",
+            "  create_entry",
+            "    + create works",
+            "    - empty title",
+            "    - long title",
+            "  delete_entry",
+            "    + delete works",
+        ];
+        assert_eq!(metadata_account_lines(&account), expected);
+    }
+}
+
+mod catalog {
+    use solify_common::{ ExpectedOutcome, InstructionTestCases, TestCase, TestCaseType, TestMetadata };
+
+    use crate::commands::catalog::{ render_catalog, CatalogFormat };
+
+    fn metadata() -> TestMetadata {
+        let case = |description: &str, test_type, expected_outcome| TestCase {
+            test_type,
+            description: description.to_string(),
+            argument_values: vec![],
+            expected_outcome,
+        };
+        TestMetadata {
+            instruction_order: vec!["increment".to_string()],
+            account_dependencies: vec![],
+            pda_init_sequence: vec![],
+            setup_requirements: vec![],
+            test_cases: vec![InstructionTestCases {
+                instruction_name: "increment".to_string(),
+                arguments: vec![],
+                positive_cases: vec![case(
+                    "increment - valid inputs",
+                    TestCaseType::Positive,
+                    ExpectedOutcome::Success { state_changes: vec![] },
+                )],
+                negative_cases: vec![case(
+                    "increment - amount overflows",
+                    TestCaseType::NegativeOverflow,
+                    ExpectedOutcome::Failure {
+                        error_code: Some("6000".to_string()),
+                        error_message: "Overflow, too large".to_string(),
+                    },
+                )],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_markdown_catalog_has_one_row_per_case() {
+        let catalog = render_catalog(&metadata(), CatalogFormat::Markdown);
+        let lines: Vec<&str> = catalog.lines().collect();
+        assert_eq!(lines, [
+            "| Instruction | Case type | Description | Expected outcome | Error code |",
+            "|---|---|---|---|---|",
+            "| increment | Positive | increment - valid inputs | Success |  |",
+            "| increment | NegativeOverflow | increment - amount overflows | Failure: Overflow, too large | 6000 |",
+        ]);
+    }
+
+    #[test]
+    fn test_csv_catalog_quotes_fields_with_commas() {
+        let catalog = render_catalog(&metadata(), CatalogFormat::Csv);
+        let lines: Vec<&str> = catalog.lines().collect();
+        assert_eq!(lines[0], "Instruction,Case type,Description,Expected outcome,Error code");
+        assert_eq!(lines[2], "increment,NegativeOverflow,increment - amount overflows,\"Failure: Overflow, too large\",6000");
+        assert_eq!(lines.len(), 3);
+    }
+
+    #[test]
+    fn test_catalog_format_parses_flag_values() {
+        assert_eq!("md".parse::<CatalogFormat>().unwrap(), CatalogFormat::Markdown);
+        assert_eq!("CSV".parse::<CatalogFormat>().unwrap(), CatalogFormat::Csv);
+        assert!("xlsx".parse::<CatalogFormat>().is_err());
+    }
+}
+
+mod estimate {
+    use solify_common::{IdlAccountItem, IdlField, IdlInstruction};
+    use solify_parser::{get_program_id, parse_idl};
+
+    use super::sample_idl;
+    use crate::commands::estimate::{estimate, estimate_lines};
+
+    #[test]
+    fn test_small_idl_is_within_limits() {
+        let idl_path = sample_idl("counter_program.json");
+        let idl_data = parse_idl(&idl_path).unwrap();
+        let program_id = get_program_id(&idl_path).unwrap();
+
+        let estimate = estimate(&idl_data, &program_id).unwrap();
+        assert!(estimate.within_limits(), "{:?}", estimate);
+        assert_eq!(estimate.instructions.len(), idl_data.instructions.len());
+        assert!(estimate_lines(&estimate).last().unwrap().contains("within limits"));
+    }
+
+    #[test]
+    fn test_huge_idl_is_too_large() {
+        let idl_path = sample_idl("counter_program.json");
+        let mut idl_data = parse_idl(&idl_path).unwrap();
+        let program_id = get_program_id(&idl_path).unwrap();
+        idl_data.instructions = (0..40)
+            .map(|i| IdlInstruction {
+                name: format!("instruction_with_a_long_name_{}", i),
+                accounts: vec![IdlAccountItem {
+                    name: "authority".to_string(),
+                    is_mut: true,
+                    is_signer: true,
+                    is_optional: false,
+                    docs: vec![],
+                    pda: None,
+                    address: None,
+                }],
+                args: vec![IdlField { name: "amount".to_string(), field_type: "u64".to_string() }],
+                docs: vec![],
+                returns: None,
+            })
+            .collect();
+
+        let estimate = estimate(&idl_data, &program_id).unwrap();
+        assert!(!estimate.idl_fits());
+        assert!(!estimate.metadata_fits());
+        let lines = estimate_lines(&estimate);
+        assert!(lines.last().unwrap().contains("too large"), "{:?}", lines);
+        assert_eq!(estimate.instructions.len(), 40);
+    }
+}
+
+mod utils {
+    use crate::utils::truncate_str;
+
+    #[test]
+    fn test_truncate_str_keeps_short_strings() {
+        assert_eq!(truncate_str("counter", 10), "counter");
+        assert_eq!(truncate_str("transfer_tokens", 10), "transfe...");
+    }
+
+    #[test]
+    fn test_truncate_str_cuts_multibyte_text_on_char_boundaries() {
+        assert_eq!(truncate_str("🚀🚀🚀🚀🚀🚀", 5), "🚀🚀...");
+        assert_eq!(truncate_str("añoñoño", 6), "año...");
+        assert_eq!(truncate_str("ñññ", 3), "ñññ");
+    }
+}
+
+mod doctor {
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    use serde_json::json;
+    use solana_client::rpc_client::RpcClient;
+    use solana_client::rpc_request::RpcRequest;
+    use solana_sdk::signature::Keypair;
+
+    use crate::commands::doctor::{ check_line, run_checks, Check };
+
+    fn failed(checks: &[Check]) -> Vec<&str> {
+        checks.iter().filter(|check| !check.passed).map(|check| check.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_doctor_reports_every_failing_check() {
+        // The "fails" mock answers every request with null, so each RPC call errors.
+        let rpc = RpcClient::new_mock("fails".to_string());
+        let checks = run_checks(&rpc, Path::new("/nonexistent/id.json"), Some("not-a-pubkey"));
+
+        assert_eq!(failed(&checks), ["RPC", "Wallet", "Balance", "Solify program", "Program"]);
+        assert!(checks[1].detail.contains("/nonexistent/id.json"), "{}", checks[1].detail);
+        assert!(check_line(&checks[0]).starts_with("✗ RPC: "));
+    }
+
+    #[test]
+    fn test_doctor_fails_an_empty_wallet_and_a_missing_program() {
+        let dir = tempfile::tempdir().unwrap();
+        let wallet = dir.path().join("id.json");
+        let keypair = Keypair::new();
+        std::fs::write(&wallet, serde_json::to_string(&keypair.to_bytes().to_vec()).unwrap()).unwrap();
+
+        let mocks = HashMap::from([
+            (RpcRequest::GetHealth, json!("ok")),
+            (RpcRequest::GetBalance, json!({ "context": { "slot": 1 }, "value": 0 })),
+        ]);
+        let rpc = RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks);
+        let checks = run_checks(&rpc, &wallet, None);
+
+        assert_eq!(failed(&checks), ["Balance", "Solify program"]);
+        assert!(checks[2].detail.contains("0.000000000 SOL"), "{}", checks[2].detail);
+        assert!(check_line(&checks[1]).starts_with("✓ Wallet: "));
+    }
+}
+
+mod metadata_file {
+    use std::sync::{ Arc, Mutex };
+
+    use solify_analyzer::DependencyAnalyzer;
+    use solify_generator::GenerateOptions;
+    use solify_parser::{ get_program_id, parse_idl };
+    use tracing::span::{ Attributes, Id };
+    use tracing_subscriber::layer::{ Context, SubscriberExt };
+    use tracing_subscriber::Layer;
+
+    use super::sample_idl;
+    use crate::commands::gen_test::{ execute_from_metadata, ReportOptions };
+
+    /// Records the name of every span opened while it is installed.
+    struct SpanNames(Arc<Mutex<Vec<String>>>);
+
+    impl<S: tracing::Subscriber> Layer<S> for SpanNames {
+        fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+            self.0.lock().unwrap().push(attrs.metadata().name().to_string());
+        }
+    }
+
+    #[test]
+    fn test_generation_from_a_metadata_file_skips_analysis_and_rpc() {
+        let idl_path = sample_idl("counter_program.json");
+        let idl_data = parse_idl(&idl_path).unwrap();
+        let execution_order: Vec<String> = idl_data.instructions.iter().map(|i| i.name.clone()).collect();
+        let metadata = DependencyAnalyzer::new()
+            .analyze_dependencies(&idl_data, &execution_order, get_program_id(&idl_path).unwrap())
+            .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let metadata_path = dir.path().join("metadata.json");
+        std::fs::write(&metadata_path, serde_json::to_string(&metadata).unwrap()).unwrap();
+
+        let spans = Arc::new(Mutex::new(Vec::new()));
+        let subscriber = tracing_subscriber::registry().with(SpanNames(spans.clone()));
+        let test_file = tracing::subscriber::with_default(subscriber, || {
+            execute_from_metadata(
+                idl_path,
+                &metadata_path,
+                dir.path().to_path_buf(),
+                GenerateOptions::default(),
+                ReportOptions::default()
+            ).unwrap()
+        });
+
+        let spans = spans.lock().unwrap();
+        assert!(spans.iter().any(|name| name == "generate"), "{:?}", spans);
+        // The analyzer opens "analyze" spans; RPC calls open "store" and "fetch" spans.
+        assert!(!spans.iter().any(|name| ["analyze", "store", "fetch"].contains(&name.as_str())), "{:?}", spans);
+        let generated = std::fs::read_to_string(test_file).unwrap();
+        assert!(generated.contains("describe("), "{}", generated);
+    }
+
+    #[test]
+    fn test_metadata_file_must_match_the_idl() {
+        let dir = tempfile::tempdir().unwrap();
+        let metadata_path = dir.path().join("metadata.json");
+        let metadata = solify_common::TestMetadata {
+            instruction_order: vec!["withdraw".to_string()],
+            account_dependencies: Vec::new(),
+            pda_init_sequence: Vec::new(),
+            setup_requirements: Vec::new(),
+            test_cases: Vec::new(),
+        };
+        std::fs::write(&metadata_path, serde_json::to_string(&metadata).unwrap()).unwrap();
+
+        let err = execute_from_metadata(
+            sample_idl("counter_program.json"),
+            &metadata_path,
+            dir.path().to_path_buf(),
+            GenerateOptions::default(),
+            ReportOptions::default()
+        ).unwrap_err();
+        assert!(err.to_string().contains("'withdraw'"), "{}", err);
+    }
+}
+
+/// Runs with `cargo test -p solify --no-default-features`.
+#[cfg(not(feature = "tui"))]
+mod without_tui {
+    use solify_generator::GenerateOptions;
+
+    use super::sample_idl;
+    use crate::commands::gen_test::{ execute, OffChainOptions, OnChainOptions, ReportOptions };
+    use crate::RpcEndpoint;
+
+    #[tokio::test]
+    async fn test_gen_test_runs_from_flags_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        execute(
+            sample_idl("counter_program.json"),
+            dir.path().to_path_buf(),
+            &RpcEndpoint { url: "http://127.0.0.1:8899".to_string(), headers: Vec::new(), commitment: None },
+            Some(OffChainOptions::default()),
+            GenerateOptions::default(),
+            ReportOptions::default(),
+            OnChainOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        let generated: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(generated.len(), 1, "{:?}", generated);
+    }
+
+    #[test]
+    fn test_missing_wallet_is_an_error_instead_of_a_prompt() {
+        let Err(err) = crate::commands::gen_test::resolve_processing(
+            &RpcEndpoint { url: "http://127.0.0.1:8899".to_string(), headers: Vec::new(), commitment: None },
+            None,
+            OnChainOptions::default(),
+        ) else {
+            panic!("expected a missing wallet error");
+        };
+        assert!(err.to_string().contains("--wallet"), "{}", err);
+    }
+}
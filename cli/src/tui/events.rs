@@ -13,6 +13,7 @@ pub enum AppEvent {
     Esc,
     Char(char),
     Tick,
+    Resize,
     MouseScroll { up: bool, column: u16, row: u16 },
 }
 
@@ -30,6 +31,7 @@ impl EventHandler {
             match event::read()? {
                 Event::Key(key) => return Ok(self.handle_key_event(key)),
                 Event::Mouse(mouse) => return Ok(self.handle_mouse_event(mouse)),
+                Event::Resize(_, _) => return Ok(AppEvent::Resize),
                 _ => {}
             }
         }
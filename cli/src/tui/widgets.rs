@@ -58,7 +58,7 @@ pub fn render_progress(f: &mut Frame, area: Rect, label: &str, progress: f64) {
     f.render_widget(gauge, area);
 }
 
-pub fn render_list<'a>(
+pub fn render_list(
     f: &mut Frame,
     area: Rect,
     title: &str,
@@ -15,12 +15,16 @@ pub fn format_sol(lamports: u64) -> String {
     format!("{:.9} SOL", lamports_to_sol(lamports))
 }
 
+/// Shortens `s` to at most `max_len` characters, ending in `...` when cut.
+/// Lengths count characters, so multi-byte text is never split mid-character.
 pub fn truncate_str(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
-    } else {
-        format!("{}...", &s[..max_len.saturating_sub(3)])
+    if s.char_indices().nth(max_len).is_none() {
+        return s.to_string();
     }
+    let end = s.char_indices()
+        .nth(max_len.saturating_sub(3))
+        .map_or(s.len(), |(index, _)| index);
+    format!("{}...", &s[..end])
 }
 
 pub fn format_timestamp(timestamp: i64) -> String {
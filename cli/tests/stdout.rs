@@ -0,0 +1,41 @@
+//! Runs the `solify` binary to check what reaches its stdout.
+
+use std::path::{ Path, PathBuf };
+use std::process::Command;
+
+use solify_analyzer::DependencyAnalyzer;
+use solify_generator::{ generate, GenerateOptions };
+use solify_parser::{ get_program_id, parse_idl };
+
+fn sample_idl(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../parser/idls").join(name)
+}
+
+#[test]
+fn test_gen_test_stdout_holds_only_the_generated_tests() {
+    let idl_path = sample_idl("counter_program.json");
+    let idl_data = parse_idl(&idl_path).unwrap();
+    let execution_order: Vec<String> = idl_data.instructions.iter().map(|i| i.name.clone()).collect();
+    let metadata = DependencyAnalyzer::new()
+        .analyze_dependencies(&idl_data, &execution_order, get_program_id(&idl_path).unwrap())
+        .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let metadata_path = dir.path().join("metadata.json");
+    std::fs::write(&metadata_path, serde_json::to_string(&metadata).unwrap()).unwrap();
+
+    // --verbose logs at startup, and RUST_LOG turns on every crate's logs.
+    let output = Command::new(env!("CARGO_BIN_EXE_solify"))
+        .args(["--verbose", "gen-test", "--stdout", "--idl"])
+        .arg(&idl_path)
+        .arg("--metadata")
+        .arg(&metadata_path)
+        .env("RUST_LOG", "debug")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let expected = generate(&metadata, &idl_data, dir.path(), &GenerateOptions::default()).unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout, std::fs::read_to_string(expected.path).unwrap());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Verbose mode enabled"));
+}
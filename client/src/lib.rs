@@ -1,27 +1,52 @@
 use anyhow::{Context, Result};
-use solana_client::rpc_client::RpcClient;
+use solana_client::client_error::reqwest;
+use solana_client::rpc_client::{RpcClient, RpcClientConfig};
+use solana_rpc_client::http_sender::HttpSender;
 use solana_commitment_config::CommitmentConfig;
-use solana_sdk::instruction::Instruction as SolanaInstruction;
+use solana_sdk::instruction::{AccountMeta, Instruction as SolanaInstruction};
 use solana_sdk::{
+    account::Account,
+    hash::{hash, Hash},
+    message::Message,
     pubkey::Pubkey,
-    signature::{Signature, Signer},
+    signature::{Keypair, Signature, Signer},
     transaction::Transaction,
 };
 
 use solify_common::types::{IdlData as CommonIdlData, TestMetadata as CommonTestMetadata};
 use solify_common::ArgumentType as C;
 use types::ArgumentType as T;
+use std::path::Path;
 use std::str::FromStr;
+use std::time::Duration;
 
 #[path = "clients/rust/src/generated/mod.rs"]
 pub mod generated;
 
+#[cfg(test)]
+mod tests;
+
 pub use generated::programs::SOLIFY_ID;
 pub use generated::{accounts, errors, instructions, types};
 
 pub struct SolifyClient {
     rpc: RpcClient,
     commitment: CommitmentConfig,
+    expected_program_hash: Option<Hash>,
+    simulation_policy: SimulationPolicy,
+    additional_signers: Vec<Keypair>,
+}
+
+/// Whether [`SolifyClient`] simulates a transaction before sending it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SimulationPolicy {
+    /// Simulate first and abort when the simulation fails.
+    #[default]
+    Always,
+    /// Send without simulating, saving an RPC round-trip.
+    Never,
+    /// Simulate first, but only log a warning when the simulation fails.
+    Warn,
 }
 
 impl SolifyClient {
@@ -34,11 +59,50 @@ impl SolifyClient {
         commitment: CommitmentConfig,
     ) -> Result<Self> {
         let rpc = RpcClient::new_with_commitment(rpc_url.as_ref().to_string(), commitment);
-        Ok(Self { rpc, commitment })
+        Ok(Self::from_rpc_client(rpc, commitment))
+    }
+
+    /// Like [`SolifyClient::new_with_commitment`], but sends `headers` with every
+    /// RPC request, e.g. the API key a paid RPC provider expects.
+    pub fn new_with_headers(
+        rpc_url: impl AsRef<str>,
+        commitment: CommitmentConfig,
+        headers: &[(String, String)],
+    ) -> Result<Self> {
+        let rpc = rpc_client_with_headers(rpc_url, commitment, headers)?;
+        Ok(Self::from_rpc_client(rpc, commitment))
     }
 
     pub fn from_rpc_client(rpc: RpcClient, commitment: CommitmentConfig) -> Self {
-        Self { rpc, commitment }
+        Self {
+            rpc,
+            commitment,
+            expected_program_hash: None,
+            simulation_policy: SimulationPolicy::default(),
+            additional_signers: Vec::new(),
+        }
+    }
+
+    /// Makes [`SolifyClient::check_program_compatibility`] require the deployed
+    /// program to hash to `hash`, as reported by [`SolifyClient::deployed_program_hash`].
+    pub fn with_expected_program_hash(mut self, hash: Hash) -> Self {
+        self.expected_program_hash = Some(hash);
+        self
+    }
+
+    /// Sets whether transactions are simulated before they are sent.
+    pub fn with_simulation_policy(mut self, policy: SimulationPolicy) -> Self {
+        self.simulation_policy = policy;
+        self
+    }
+
+    /// Makes `signers` co-sign every transaction next to the authority, e.g. the
+    /// other members of a multisig authority. Solify's instructions declare no
+    /// such signers, so they are attached to the first instruction as read-only
+    /// signer accounts, which the program ignores.
+    pub fn with_additional_signers(mut self, signers: Vec<Keypair>) -> Self {
+        self.additional_signers = signers;
+        self
     }
 
     pub fn rpc(&self) -> &RpcClient {
@@ -49,25 +113,70 @@ impl SolifyClient {
         self.commitment
     }
 
+    /// Hash of the deployed Solify executable, with trailing zero padding removed
+    /// so it matches `solana-verify get-executable-hash` on the built `.so`.
+    pub fn deployed_program_hash(&self) -> Result<Hash> {
+        let program = self
+            .rpc
+            .get_account_with_commitment(&SOLIFY_ID, self.commitment)
+            .context("Failed to fetch the Solify program account")?
+            .value
+            .filter(|account| account.executable)
+            .ok_or_else(|| anyhow::anyhow!("The Solify program {} is not deployed on this cluster", SOLIFY_ID))?;
+
+        let executable = if program.owner == BPF_LOADER_UPGRADEABLE_ID {
+            let programdata_address = program
+                .data
+                .get(4..36)
+                .and_then(|bytes| Pubkey::try_from(bytes).ok())
+                .context("Malformed upgradeable program account")?;
+            let programdata = self
+                .rpc
+                .get_account_with_commitment(&programdata_address, self.commitment)
+                .context("Failed to fetch the Solify program data account")?
+                .value
+                .context("The Solify program data account is missing")?;
+            programdata.data.get(PROGRAM_DATA_HEADER_LEN..).unwrap_or_default().to_vec()
+        } else {
+            program.data
+        };
+
+        let len = executable.iter().rposition(|byte| *byte != 0).map_or(0, |last| last + 1);
+        Ok(hash(&executable[..len]))
+    }
+
+    /// Checks that the deployed Solify program is the build these bindings were
+    /// generated from. Without an expected hash configured through
+    /// [`SolifyClient::with_expected_program_hash`] there is nothing to compare
+    /// against, so the program is not fetched.
+    pub fn check_program_compatibility(&self) -> Result<()> {
+        let Some(expected) = self.expected_program_hash else {
+            return Ok(());
+        };
+        let deployed = self.deployed_program_hash()?;
+        if expected != deployed {
+            return Err(ProgramVersionMismatch {
+                program_id: SOLIFY_ID,
+                expected,
+                deployed,
+            }.into());
+        }
+        Ok(())
+    }
+
     pub fn store_idl_data<S: Signer>(
         &self,
         authority: &S,
         program_id: Pubkey,
         idl_data: &CommonIdlData,
     ) -> Result<Signature> {
-        let generated_idl = convert_idl_data(idl_data)?;
-        let (idl_storage, _) = derive_idl_storage_address(&program_id, &authority.pubkey());
-
-        let accounts = instructions::StoreIdlData {
-            idl_storage,
-            authority: authority.pubkey(),
-            system_program: system_program_id(),
-        };
-        let args = instructions::StoreIdlDataInstructionArgs {
-            idl_data: generated_idl,
-            program_id,
-        };
-        let instruction = accounts.instruction(args);
+        let _span = tracing::info_span!(
+            "store",
+            %program_id,
+            instructions = idl_data.instructions.len(),
+        ).entered();
+        let instruction = store_idl_instruction(&authority.pubkey(), program_id, idl_data)?;
+        check_transaction_size(std::slice::from_ref(&instruction), &authority.pubkey())?;
 
         self.send_instruction(authority, &[instruction])
     }
@@ -78,6 +187,11 @@ impl SolifyClient {
         program_id: Pubkey,
         idl_data: &CommonIdlData,
     ) -> Result<Signature> {
+        let _span = tracing::info_span!(
+            "store",
+            %program_id,
+            instructions = idl_data.instructions.len(),
+        ).entered();
         let generated_idl = convert_idl_data(idl_data)?;
         let (idl_storage, _) = derive_idl_storage_address(&program_id, &authority.pubkey());
 
@@ -91,6 +205,7 @@ impl SolifyClient {
             program_id,
         };
         let instruction = accounts.instruction(args);
+        check_transaction_size(std::slice::from_ref(&instruction), &authority.pubkey())?;
 
         self.send_instruction(authority, &[instruction])
     }
@@ -102,21 +217,75 @@ impl SolifyClient {
         execution_order: Vec<String>,
         paraphrase: &str,
         program_name: impl Into<String>,
+    ) -> Result<Signature> {
+        let _span = tracing::info_span!(
+            "analyze",
+            on_chain = true,
+            %program_id,
+            instructions = execution_order.len(),
+        ).entered();
+        let instruction = generate_metadata_instruction(
+            &authority.pubkey(),
+            program_id,
+            execution_order,
+            paraphrase,
+            program_name.into(),
+        )?;
+
+        self.send_instruction(authority, &[instruction])
+    }
+
+    /// Simulates [`SolifyClient::generate_metadata`] without sending it and returns
+    /// the program logs, including the analyzer's `msg!` output. A failing
+    /// simulation still returns its logs, which end with the failure.
+    pub fn simulate_generate_metadata<S: Signer>(
+        &self,
+        authority: &S,
+        program_id: Pubkey,
+        execution_order: Vec<String>,
+        paraphrase: &str,
+        program_name: impl Into<String>,
+    ) -> Result<Vec<String>> {
+        let instruction = generate_metadata_instruction(
+            &authority.pubkey(),
+            program_id,
+            execution_order,
+            paraphrase,
+            program_name.into(),
+        )?;
+        let transaction = self.signed_transaction(authority, &[instruction])?;
+        let simulation = self
+            .rpc
+            .simulate_transaction(&transaction)
+            .context("Failed to simulate GenerateMetadata")?;
+        if let Some(err) = &simulation.value.err {
+            tracing::debug!(?err, "GenerateMetadata simulation failed");
+        }
+        Ok(simulation.value.logs.unwrap_or_default())
+    }
+
+    /// Regenerates the test metadata for a new instruction order from the IDL already
+    /// stored on-chain, leaving the IDL storage account untouched.
+    pub fn update_execution_order<S: Signer>(
+        &self,
+        authority: &S,
+        program_id: Pubkey,
+        execution_order: Vec<String>,
+        paraphrase: &str,
     ) -> Result<Signature> {
         let (idl_storage, _) = derive_idl_storage_address(&program_id, &authority.pubkey());
         let (test_metadata_config, _) =
-            derive_test_metadata_config_address(&program_id, &authority.pubkey(), paraphrase);
+            derive_test_metadata_config_address(&program_id, &authority.pubkey(), paraphrase)?;
 
-        let accounts = instructions::GenerateMetadata {
+        let accounts = instructions::UpdateExecutionOrder {
             test_metadata_config,
             idl_storage,
             authority: authority.pubkey(),
             system_program: system_program_id(),
         };
-        let args = instructions::GenerateMetadataInstructionArgs {
+        let args = instructions::UpdateExecutionOrderInstructionArgs {
             execution_order,
             program_id,
-            program_name: program_name.into(),
             paraphrase: paraphrase.to_string(),
         };
         let instruction = accounts.instruction(args);
@@ -124,34 +293,46 @@ impl SolifyClient {
         self.send_instruction(authority, &[instruction])
     }
 
-
-
     pub fn fetch_idl_storage(
         &self,
         authority: Pubkey,
         program_id: Pubkey,
     ) -> Result<Option<IdlStorageAccount>> {
         let (address, _) = derive_idl_storage_address(&program_id, &authority);
+        let Some(decoded) = self.fetch_idl_storage_raw(authority, program_id)? else {
+            return Ok(None);
+        };
+
+        Ok(Some(IdlStorageAccount {
+            address,
+            authority: decoded.authority,
+            program_id: decoded.program_id,
+            idl_data: convert_idl_data_back(&decoded.idl_data),
+            timestamp: decoded.timestamp,
+        }))
+    }
+
+    /// Like [`SolifyClient::fetch_idl_storage`], but returns the account exactly as
+    /// decoded, discriminator included, with the IDL in the program's own types.
+    pub fn fetch_idl_storage_raw(
+        &self,
+        authority: Pubkey,
+        program_id: Pubkey,
+    ) -> Result<Option<accounts::IdlStorage>> {
+        let (address, _) = derive_idl_storage_address(&program_id, &authority);
+        let _span = tracing::info_span!("fetch", account = "idl_storage", %address).entered();
         let response = self
             .rpc
             .get_account_with_commitment(&address, self.commitment)
             .context("Failed to fetch IDL storage account")?;
 
-        if let Some(account) = response.value {
-            let decoded = accounts::idl_storage::IdlStorage::from_bytes(&account.data)
-                .context("Failed to decode IDL storage account data")?;
-            let idl_data = convert_idl_data_back(&decoded.idl_data);
-
-            Ok(Some(IdlStorageAccount {
-                address,
-                authority: decoded.authority,
-                program_id: decoded.program_id,
-                idl_data,
-                timestamp: decoded.timestamp,
-            }))
-        } else {
-            Ok(None)
-        }
+        response.value
+            .map(|account| {
+                ensure_owned_by_solify(&account, &address)?;
+                accounts::IdlStorage::from_bytes(&account.data)
+                    .context("Failed to decode IDL storage account data")
+            })
+            .transpose()
     }
 
     pub fn fetch_test_metadata(
@@ -160,15 +341,40 @@ impl SolifyClient {
         program_id: Pubkey,
         paraphrase: &str,
     ) -> Result<Option<TestMetadataAccount>> {
-        let (address, _) = derive_test_metadata_config_address(&program_id, &authority, &paraphrase);
+        let (address, _) = derive_test_metadata_config_address(&program_id, &authority, paraphrase)?;
+        let Some(decoded) = self.fetch_test_metadata_raw(authority, program_id, paraphrase)? else {
+            return Ok(None);
+        };
+
+        Ok(Some(TestMetadataAccount {
+            address,
+            authority: decoded.authority,
+            program_id: decoded.program_id,
+            test_metadata: convert_test_metadata_back(&decoded.test_metadata)?,
+            program_name: decoded.program_name,
+            timestamp: decoded.timestamp,
+        }))
+    }
+
+    /// Like [`SolifyClient::fetch_test_metadata`], but returns the account exactly
+    /// as decoded, including the discriminator and paraphrase, with the metadata in
+    /// the program's own types.
+    pub fn fetch_test_metadata_raw(
+        &self,
+        authority: Pubkey,
+        program_id: Pubkey,
+        paraphrase: &str,
+    ) -> Result<Option<accounts::TestMetadataConfig>> {
+        let (address, _) = derive_test_metadata_config_address(&program_id, &authority, paraphrase)?;
+        let _span = tracing::info_span!("fetch", account = "test_metadata", %address).entered();
         let response = self
             .rpc
             .get_account_with_commitment(&address, self.commitment)
             .context("Failed to fetch test metadata account")?;
 
         if let Some(account) = response.value {
-            
-            let decoded = accounts::test_metadata_config::TestMetadataConfig::from_bytes(&account.data)
+            ensure_owned_by_solify(&account, &address)?;
+            let decoded = accounts::TestMetadataConfig::from_bytes(&account.data)
             .with_context(|| {
                 format!(
                     "Failed to decode TestMetadataConfig due to serialization format mismatch. \
@@ -179,48 +385,71 @@ impl SolifyClient {
                     account.data.len()
                 )
             })?;
-            let test_metadata = convert_test_metadata_back(&decoded.test_metadata)?;
-
-            Ok(Some(TestMetadataAccount {
-                address,
-                authority: decoded.authority,
-                program_id: decoded.program_id,
-                program_name: decoded.program_name,
-                test_metadata,
-                timestamp: decoded.timestamp,
-            }))
+            Ok(Some(decoded))
         } else {
             Ok(None)
         }
     }
 
-    fn send_instruction<S: Signer>(
+    fn signed_transaction<S: Signer>(
         &self,
         authority: &S,
         instructions: &[SolanaInstruction],
-    ) -> Result<Signature> {
+    ) -> Result<Transaction> {
         let recent_blockhash = self
             .rpc
             .get_latest_blockhash()
             .context("Failed to fetch latest blockhash")?;
 
-        let transaction = Transaction::new_signed_with_payer(
-            instructions,
+        let additional_signers: Vec<&Keypair> = self.additional_signers
+            .iter()
+            .filter(|signer| signer.pubkey() != authority.pubkey())
+            .collect();
+        let mut instructions = instructions.to_vec();
+        if let Some(first) = instructions.first_mut() {
+            first.accounts.extend(additional_signers.iter().map(|signer| AccountMeta::new_readonly(signer.pubkey(), true)));
+        }
+        let mut signers: Vec<&dyn Signer> = vec![authority];
+        signers.extend(additional_signers.into_iter().map(|signer| signer as &dyn Signer));
+
+        Ok(Transaction::new_signed_with_payer(
+            &instructions,
             Some(&authority.pubkey()),
-            &[authority],
+            &signers,
             recent_blockhash,
-        );
+        ))
+    }
+
+    fn send_instruction<S: Signer>(
+        &self,
+        authority: &S,
+        instructions: &[SolanaInstruction],
+    ) -> Result<Signature> {
+        let transaction = self.signed_transaction(authority, instructions)?;
 
         // Simulate the transaction first to catch errors early
-        let simulation_result = self.rpc.simulate_transaction(&transaction);
-        if let Ok(simulation) = simulation_result {
-            if let Some(err) = simulation.value.err {
-                return Err(anyhow::anyhow!(
-                    "Transaction simulation failed: {:?}. Logs: {:?}",
-                    err,
-                    simulation.value.logs
-                ));
+        let simulation = match self.simulation_policy {
+            SimulationPolicy::Never => None,
+            SimulationPolicy::Always | SimulationPolicy::Warn => Some(self.rpc.simulate_transaction(&transaction)),
+        };
+        match simulation {
+            Some(Ok(simulation)) => {
+                if let Some(err) = simulation.value.err {
+                    if self.simulation_policy == SimulationPolicy::Warn {
+                        tracing::warn!(?err, logs = ?simulation.value.logs, "transaction simulation failed, sending anyway");
+                    } else {
+                        return Err(anyhow::anyhow!(
+                            "Transaction simulation failed: {:?}. Logs: {:?}",
+                            err,
+                            simulation.value.logs
+                        ));
+                    }
+                }
             }
+            // The request itself failed, e.g. the RPC does not support simulation or
+            // the network dropped it, so nothing is known about the transaction yet.
+            Some(Err(e)) => tracing::warn!(error = %e, "could not simulate transaction, sending without simulation"),
+            None => {}
         }
 
         // Send and confirm the transaction
@@ -240,6 +469,211 @@ impl SolifyClient {
     }
 }
 
+/// Timeout of the HTTP client behind [`rpc_client_with_headers`], matching `RpcClient::new`.
+const RPC_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The default Solana client headers plus `headers`, which win on a name clash.
+pub fn rpc_header_map(headers: &[(String, String)]) -> Result<reqwest::header::HeaderMap> {
+    let mut header_map = HttpSender::default_headers();
+    for (name, value) in headers {
+        let name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+            .with_context(|| format!("Invalid RPC header name: {:?}", name))?;
+        let value = reqwest::header::HeaderValue::from_str(value)
+            .with_context(|| format!("Invalid value for RPC header {}", name))?;
+        header_map.insert(name, value);
+    }
+    Ok(header_map)
+}
+
+/// Builds an [`RpcClient`] whose HTTP sender attaches `headers` to every request.
+pub fn rpc_client_with_headers(
+    rpc_url: impl AsRef<str>,
+    commitment: CommitmentConfig,
+    headers: &[(String, String)],
+) -> Result<RpcClient> {
+    let http_client = reqwest::Client::builder()
+        .default_headers(rpc_header_map(headers)?)
+        .timeout(RPC_TIMEOUT)
+        .pool_idle_timeout(RPC_TIMEOUT)
+        .build()
+        .context("Failed to build the RPC HTTP client")?;
+    let sender = HttpSender::new_with_client(rpc_url.as_ref(), http_client);
+    Ok(RpcClient::new_sender(sender, RpcClientConfig::with_commitment(commitment)))
+}
+
+/// Owner of programs deployed with `solana program deploy`.
+const BPF_LOADER_UPGRADEABLE_ID: Pubkey =
+    solana_pubkey::pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+
+/// Enum tag, deployment slot and optional upgrade authority before the ELF in a program data account.
+const PROGRAM_DATA_HEADER_LEN: usize = 4 + 8 + 1 + 32;
+
+/// Returned by [`SolifyClient::check_program_compatibility`] when the deployed
+/// program is not the build the client expects.
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "Your CLI expects Solify program build {expected} but the deployment at {program_id} is \
+    build {deployed}; upgrade the CLI or point it at a cluster running the matching program"
+)]
+pub struct ProgramVersionMismatch {
+    pub program_id: Pubkey,
+    pub expected: Hash,
+    pub deployed: Hash,
+}
+
+/// Largest serialized transaction the cluster accepts (`PACKET_DATA_SIZE`).
+pub const MAX_TRANSACTION_SIZE: usize = 1232;
+
+/// Returned before any RPC call when a transaction cannot fit in a single packet.
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "Your Anchor program is too large for on-chain processing: the transaction would be \
+    {transaction_size} bytes, but at most {limit} bytes fit in a single transaction"
+)]
+pub struct ProgramTooLarge {
+    pub transaction_size: usize,
+    pub limit: usize,
+}
+
+/// Signed size of a transaction carrying `instructions`.
+pub fn transaction_size(instructions: &[SolanaInstruction], payer: &Pubkey) -> usize {
+    let message = Message::new(instructions, Some(payer));
+    let signatures = usize::from(message.header.num_required_signatures);
+    // Compact-u16 signature count, the signatures themselves, then the message.
+    1 + signatures * 64 + message.serialize().len()
+}
+
+/// Computes the signed size of a transaction carrying `instructions` and fails with
+/// [`ProgramTooLarge`] if it exceeds [`MAX_TRANSACTION_SIZE`].
+pub fn check_transaction_size(instructions: &[SolanaInstruction], payer: &Pubkey) -> Result<()> {
+    let transaction_size = transaction_size(instructions, payer);
+    if transaction_size > MAX_TRANSACTION_SIZE {
+        return Err(ProgramTooLarge {
+            transaction_size,
+            limit: MAX_TRANSACTION_SIZE,
+        }.into());
+    }
+    Ok(())
+}
+
+/// The `store_idl_data` instruction uploading `idl_data` for `program_id`.
+pub fn store_idl_instruction(
+    authority: &Pubkey,
+    program_id: Pubkey,
+    idl_data: &CommonIdlData,
+) -> Result<SolanaInstruction> {
+    let (idl_storage, _) = derive_idl_storage_address(&program_id, authority);
+    let accounts = instructions::StoreIdlData {
+        idl_storage,
+        authority: *authority,
+        system_program: system_program_id(),
+    };
+    let args = instructions::StoreIdlDataInstructionArgs {
+        idl_data: convert_idl_data(idl_data)?,
+        program_id,
+    };
+    Ok(accounts.instruction(args))
+}
+
+/// `INIT_SPACE` of the program's `TestMetadata`, summed from the `max_len` bounds
+/// of its fields. `generate_metadata` fails when the analysis serializes to more.
+/// The program's tests check this against the account layout.
+pub const TEST_METADATA_SPACE: usize = 3661;
+
+/// Borsh size of `metadata` in the program's account layout.
+pub fn serialized_test_metadata_len(metadata: &CommonTestMetadata) -> Result<usize> {
+    let generated = convert_test_metadata(metadata)?;
+    Ok(borsh::to_vec(&generated).context("Failed to serialize test metadata")?.len())
+}
+
+/// Borsh size of one instruction's test cases in the program's account layout.
+pub fn serialized_test_cases_len(test_cases: &solify_common::InstructionTestCases) -> Result<usize> {
+    let generated = convert_instruction_test_cases(test_cases)?;
+    Ok(borsh::to_vec(&generated).context("Failed to serialize test cases")?.len())
+}
+
+/// Loads a signing keypair from a wallet file.
+///
+/// Accepts the `solana-keygen` JSON byte array as well as a base58 encoded string,
+/// each holding either the full 64-byte keypair or the 32-byte secret key.
+pub fn load_signer<P: AsRef<Path>>(path: P) -> Result<Keypair> {
+    let path = path.as_ref();
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read wallet file: {:?}", path))?;
+    let contents = contents.trim();
+
+    let bytes: Vec<u8> = if contents.starts_with('[') {
+        serde_json::from_str(contents)
+            .with_context(|| format!("Failed to parse wallet JSON: {:?}", path))?
+    } else {
+        bs58::decode(contents)
+            .into_vec()
+            .with_context(|| format!("Failed to decode base58 wallet key: {:?}", path))?
+    };
+
+    keypair_from_bytes(&bytes).with_context(|| format!("Invalid wallet keypair: {:?}", path))
+}
+
+fn keypair_from_bytes(bytes: &[u8]) -> Result<Keypair> {
+    match bytes.len() {
+        64 => Keypair::try_from(bytes)
+            .map_err(|e| anyhow::anyhow!("Malformed 64-byte keypair: {}", e)),
+        32 => {
+            let mut secret_key = [0u8; 32];
+            secret_key.copy_from_slice(bytes);
+            Ok(Keypair::new_from_array(secret_key))
+        }
+        len => anyhow::bail!("Expected a 32 or 64 byte key, got {} bytes", len),
+    }
+}
+
+/// Rejects an account at a Solify PDA that the Solify program does not own, so
+/// data someone else placed there is never decoded as ours.
+fn ensure_owned_by_solify(account: &Account, address: &Pubkey) -> Result<()> {
+    anyhow::ensure!(
+        account.owner == SOLIFY_ID,
+        "Account {} is owned by {}, not the Solify program {}",
+        address,
+        account.owner,
+        SOLIFY_ID
+    );
+    Ok(())
+}
+
+/// Hashes the on-chain (Borsh) encoding of an IDL, so a local IDL can be compared
+/// against the one already stored without re-uploading it.
+pub fn idl_hash(idl_data: &CommonIdlData) -> Result<Hash> {
+    let generated_idl = convert_idl_data(idl_data)?;
+    let bytes = borsh::to_vec(&generated_idl).context("Failed to serialize IDL data")?;
+    Ok(hash(&bytes))
+}
+
+fn generate_metadata_instruction(
+    authority: &Pubkey,
+    program_id: Pubkey,
+    execution_order: Vec<String>,
+    paraphrase: &str,
+    program_name: String,
+) -> Result<SolanaInstruction> {
+    let (idl_storage, _) = derive_idl_storage_address(&program_id, authority);
+    let (test_metadata_config, _) =
+        derive_test_metadata_config_address(&program_id, authority, paraphrase)?;
+
+    let accounts = instructions::GenerateMetadata {
+        test_metadata_config,
+        idl_storage,
+        authority: *authority,
+        system_program: system_program_id(),
+    };
+    let args = instructions::GenerateMetadataInstructionArgs {
+        execution_order,
+        program_id,
+        program_name,
+        paraphrase: paraphrase.to_string(),
+    };
+    Ok(accounts.instruction(args))
+}
+
 pub fn derive_idl_storage_address(program_id: &Pubkey, authority: &Pubkey) -> (Pubkey, u8) {
     Pubkey::find_program_address(
         &[b"idl_storage", program_id.as_ref(), authority.as_ref()],
@@ -247,15 +681,38 @@ pub fn derive_idl_storage_address(program_id: &Pubkey, authority: &Pubkey) -> (P
     )
 }
 
+/// Fails with [`SeedTooLong`] when `paraphrase` does not fit in a single seed.
 pub fn derive_test_metadata_config_address(
     program_id: &Pubkey,
     authority: &Pubkey,
     paraphrase: &str,
-) -> (Pubkey, u8) {
-    Pubkey::find_program_address(
+) -> Result<(Pubkey, u8)> {
+    check_seed_len("paraphrase", paraphrase.as_bytes())?;
+    Ok(Pubkey::find_program_address(
         &[b"tests_metadata", program_id.as_ref(), authority.as_ref(), paraphrase.as_bytes()],
         &generated::SOLIFY_ID,
-    )
+    ))
+}
+
+/// Longest seed a PDA can be derived from, in bytes.
+pub const MAX_SEED_LEN: usize = solana_pubkey::MAX_SEED_LEN;
+
+/// Returned instead of deriving an address from a seed longer than [`MAX_SEED_LEN`],
+/// which `Pubkey::find_program_address` panics on.
+#[derive(Debug, thiserror::Error)]
+#[error("The {seed} is {length} bytes long, but PDA seeds are limited to {limit} bytes")]
+pub struct SeedTooLong {
+    pub seed: &'static str,
+    pub length: usize,
+    pub limit: usize,
+}
+
+/// Checks an argument-derived seed before it reaches `find_program_address`.
+fn check_seed_len(seed: &'static str, bytes: &[u8]) -> Result<()> {
+    if bytes.len() > MAX_SEED_LEN {
+        return Err(SeedTooLong { seed, length: bytes.len(), limit: MAX_SEED_LEN }.into());
+    }
+    Ok(())
 }
 
 #[derive(Debug, Clone)]
@@ -445,6 +902,8 @@ fn convert_idl_instruction_back(generated: &types::IdlInstruction) -> solify_com
         accounts: generated.accounts.iter().map(convert_idl_account_item_back).collect(),
         args: generated.args.iter().map(convert_idl_field_back).collect(),
         docs: generated.docs.clone(),
+        // Not stored on-chain.
+        returns: None,
     }
 }
 
@@ -456,6 +915,8 @@ fn convert_idl_account_item_back(generated: &types::IdlAccountItem) -> solify_co
         is_optional: generated.is_optional,
         docs: generated.docs.clone(),
         pda: generated.pda.as_ref().map(convert_idl_pda_back),
+        // The on-chain layout does not store `address` constraints.
+        address: None,
     }
 }
 
@@ -493,6 +954,8 @@ fn convert_idl_type_def_back(generated: &types::IdlTypeDef) -> solify_common::Id
         name: generated.name.clone(),
         kind: generated.kind.clone(),
         fields: generated.fields.clone(),
+        // Field types stay off-chain; newtypes are resolved from the local IDL instead.
+        field_types: vec![],
     }
 }
 
@@ -798,6 +1261,9 @@ fn convert_account_dependency_back(src: &types::AccountDependency) -> solify_com
         is_mut: src.is_mut,
         must_be_initialized: src.must_be_initialized,
         initialization_order: src.initialization_order,
+        // The on-chain analyzer does not infer close or realloc constraints.
+        closed_by: Vec::new(),
+        reallocated_by: Vec::new(),
     }
 }
 
@@ -825,6 +1291,20 @@ fn convert_seed_component_back(src: &types::SeedComponent) -> solify_common::See
     }
 }
 
+/// The on-chain layout has no account field, so the account is read back from
+/// what the program's setup generator records for each requirement type.
+fn setup_account_name(src: &types::SetupRequirement) -> Option<String> {
+    let account = match src.requirement_type {
+        types::SetupType::CreateKeypair => src.description.strip_prefix("Create keypair for "),
+        types::SetupType::FundAccount => src.dependencies.first().map(String::as_str),
+        types::SetupType::InitializePda => src.description
+            .strip_prefix("Initialize ")
+            .and_then(|rest| rest.strip_suffix(" PDA")),
+        types::SetupType::MintTokens | types::SetupType::CreateAta => None,
+    };
+    account.map(String::from)
+}
+
 fn convert_setup_requirement_back(src: &types::SetupRequirement) -> solify_common::SetupRequirement {
     solify_common::SetupRequirement {
         requirement_type: match src.requirement_type {
@@ -836,6 +1316,7 @@ fn convert_setup_requirement_back(src: &types::SetupRequirement) -> solify_commo
         },
         description: src.description.clone(),
         dependencies: src.dependencies.clone(),
+        account_name: setup_account_name(src),
     }
 }
 
@@ -905,7 +1386,18 @@ fn convert_argument_type_back(src: &types::ArgumentType) -> Result<solify_common
     Ok(out)
 }
 
+/// Maximum nesting of `Vec<...>`/`Option<...>` wrappers accepted when parsing a type name.
+const MAX_TYPE_DEPTH: usize = 32;
+
 fn parse_argument_type_from_name(name: &str) -> Result<solify_common::ArgumentType> {
+    parse_argument_type_from_name_with_depth(name, 0)
+}
+
+fn parse_argument_type_from_name_with_depth(name: &str, depth: usize) -> Result<solify_common::ArgumentType> {
+    if depth > MAX_TYPE_DEPTH {
+        anyhow::bail!("Type nesting exceeds maximum depth of {}", MAX_TYPE_DEPTH);
+    }
+
     // Simple parser for basic types - this is a simplified version
     match name {
         "u8" => Ok(solify_common::ArgumentType::U8),
@@ -924,13 +1416,13 @@ fn parse_argument_type_from_name(name: &str) -> Result<solify_common::ArgumentTy
         _ => {
             // Try to parse Vec<...> or Option<...>
             if let Some(inner) = name.strip_prefix("Vec<").and_then(|s| s.strip_suffix('>')) {
-                let inner_type = parse_argument_type_from_name(inner)?;
+                let inner_type = parse_argument_type_from_name_with_depth(inner, depth + 1)?;
                 Ok(solify_common::ArgumentType::Vec {
                     inner_type: Box::new(inner_type),
                     max_length: None,
                 })
             } else if let Some(inner) = name.strip_prefix("Option<").and_then(|s| s.strip_suffix('>')) {
-                let inner_type = parse_argument_type_from_name(inner)?;
+                let inner_type = parse_argument_type_from_name_with_depth(inner, depth + 1)?;
                 Ok(solify_common::ArgumentType::Option {
                     inner_type: Box::new(inner_type),
                 })
@@ -0,0 +1,621 @@
+use crate::{parse_argument_type_from_name, MAX_TYPE_DEPTH};
+
+fn nested_vec_name(depth: usize) -> String {
+    format!("{}u8{}", "Vec<".repeat(depth), ">".repeat(depth))
+}
+
+#[test]
+fn test_parse_argument_type_from_name_nested() {
+    let ty = parse_argument_type_from_name("Option<Vec<u64>>").unwrap();
+    match ty {
+        solify_common::ArgumentType::Option { inner_type } => {
+            assert!(matches!(*inner_type, solify_common::ArgumentType::Vec { .. }));
+        }
+        other => panic!("Expected Option, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_argument_type_from_name_at_max_depth() {
+    let result = parse_argument_type_from_name(&nested_vec_name(MAX_TYPE_DEPTH));
+    assert!(result.is_ok(), "Type at max depth should parse: {:?}", result);
+}
+
+#[test]
+fn test_parse_argument_type_from_name_rejects_pathological_nesting() {
+    let err = parse_argument_type_from_name(&nested_vec_name(10_000)).unwrap_err();
+    assert!(err.to_string().contains("maximum depth"), "Unexpected error: {}", err);
+}
+
+mod load_signer {
+    use std::io::Write;
+
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use crate::load_signer;
+
+    fn write_wallet(contents: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        file
+    }
+
+    #[test]
+    fn test_load_signer_json_keypair() {
+        let keypair = Keypair::new();
+        let file = write_wallet(&serde_json::to_string(&keypair.to_bytes().to_vec()).unwrap());
+        assert_eq!(load_signer(file.path()).unwrap().pubkey(), keypair.pubkey());
+    }
+
+    #[test]
+    fn test_load_signer_json_secret_key() {
+        let keypair = Keypair::new();
+        let file = write_wallet(&serde_json::to_string(&keypair.secret_bytes().to_vec()).unwrap());
+        assert_eq!(load_signer(file.path()).unwrap().pubkey(), keypair.pubkey());
+    }
+
+    #[test]
+    fn test_load_signer_base58_keypair() {
+        let keypair = Keypair::new();
+        let file = write_wallet(&format!("{}\n", keypair.to_base58_string()));
+        assert_eq!(load_signer(file.path()).unwrap().pubkey(), keypair.pubkey());
+    }
+
+    #[test]
+    fn test_load_signer_base58_secret_key() {
+        let keypair = Keypair::new();
+        let file = write_wallet(&bs58::encode(keypair.secret_bytes()).into_string());
+        assert_eq!(load_signer(file.path()).unwrap().pubkey(), keypair.pubkey());
+    }
+
+    #[test]
+    fn test_load_signer_rejects_wrong_length() {
+        let file = write_wallet("[1, 2, 3]");
+        let err = load_signer(file.path()).unwrap_err();
+        assert!(format!("{:#}", err).contains("got 3 bytes"), "Unexpected error: {:#}", err);
+    }
+
+    #[test]
+    fn test_load_signer_rejects_mismatched_keypair() {
+        let mut bytes = Keypair::new().to_bytes().to_vec();
+        bytes[32..].copy_from_slice(&Keypair::new().pubkey().to_bytes());
+        let file = write_wallet(&serde_json::to_string(&bytes).unwrap());
+        assert!(load_signer(file.path()).is_err());
+    }
+
+    #[test]
+    fn test_load_signer_rejects_invalid_input() {
+        assert!(load_signer(write_wallet("not-a-key!").path()).is_err());
+        assert!(load_signer(write_wallet("[1, 2,").path()).is_err());
+        assert!(load_signer("/nonexistent/wallet.json").is_err());
+    }
+}
+
+mod idl_hash {
+    use solify_common::{IdlData, IdlField, IdlInstruction};
+
+    use crate::idl_hash;
+
+    fn idl(args: Vec<IdlField>) -> IdlData {
+        IdlData {
+            name: "counter".to_string(),
+            version: "0.1.0".to_string(),
+            instructions: vec![IdlInstruction {
+                name: "increment".to_string(),
+                accounts: vec![],
+                args,
+                docs: vec![],
+                returns: None,
+            }],
+            accounts: vec![],
+            types: vec![],
+            errors: vec![],
+            constants: vec![],
+            events: vec![],
+        }
+    }
+
+    #[test]
+    fn test_idl_hash_is_stable_for_identical_idls() {
+        assert_eq!(idl_hash(&idl(vec![])).unwrap(), idl_hash(&idl(vec![])).unwrap());
+    }
+
+    #[test]
+    fn test_idl_hash_changes_with_idl_contents() {
+        let changed = idl(vec![IdlField {
+            name: "amount".to_string(),
+            field_type: "u64".to_string(),
+        }]);
+        assert_ne!(idl_hash(&idl(vec![])).unwrap(), idl_hash(&changed).unwrap());
+    }
+}
+
+mod transaction_size {
+    use solana_sdk::signature::{Keypair, Signer};
+    use solana_sdk::pubkey::Pubkey;
+    use solify_common::{IdlData, IdlInstruction};
+
+    use crate::{ProgramTooLarge, SolifyClient, MAX_TRANSACTION_SIZE};
+
+    fn idl_with_instructions(count: usize) -> IdlData {
+        IdlData {
+            name: "oversized".to_string(),
+            version: "0.1.0".to_string(),
+            instructions: (0..count)
+                .map(|i| IdlInstruction {
+                    name: format!("instruction_number_{}", i),
+                    accounts: vec![],
+                    args: vec![],
+                    docs: vec![],
+                    returns: None,
+                })
+                .collect(),
+            accounts: vec![],
+            types: vec![],
+            errors: vec![],
+            constants: vec![],
+            events: vec![],
+        }
+    }
+
+    #[test]
+    fn test_store_idl_data_rejects_oversized_idl_before_rpc() {
+        // Nothing listens on this port, so reaching the RPC would yield a different error.
+        let client = SolifyClient::new("http://127.0.0.1:1").unwrap();
+        let authority = Keypair::new();
+
+        let err = client
+            .store_idl_data(&authority, Pubkey::new_unique(), &idl_with_instructions(100))
+            .unwrap_err();
+
+        let too_large = err.downcast_ref::<ProgramTooLarge>().expect("expected ProgramTooLarge");
+        assert_eq!(too_large.limit, MAX_TRANSACTION_SIZE);
+        assert!(too_large.transaction_size > MAX_TRANSACTION_SIZE);
+    }
+
+    #[test]
+    fn test_check_transaction_size_accepts_small_transactions() {
+        let payer = Keypair::new().pubkey();
+        assert!(crate::check_transaction_size(&[], &payer).is_ok());
+    }
+}
+
+mod program_compatibility {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+    use solana_client::rpc_client::RpcClient;
+    use solana_client::rpc_request::RpcRequest;
+    use solana_commitment_config::CommitmentConfig;
+    use solana_sdk::hash::hash;
+
+    use crate::{ProgramVersionMismatch, SolifyClient, SOLIFY_ID};
+
+    /// A client whose RPC reports a non-upgradeable Solify program holding `elf-v1`.
+    fn client_with_deployed_program() -> SolifyClient {
+        let account = json!({
+            "context": { "slot": 1 },
+            "value": {
+                // base64 of b"elf-v1" followed by zero padding
+                "data": ["ZWxmLXYxAAAA", "base64"],
+                "executable": true,
+                "lamports": 1_000_000,
+                "owner": "BPFLoader2111111111111111111111111111111111",
+                "rentEpoch": 0
+            }
+        });
+        let mocks = HashMap::from([(RpcRequest::GetAccountInfo, account)]);
+        let rpc = RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks);
+        SolifyClient::from_rpc_client(rpc, CommitmentConfig::confirmed())
+    }
+
+    #[test]
+    fn test_deployed_program_hash_ignores_zero_padding() {
+        let deployed = client_with_deployed_program().deployed_program_hash().unwrap();
+        assert_eq!(deployed, hash(b"elf-v1"));
+    }
+
+    #[test]
+    fn test_compatibility_check_skips_the_fetch_without_an_expected_hash() {
+        // The "fails" mock errors on every request, so any fetch would surface here.
+        let rpc = RpcClient::new_mock("fails".to_string());
+        let client = SolifyClient::from_rpc_client(rpc, CommitmentConfig::confirmed());
+        assert!(client.check_program_compatibility().is_ok());
+    }
+
+    #[test]
+    fn test_matching_program_is_compatible() {
+        let client = client_with_deployed_program().with_expected_program_hash(hash(b"elf-v1"));
+        assert!(client.check_program_compatibility().is_ok());
+    }
+
+    #[test]
+    fn test_mismatched_program_reports_both_builds() {
+        let client = client_with_deployed_program().with_expected_program_hash(hash(b"elf-v2"));
+        let err = client.check_program_compatibility().unwrap_err();
+
+        let mismatch = err.downcast_ref::<ProgramVersionMismatch>().expect("expected ProgramVersionMismatch");
+        assert_eq!(mismatch.program_id, SOLIFY_ID);
+        assert_eq!(mismatch.expected, hash(b"elf-v2"));
+        assert_eq!(mismatch.deployed, hash(b"elf-v1"));
+        assert!(err.to_string().starts_with("Your CLI expects Solify program build"));
+    }
+}
+
+mod simulation {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use serde_json::{json, Value};
+    use solana_client::rpc_client::RpcClient;
+    use solana_client::rpc_request::RpcRequest;
+    use solana_commitment_config::CommitmentConfig;
+    use solana_sdk::signature::Keypair;
+    use solana_sdk::instruction::Instruction;
+    use solana_sdk::pubkey::Pubkey;
+    use tracing::field::{Field, Visit};
+    use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+    use crate::{SimulationPolicy, SolifyClient};
+
+    /// Messages of the warnings logged while a test runs.
+    #[derive(Clone, Default)]
+    struct Warnings(Arc<Mutex<Vec<String>>>);
+
+    impl Visit for Warnings {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            if field.name() == "message" {
+                self.0.lock().unwrap().push(format!("{:?}", value));
+            }
+        }
+    }
+
+    impl<S: tracing::Subscriber> Layer<S> for Warnings {
+        fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+            if *event.metadata().level() == tracing::Level::WARN {
+                event.record(&mut self.clone());
+            }
+        }
+    }
+
+    /// Sends an instruction through a mock RPC whose `simulateTransaction` answers `simulation`.
+    fn send_with_simulation(simulation: Value) -> (anyhow::Result<()>, Vec<String>) {
+        send_with_policy(SimulationPolicy::default(), simulation)
+    }
+
+    fn send_with_policy(policy: SimulationPolicy, simulation: Value) -> (anyhow::Result<()>, Vec<String>) {
+        let mocks = HashMap::from([(RpcRequest::SimulateTransaction, simulation)]);
+        let rpc = RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks);
+        let client = SolifyClient::from_rpc_client(rpc, CommitmentConfig::confirmed()).with_simulation_policy(policy);
+        let payer = Keypair::new();
+        let instruction = Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![]);
+
+        let warnings = Warnings::default();
+        let subscriber = tracing_subscriber::registry().with(warnings.clone());
+        let result = tracing::subscriber::with_default(subscriber, || {
+            client.send_instruction(&payer, &[instruction]).map(|_| ())
+        });
+        let logged = warnings.0.lock().unwrap().clone();
+        (result, logged)
+    }
+
+    fn failed_simulation() -> Value {
+        json!({
+            "context": { "slot": 1 },
+            "value": {
+                "err": { "InstructionError": [0, { "Custom": 6000 }] },
+                "logs": ["Program log: Error: custom program error"],
+                "accounts": null,
+                "unitsConsumed": 0,
+                "returnData": null
+            }
+        })
+    }
+
+    #[test]
+    fn test_failed_simulation_request_warns_and_sends() {
+        // Not a simulation response, so the request fails on the client.
+        let (result, warnings) = send_with_simulation(json!("simulation unavailable"));
+        assert!(result.is_ok(), "Send should proceed: {:?}", result);
+        assert_eq!(warnings, ["could not simulate transaction, sending without simulation"]);
+    }
+
+    #[test]
+    fn test_simulated_program_error_aborts_before_sending() {
+        let (result, warnings) = send_with_simulation(failed_simulation());
+        let err = result.unwrap_err().to_string();
+        assert!(err.starts_with("Transaction simulation failed"), "Unexpected error: {}", err);
+        assert!(err.contains("custom program error"));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_warn_policy_sends_despite_a_simulated_program_error() {
+        let (result, warnings) = send_with_policy(SimulationPolicy::Warn, failed_simulation());
+        assert!(result.is_ok(), "Send should proceed: {:?}", result);
+        assert_eq!(warnings, ["transaction simulation failed, sending anyway"]);
+    }
+
+    #[test]
+    fn test_never_policy_sends_without_simulating() {
+        // Had the transaction been simulated, this would abort or warn.
+        let (result, warnings) = send_with_policy(SimulationPolicy::Never, failed_simulation());
+        assert!(result.is_ok(), "Send should proceed: {:?}", result);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_simulate_generate_metadata_returns_the_program_logs() {
+        let logs = [
+            "Program Solify111 invoke [1]",
+            "Program log: Instruction: GenerateMetadata",
+            "Program log: analyzing 2 instructions",
+            "Program Solify111 success",
+        ];
+        let simulation = json!({
+            "context": { "slot": 1 },
+            "value": { "err": null, "logs": logs, "accounts": null, "unitsConsumed": 1200, "returnData": null }
+        });
+        let mocks = HashMap::from([(RpcRequest::SimulateTransaction, simulation)]);
+        let rpc = RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks);
+        let client = SolifyClient::from_rpc_client(rpc, CommitmentConfig::confirmed());
+
+        let simulated = client
+            .simulate_generate_metadata(
+                &Keypair::new(),
+                Pubkey::new_unique(),
+                vec!["initialize".to_string(), "increment".to_string()],
+                "counter-tests",
+                "counter",
+            )
+            .unwrap();
+        assert_eq!(simulated, logs);
+    }
+}
+
+mod additional_signers {
+    use solana_client::rpc_client::RpcClient;
+    use solana_commitment_config::CommitmentConfig;
+    use solana_sdk::instruction::Instruction;
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use crate::SolifyClient;
+
+    #[test]
+    fn test_additional_signers_sign_the_transaction() {
+        let cosigners = vec![Keypair::new(), Keypair::new()];
+        let cosigner_keys: Vec<Pubkey> = cosigners.iter().map(|signer| signer.pubkey()).collect();
+        let rpc = RpcClient::new_mock("succeeds".to_string());
+        let client = SolifyClient::from_rpc_client(rpc, CommitmentConfig::confirmed()).with_additional_signers(cosigners);
+        let authority = Keypair::new();
+        let instruction = Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![]);
+
+        let transaction = client.signed_transaction(&authority, &[instruction]).unwrap();
+
+        assert_eq!(transaction.message.header.num_required_signatures, 3);
+        // The payer signs first; the message orders the read-only signers after it.
+        let signer_keys = &transaction.message.account_keys[..3];
+        assert_eq!(signer_keys[0], authority.pubkey());
+        assert!(cosigner_keys.iter().all(|key| signer_keys.contains(key)));
+        assert!(transaction.is_signed());
+        assert!(transaction.verify().is_ok());
+    }
+}
+
+mod raw_accounts {
+    use std::collections::HashMap;
+
+    use borsh::BorshSerialize;
+    use serde_json::json;
+    use solana_client::rpc_client::RpcClient;
+    use solana_client::rpc_request::RpcRequest;
+    use solana_commitment_config::CommitmentConfig;
+    use solana_sdk::pubkey::Pubkey;
+
+    use crate::accounts::{
+        IdlStorage,
+        TestMetadataConfig,
+        IDL_STORAGE_DISCRIMINATOR,
+        TEST_METADATA_CONFIG_DISCRIMINATOR,
+    };
+    use crate::types::{IdlData, SetupRequirement, SetupType, TestMetadata};
+    use crate::SolifyClient;
+
+    /// A client whose RPC answers the next account lookup with `account`.
+    fn client_serving(account: &impl BorshSerialize) -> SolifyClient {
+        client_serving_owned_by(account, &crate::SOLIFY_ID)
+    }
+
+    /// Like [`client_serving`], with the account owned by `owner`.
+    fn client_serving_owned_by(account: &impl BorshSerialize, owner: &Pubkey) -> SolifyClient {
+        let data = bs58::encode(borsh::to_vec(account).unwrap()).into_string();
+        let response = json!({
+            "context": { "slot": 1 },
+            "value": {
+                "data": [data, "base58"],
+                "executable": false,
+                "lamports": 1_000_000,
+                "owner": owner.to_string(),
+                "rentEpoch": 0
+            }
+        });
+        let mocks = HashMap::from([(RpcRequest::GetAccountInfo, response)]);
+        SolifyClient::from_rpc_client(RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks), CommitmentConfig::confirmed())
+    }
+
+    #[test]
+    fn test_raw_idl_storage_keeps_the_discriminator() {
+        let (authority, program_id) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let stored = IdlStorage {
+            discriminator: IDL_STORAGE_DISCRIMINATOR,
+            authority,
+            program_id,
+            idl_data: IdlData {
+                name: "counter".to_string(),
+                version: "0.1.0".to_string(),
+                instructions: vec![],
+                accounts: vec![],
+                types: vec![],
+                errors: vec![],
+                constants: vec![],
+                events: vec![],
+            },
+            timestamp: 1_700_000_000,
+        };
+        let raw = client_serving(&stored).fetch_idl_storage_raw(authority, program_id).unwrap().unwrap();
+        assert_eq!(raw, stored);
+        let converted = client_serving(&stored).fetch_idl_storage(authority, program_id).unwrap().unwrap();
+        assert_eq!((converted.idl_data.name.as_str(), converted.timestamp), ("counter", 1_700_000_000));
+    }
+
+    #[test]
+    fn test_idl_storage_owned_by_another_program_is_rejected() {
+        let (authority, program_id) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let stored = IdlStorage {
+            discriminator: IDL_STORAGE_DISCRIMINATOR,
+            authority,
+            program_id,
+            idl_data: IdlData {
+                name: "counter".to_string(),
+                version: "0.1.0".to_string(),
+                instructions: vec![],
+                accounts: vec![],
+                types: vec![],
+                errors: vec![],
+                constants: vec![],
+                events: vec![],
+            },
+            timestamp: 1_700_000_000,
+        };
+        let impostor = Pubkey::new_unique();
+        let err = client_serving_owned_by(&stored, &impostor)
+            .fetch_idl_storage(authority, program_id)
+            .unwrap_err();
+        assert!(err.to_string().contains(&format!("is owned by {}", impostor)), "{}", err);
+    }
+
+    #[test]
+    fn test_raw_test_metadata_keeps_the_paraphrase() {
+        let (authority, program_id) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let stored = TestMetadataConfig {
+            discriminator: TEST_METADATA_CONFIG_DISCRIMINATOR,
+            authority,
+            program_id,
+            paraphrase: "first run".to_string(),
+            program_name: "counter".to_string(),
+            test_metadata: TestMetadata {
+                instruction_order: vec!["initialize".to_string()],
+                account_dependencies: vec![],
+                pda_init_sequence: vec![],
+                setup_requirements: vec![SetupRequirement {
+                    requirement_type: SetupType::CreateKeypair,
+                    description: "Create keypair for payer".to_string(),
+                    dependencies: vec![],
+                }],
+                test_cases: vec![],
+            },
+            timestamp: 1_700_000_000,
+        };
+        let raw = client_serving(&stored).fetch_test_metadata_raw(authority, program_id, "first run").unwrap().unwrap();
+        assert_eq!(raw.paraphrase, "first run");
+        assert_eq!(raw.discriminator, TEST_METADATA_CONFIG_DISCRIMINATOR);
+        assert_eq!(raw, stored);
+        let converted = client_serving(&stored).fetch_test_metadata(authority, program_id, "first run").unwrap().unwrap();
+        assert_eq!(converted.test_metadata.instruction_order, ["initialize"]);
+        let setup = &converted.test_metadata.setup_requirements[0];
+        assert_eq!(setup.account_name.as_deref(), Some("payer"));
+    }
+}
+
+mod seed_length {
+    use solana_client::rpc_client::RpcClient;
+    use solana_commitment_config::CommitmentConfig;
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::signature::Keypair;
+
+    use crate::{derive_test_metadata_config_address, SeedTooLong, SolifyClient, MAX_SEED_LEN};
+
+    #[test]
+    fn test_paraphrase_at_the_seed_limit_derives_an_address() {
+        let paraphrase = "a".repeat(MAX_SEED_LEN);
+        let result = derive_test_metadata_config_address(&Pubkey::new_unique(), &Pubkey::new_unique(), &paraphrase);
+        assert!(result.is_ok(), "Unexpected error: {:?}", result);
+    }
+
+    #[test]
+    fn test_overlong_paraphrase_is_an_error_instead_of_a_panic() {
+        let paraphrase = "a".repeat(33);
+        let err = derive_test_metadata_config_address(&Pubkey::new_unique(), &Pubkey::new_unique(), &paraphrase)
+            .unwrap_err();
+        let too_long = err.downcast_ref::<SeedTooLong>().expect("not a SeedTooLong error");
+        assert_eq!((too_long.seed, too_long.length, too_long.limit), ("paraphrase", 33, 32));
+
+        // Rejected before anything is sent.
+        let rpc = RpcClient::new_mock("fails".to_string());
+        let client = SolifyClient::from_rpc_client(rpc, CommitmentConfig::confirmed());
+        let err = client
+            .generate_metadata(&Keypair::new(), Pubkey::new_unique(), vec![], &paraphrase, "counter")
+            .unwrap_err();
+        assert!(err.is::<SeedTooLong>(), "Unexpected error: {}", err);
+    }
+}
+
+mod rpc_headers {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    use solana_commitment_config::CommitmentConfig;
+
+    use crate::{rpc_header_map, SolifyClient};
+
+    fn api_key() -> Vec<(String, String)> {
+        vec![("x-api-key".to_string(), "secret".to_string())]
+    }
+
+    #[test]
+    fn test_rpc_header_map_keeps_default_headers() {
+        let headers = rpc_header_map(&api_key()).unwrap();
+        assert_eq!(headers.get("x-api-key").unwrap(), "secret");
+        assert!(headers.contains_key("solana-client"));
+    }
+
+    #[test]
+    fn test_rpc_header_map_rejects_invalid_names() {
+        assert!(rpc_header_map(&[("bad header".to_string(), "v".to_string())]).is_err());
+    }
+
+    #[test]
+    fn test_headers_are_sent_with_rpc_requests() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            while !String::from_utf8_lossy(&request).contains("\"getSlot\"") {
+                let n = stream.read(&mut buf).unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            let body = r#"{"jsonrpc":"2.0","result":7,"id":1}"#;
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+            String::from_utf8_lossy(&request).to_lowercase()
+        });
+
+        let client = SolifyClient::new_with_headers(&url, CommitmentConfig::confirmed(), &api_key()).unwrap();
+        assert_eq!(client.rpc().get_slot().unwrap(), 7);
+
+        let request = server.join().unwrap();
+        assert!(request.contains("x-api-key: secret"));
+    }
+}
@@ -1,5 +1,7 @@
 pub mod types;
 pub mod errors;
+pub mod naming;
 
 pub use types::*;
-pub use errors::*;
\ No newline at end of file
+pub use errors::*;
+pub use naming::*;
\ No newline at end of file
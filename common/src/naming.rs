@@ -0,0 +1,23 @@
+/// Converts an IDL name to the camelCase key Anchor's TypeScript client expects.
+/// Names that are already camelCase pass through unchanged.
+pub fn to_camel_case(s: &str) -> String {
+    s.split('_')
+        .filter(|word| !word.is_empty())
+        .enumerate()
+        .map(|(i, word)| {
+            let word = if word.chars().all(|c| !c.is_lowercase()) {
+                word.to_lowercase()
+            } else {
+                word.to_string()
+            };
+            let mut chars = word.chars();
+            let first_char = chars.next().unwrap_or_default();
+            let first_char: String = if i == 0 {
+                first_char.to_lowercase().collect()
+            } else {
+                first_char.to_uppercase().collect()
+            };
+            first_char + chars.as_str()
+        })
+        .collect()
+}
@@ -3,7 +3,7 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
 
-#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct IdlData {
     pub name: String,
     pub version: String,
@@ -20,21 +20,21 @@ pub struct IdlData {
     pub events: Vec<IdlEvent>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct IdlError {
     pub code: u32,
     pub name: String,
     pub msg: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct IdlConstant {
     pub name: String,
     pub constant_type: String,
     pub value: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct IdlEvent {
     pub name: String,
     #[serde(default)]
@@ -42,15 +42,18 @@ pub struct IdlEvent {
     pub fields: Vec<IdlField>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct IdlInstruction {
     pub name: String,
     pub accounts: Vec<IdlAccountItem>,
     pub args: Vec<IdlField>,
     pub docs: Vec<String>,
+    /// Type of the value the instruction returns through `set_return_data`, if any.
+    #[serde(default)]
+    pub returns: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct IdlAccountItem {
     pub name: String,
     pub is_mut: bool,
@@ -58,16 +61,19 @@ pub struct IdlAccountItem {
     pub is_optional: bool,
     pub docs: Vec<String>,
     pub pda: Option<IdlPda>,
+    /// Fixed address from an Anchor `address` constraint, e.g. the token program.
+    #[serde(default)]
+    pub address: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct IdlPda {
     pub seeds: Vec<IdlSeed>,
     #[serde(default)]
     pub program: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct IdlSeed {
     pub kind: String,
     #[serde(default)]
@@ -76,23 +82,26 @@ pub struct IdlSeed {
     pub value: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct IdlAccount {
     pub name: String,
     pub fields: Vec<IdlField>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct IdlField {
     pub name: String,
     pub field_type: String, 
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct IdlTypeDef {
     pub name: String,
     pub kind: String, 
     pub fields: Vec<String>, 
+    /// Type of each struct field, parallel to `fields`; empty for enums.
+    #[serde(default)]
+    pub field_types: Vec<String>,
 }
 
 
@@ -101,6 +110,12 @@ pub struct IdlTypeDef {
 pub struct ParsedIdl {
     #[serde(default)]
     pub address: String,
+    /// Program name of the pre-0.30 layout, which has no `metadata`.
+    #[serde(default)]
+    pub name: String,
+    /// Program version of the pre-0.30 layout.
+    #[serde(default)]
+    pub version: String,
     #[serde(default)]
     pub metadata: IdlMetadata,
     pub instructions: Vec<Instruction>,
@@ -143,7 +158,9 @@ pub struct EventDef {
 
 #[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize, Default)]
 pub struct IdlMetadata {
+    #[serde(default)]
     pub name: String,
+    #[serde(default)]
     pub version: String,
     #[serde(default)]
     pub spec: String,
@@ -160,6 +177,8 @@ pub struct Instruction {
     pub args: Vec<ArgumentDef>,
     #[serde(default)]
     pub docs: Vec<String>,
+    #[serde(default)]
+    pub returns: Option<IdlType>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
@@ -268,6 +287,7 @@ pub struct TypeDef {
 pub enum TypeKind {
     #[serde(rename = "struct")]
     Struct { 
+        #[serde(default, deserialize_with = "deserialize_struct_fields")]
         fields: Vec<FieldDef> 
     },
     
@@ -277,6 +297,33 @@ pub enum TypeKind {
     },
 }
 
+/// Struct fields are either named objects or, for tuple structs, bare types;
+/// tuple fields are named by their position.
+fn deserialize_struct_fields<'de, D>(deserializer: D) -> Result<Vec<FieldDef>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StructField {
+        Named(FieldDef),
+        Tuple(IdlType),
+    }
+
+    let fields = Vec::<StructField>::deserialize(deserializer)?;
+    Ok(fields
+        .into_iter()
+        .enumerate()
+        .map(|(index, field)| match field {
+            StructField::Named(field) => field,
+            StructField::Tuple(field_type) => FieldDef {
+                name: index.to_string(),
+                field_type,
+            },
+        })
+        .collect())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct EnumVariant {
     pub name: String,
@@ -286,7 +333,7 @@ pub struct EnumVariant {
 }
 
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Serialize, Deserialize)]
 pub struct TestMetadata {
     pub instruction_order: Vec<String>,
     pub account_dependencies: Vec<AccountDependency>,
@@ -295,7 +342,7 @@ pub struct TestMetadata {
     pub test_cases: Vec<InstructionTestCases>,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Serialize, Deserialize)]
 pub struct AccountDependency {
     pub account_name: String,
     pub depends_on: Vec<String>,
@@ -304,9 +351,13 @@ pub struct AccountDependency {
     pub is_mut: bool,
     pub must_be_initialized: bool,
     pub initialization_order: u8,
+    /// Instructions inferred to close the account.
+    pub closed_by: Vec<String>,
+    /// Instructions inferred to reallocate the account to a larger size.
+    pub reallocated_by: Vec<String>,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Serialize, Deserialize)]
 pub struct PdaInit {
     pub account_name: String,
     pub seeds: Vec<SeedComponent>,
@@ -314,27 +365,30 @@ pub struct PdaInit {
     pub space: Option<u64>,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Serialize, Deserialize)]
 pub struct SeedComponent {
     pub seed_type: SeedType,
     pub value: String,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SeedType {
     Static,
     AccountKey,
     Argument,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Serialize, Deserialize)]
 pub struct SetupRequirement {
     pub requirement_type: SetupType,
     pub description: String,
     pub dependencies: Vec<String>,
+    /// The account the requirement is for, e.g. the signer a keypair is created for.
+    #[serde(default)]
+    pub account_name: Option<String>,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SetupType {
     CreateKeypair,
     FundAccount,
@@ -1,25 +1,159 @@
 use anyhow::{ Context, Result };
-use std::collections::HashMap;
+use std::collections::{ hash_map::Entry, HashMap, HashSet };
 use std::fs::{ create_dir_all, File };
 use std::io::Write;
-use std::path::Path;
+use std::path::{ Path, PathBuf };
 
 use solify_common::{
+    AccountDependency,
+    IdlConstant,
     IdlData,
+    IdlError,
+    IdlEvent,
+    IdlField,
+    IdlInstruction,
+    IdlPda,
+    InstructionTestCases,
+    PdaInit,
     SeedComponent,
     SeedType,
     SetupType,
+    TestArgumentValue,
+    TestCase,
+    TestCaseType,
+    ExpectedOutcome,
     TestMetadata,
     TestValueType,
 };
 use solify_common::errors::SolifyError;
+use solify_common::to_camel_case;
 use tera::{ Tera, Context as TeraContext };
 use serde::{Serialize, Deserialize};
 
+#[cfg(test)]
+mod tests;
+
 #[derive(Serialize, Deserialize)]
 struct AccountInfo {
-    original_name: String,
     camel_name: String,
+    /// Expression passed for the account in `.accountsStrict({...})`.
+    js_var: String,
+}
+
+/// An IDL constant declared as a TypeScript `const`.
+#[derive(Debug, Serialize, Deserialize)]
+struct ProgramConstant {
+    name: String,
+    /// The initializer, e.g. `"vault"` or `new anchor.BN("1000")`.
+    value: String,
+    /// For string and byte constants, the static seed text they stand for and the
+    /// expression passed to `findProgramAddressSync` in its place.
+    #[serde(skip)]
+    seed: Option<(String, String)>,
+}
+
+/// A defined IDL type declared as a TypeScript `interface` or union `type`.
+#[derive(Debug, Serialize, Deserialize)]
+struct ProgramType {
+    name: String,
+    /// The declaration without `export`, e.g. `interface Item { ... }`.
+    declaration: String,
+}
+
+/// A setup PDA checked against the seeds the program's IDL declares for it.
+#[derive(Debug, Serialize)]
+struct PdaCheck {
+    /// `pda{id}`, the address the setup derived.
+    var_name: String,
+    /// camelCase account and instruction names, as in `program.idl`.
+    account: String,
+    instruction: String,
+    /// Object literal mapping each account its seeds name to the key the setup used.
+    accounts: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PdaSeedsInfo {
+    seeds: String,
+    program: String,
+}
+
+/// How the extra positive cases of an instruction avoid re-initializing the PDAs
+/// its first case created.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CaseIsolation {
+    /// Keypairs regenerated in each extra case, moving the PDAs they seed.
+    keypairs: Vec<String>,
+    /// PDAs re-derived from the regenerated keypairs.
+    pdas: Vec<IsolatedPda>,
+    /// Created PDAs with no keypair among their seeds; extra cases are skipped.
+    fixed_pdas: Vec<String>,
+}
+
+/// A PDA derived again inside a test body rather than once in `before()`.
+#[derive(Debug, Serialize, Deserialize)]
+struct IsolatedPda {
+    id: usize,
+    seeds: String,
+    program: String,
+}
+
+/// A writable account the instruction is inferred to close or reallocate, checked
+/// after each successful call.
+#[derive(Debug, Serialize, Deserialize)]
+struct AccountLifecycleCheck {
+    /// Expression the account is passed as.
+    js_var: String,
+    /// Expression that resolves to the account after the call; `null` once closed.
+    fetch: String,
+}
+
+/// An IDL event the instruction's docs say it emits, asserted after each
+/// successful call.
+#[derive(Debug, Serialize, Deserialize)]
+struct EventCheck {
+    /// camelCase name passed to `program.addEventListener`.
+    name: String,
+    fields: Vec<EventField>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EventField {
+    name: String,
+    /// Argument of the instruction the field is expected to echo.
+    argument: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeypairVar {
+    id: usize,
+    var_name: String,
+    /// Set when a `FundAccount` requirement airdrops to this keypair instead.
+    funded: bool,
+}
+
+/// An airdrop rendered for a `FundAccount` requirement.
+#[derive(Serialize, Deserialize)]
+struct FundingVar {
+    id: usize,
+    /// Public key expression of the funded account.
+    target: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AtaInfo {
+    var_name: String,
+    mint: String,
+    owner: String,
+}
+
+/// A required account that is neither a PDA nor a signer, e.g. a mint owned by
+/// another program. The setup cannot create it, so the tests declare a
+/// placeholder for it.
+#[derive(Serialize, Deserialize)]
+struct ExternalAccount {
+    var_name: String,
+    account: String,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -29,6 +163,167 @@ struct InstructionTestCaseWrapper {
     arguments: Vec<solify_common::ArgumentInfo>,
     positive_cases: Vec<solify_common::TestCase>,
     negative_cases: Vec<solify_common::TestCase>,
+    missing_signer_cases: Vec<MissingSignerCase>,
+    /// Keypairs passed to `.signers([...])`.
+    signers: Vec<String>,
+    validation_cases: Vec<TestCase>,
+    remaining_accounts: Vec<RemainingAccount>,
+    documents_remaining_accounts: bool,
+    isolation: CaseIsolation,
+    /// PDAs seeded by this instruction's arguments, derived from each case's values.
+    argument_pdas: Vec<IsolatedPda>,
+    /// Set when positive cases read the return value with `.view()` instead of sending.
+    view: Option<ViewCall>,
+    /// Accounts asserted closed after a successful call.
+    closed_accounts: Vec<AccountLifecycleCheck>,
+    /// Accounts asserted to have grown after a successful call.
+    reallocated_accounts: Vec<AccountLifecycleCheck>,
+    /// Events the positive cases listen for and assert were emitted.
+    events: Vec<EventCheck>,
+    /// TypeScript type of each argument whose type involves one declared with
+    /// `emit_types`, keyed by argument name; annotates its valid `*Value`s.
+    argument_types: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ViewCall {
+    /// Assertion on `result`, the value `.view()` resolved to.
+    assertion: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MissingSignerCase {
+    account_name: String,
+    signers: Vec<String>,
+    test: TestCase,
+}
+
+/// An extra account passed to an instruction through `.remainingAccounts([...])`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemainingAccount {
+    /// TypeScript expression evaluating to the account's `PublicKey`.
+    pub pubkey: String,
+    pub is_writable: bool,
+    pub is_signer: bool,
+}
+
+/// Options controlling which parts of the test file are generated.
+#[derive(Debug, Clone)]
+pub struct GenerateOptions {
+    /// Emit negative test cases (invalid arguments, missing signers).
+    pub negative_cases: bool,
+    /// Remaining accounts to pass to each instruction, keyed by instruction name.
+    pub remaining_accounts: HashMap<String, Vec<RemainingAccount>>,
+    /// Write `<program>.helpers.ts`, `<program>.setup.ts` and one
+    /// `<program>.<instruction>.test.ts` per instruction instead of a single file.
+    pub split: bool,
+    /// Emit only the keypair, airdrop and PDA setup, without any instruction tests.
+    pub init_only: bool,
+    /// Instructions whose positive cases call `.view()` even when they are not
+    /// detected as getters (read-only instructions with a return type).
+    pub view_instructions: Vec<String>,
+    /// What to do when a test file being written already exists.
+    pub overwrite: OverwritePolicy,
+    /// TypeScript expressions replacing the resolved account, keyed by instruction
+    /// name and then account name.
+    pub account_overrides: HashMap<String, HashMap<String, String>>,
+    /// Build the client from an embedded IDL and this program instead of
+    /// `anchor.workspace`, so the tests run against an already deployed program.
+    pub deployed_program: Option<DeployedProgram>,
+    /// Program name used for the test files and client instead of the IDL's
+    /// `metadata.name`, for IDLs that have none.
+    pub program_name: Option<String>,
+    /// Print the rendered files to stdout instead of writing them, e.g. to pipe
+    /// them into another tool. Split files are separated by a comment naming each.
+    pub stdout: bool,
+    /// Declare the IDL's constants at the top of the tests and use them in place
+    /// of static PDA seeds with the same value.
+    pub constants: bool,
+    /// Declare the IDL's defined types as TypeScript interfaces (structs) and
+    /// union types (enums) at the top of the tests.
+    pub emit_types: bool,
+    /// Add a test re-deriving each setup PDA from the seeds the program's IDL
+    /// declares and comparing it with the address the setup computed.
+    pub assert_pdas: bool,
+    /// Fail the run before any test when the IDL published for the deployed program
+    /// has a different version from the one the tests were generated from.
+    pub check_idl_version: bool,
+    /// How the instruction tests of the single-file output are grouped. Split mode
+    /// always writes one `describe` per instruction file.
+    pub group_by: GroupBy,
+    /// Listen for the IDL events an instruction's docs name, and assert in its
+    /// positive cases that each was emitted.
+    pub assert_events: bool,
+    /// Create the tests' keypairs from fixed seeds derived from their names
+    /// instead of at random, so every run uses the same addresses. The secret
+    /// keys can be recomputed from the test file, so only use this for tests.
+    pub deterministic_keys: bool,
+}
+
+/// A program the generated tests call by address rather than through the workspace.
+#[derive(Debug, Clone)]
+pub struct DeployedProgram {
+    /// Base58 address of the deployed program.
+    pub program_id: String,
+    /// The program's IDL as JSON, embedded verbatim in the generated client.
+    pub idl_json: String,
+}
+
+impl Default for GenerateOptions {
+    fn default() -> Self {
+        Self {
+            negative_cases: true,
+            remaining_accounts: HashMap::new(),
+            split: false,
+            init_only: false,
+            view_instructions: Vec::new(),
+            overwrite: OverwritePolicy::default(),
+            account_overrides: HashMap::new(),
+            deployed_program: None,
+            program_name: None,
+            stdout: false,
+            constants: false,
+            emit_types: false,
+            assert_pdas: false,
+            check_idl_version: false,
+            group_by: GroupBy::default(),
+            assert_events: false,
+            deterministic_keys: false,
+        }
+    }
+}
+
+/// How instruction tests are grouped into `describe` blocks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum GroupBy {
+    /// Every test sits directly in the program's `describe`.
+    #[default]
+    None,
+    /// Each instruction's tests get a nested `describe("<instruction>")`.
+    Instruction,
+}
+
+/// How existing test files, which may hold hand edits, are treated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverwritePolicy {
+    /// Fail before writing anything if any output file exists.
+    #[default]
+    Refuse,
+    /// Replace existing files.
+    Force,
+    /// Rename each existing file to `<file>.bak` before writing the new one.
+    Backup,
+}
+
+/// What a generation run wrote and what it could not handle.
+#[derive(Debug, Clone)]
+pub struct Generated {
+    /// The path [`generate_with_options`] returns.
+    pub path: PathBuf,
+    /// One note per feature the tests leave out or fill with a placeholder, such
+    /// as an argument type without test values or an account passed as `null`.
+    pub skipped: Vec<String>,
 }
 
 pub fn generate_with_tera(
@@ -36,80 +331,196 @@ pub fn generate_with_tera(
     idl: &IdlData,
     out_dir: impl AsRef<Path>
 ) -> Result<()> {
+    generate_with_options(meta, idl, out_dir, &GenerateOptions::default()).map(|_| ())
+}
+
+/// Renders the test file for `meta` into `out_dir` and returns the path written.
+/// In split mode this is the shared `<program>.setup.ts` the instruction files import.
+/// With [`GenerateOptions::stdout`] nothing is written and the path is where the
+/// file would have gone.
+pub fn generate_with_options(
+    meta: &TestMetadata,
+    idl: &IdlData,
+    out_dir: impl AsRef<Path>,
+    options: &GenerateOptions
+) -> Result<PathBuf> {
+    generate(meta, idl, out_dir, options).map(|generated| generated.path)
+}
+
+/// Like [`generate_with_options`], also returning notes on what was skipped.
+/// With [`GenerateOptions::stdout`] the tests are printed to this process's stdout.
+pub fn generate(
+    meta: &TestMetadata,
+    idl: &IdlData,
+    out_dir: impl AsRef<Path>,
+    options: &GenerateOptions
+) -> Result<Generated> {
+    generate_to(meta, idl, out_dir, options, &mut std::io::stdout().lock())
+}
+
+/// Like [`generate`], printing the tests to `stdout` rather than the process's
+/// own when [`GenerateOptions::stdout`] is set.
+pub fn generate_to(
+    meta: &TestMetadata,
+    idl: &IdlData,
+    out_dir: impl AsRef<Path>,
+    options: &GenerateOptions,
+    stdout: &mut dyn Write
+) -> Result<Generated> {
     let out_dir = out_dir.as_ref();
-    create_dir_all(out_dir).with_context(|| format!("creating output dir {:?}", out_dir))?;
+    let _span = tracing::info_span!(
+        "generate",
+        instructions = meta.test_cases.len(),
+        pdas = meta.pda_init_sequence.len(),
+    ).entered();
+    if !options.stdout {
+        create_dir_all(out_dir).with_context(|| format!("creating output dir {:?}", out_dir))?;
+    }
 
     let mut tera = Tera::default();
     tera
-        .add_raw_template("aggregated_tests.tera", AGGREGATED_TEMPLATE)
-        .context("add aggregated template")?;
+        .add_raw_templates(vec![
+            ("constants.tera", CONSTANTS_TEMPLATE),
+            ("types.tera", TYPES_TEMPLATE),
+            ("airdrop.tera", AIRDROP_TEMPLATE),
+            ("version_check.tera", VERSION_CHECK_TEMPLATE),
+            ("declarations.tera", DECLARATIONS_TEMPLATE),
+            ("setup_body.tera", SETUP_BODY_TEMPLATE),
+            ("argument_pdas.tera", ARGUMENT_PDAS_TEMPLATE),
+            ("value_annotation.tera", VALUE_ANNOTATION_TEMPLATE),
+            ("remaining_accounts.tera", REMAINING_ACCOUNTS_TEMPLATE),
+            ("instruction_tests.tera", INSTRUCTION_TESTS_TEMPLATE),
+            ("pda_checks.tera", PDA_CHECKS_TEMPLATE),
+            ("aggregated_tests.tera", AGGREGATED_TEMPLATE),
+            ("split_helpers.tera", SPLIT_HELPERS_TEMPLATE),
+            ("split_setup.tera", SPLIT_SETUP_TEMPLATE),
+            ("split_instruction.tera", SPLIT_INSTRUCTION_TEMPLATE),
+            ("split_pdas.tera", SPLIT_PDAS_TEMPLATE),
+        ])
+        .context("add test templates")?;
+    tera.register_filter("indent", indent_filter);
+    tera.register_filter("new_keypair", new_keypair_filter(options.deterministic_keys));
 
     let mut ctx = TeraContext::new();
 
-    let program_name = &idl.name;
+    let program_name = options.program_name.as_deref().unwrap_or(&idl.name).trim();
+    if program_name.is_empty() {
+        anyhow::bail!("The IDL has no program name to name the tests after; pass --name to set one");
+    }
     let program_name_pascal = cut_program_name(program_name);
     let program_capitalized = capitalize_first_letter(&program_name_pascal);
-    let program_name_camel = camel_case(program_name);
-    let program_name_pascal_case = to_pascal_case(program_name);
+    let program_name_camel = to_camel_case(program_name);
+    let program_name_pascal_case = ts_type_name(program_name)?;
     ctx.insert("program_name", program_name);
     ctx.insert("program_name_pascal", &program_name_pascal);
     ctx.insert("program_capitalized", &program_capitalized);
     ctx.insert("program_name_camel", &program_name_camel);
     ctx.insert("program_name_pascal_case", &program_name_pascal_case);
-    
+    ctx.insert("program_workspace_key", &workspace_key(program_name));
+    ctx.insert("idl_version", idl.version.trim());
+    ctx.insert("check_idl_version", &options.check_idl_version);
+    ctx.insert("group_by_instruction", &(options.group_by == GroupBy::Instruction));
+    ctx.insert("deterministic_keys", &options.deterministic_keys);
+    if let Some(deployed) = &options.deployed_program {
+        ctx.insert("deployed_program_id", &deployed.program_id);
+        ctx.insert("deployed_idl", deployed.idl_json.trim());
+    }
 
     // setup requirements
-    let setup_requirements = meta.setup_requirements.clone();
-    let mut map = HashMap::new();
-    let mut index = 0;
-
-    for setup_requirement in setup_requirements.iter().cloned() {
-        index += 1;
+    let mut keypairs: Vec<KeypairVar> = Vec::new();
+    let mut keypair_accounts: HashMap<String, String> = HashMap::new();
+    let mut fund_requirements: Vec<(usize, &str)> = Vec::new();
+    let mut pda_indices = Vec::new();
+    for (i, setup_requirement) in meta.setup_requirements.iter().enumerate() {
+        let index = i + 1;
 
         match setup_requirement.requirement_type {
             SetupType::CreateKeypair => {
-                map.insert(index, "Keypair.generate()");
+                // The first keypair signs every instruction. A payer keeps its own keypair
+                // rather than taking that role from the authority.
+                let account = setup_requirement.account_name.as_deref();
+                let var_name = match account {
+                    Some(account) if is_payer_account(account) => to_camel_case(account),
+                    _ if !keypairs.iter().any(|k| k.var_name == "authority") => "authority".to_string(),
+                    _ => format!("user{}", index),
+                };
+                if let Some(account) = account {
+                    keypair_accounts.insert(account.to_string(), var_name.clone());
+                }
+                keypairs.push(KeypairVar { id: index, var_name, funded: false });
             }
             SetupType::FundAccount => {
-                map.insert(index, "FundAccount");
+                if let Some(account) = setup_requirement.dependencies.first() {
+                    fund_requirements.push((index, account));
+                }
             }
             SetupType::InitializePda => {
-                map.insert(index, "PublicKey");
+                pda_indices.push(index);
             }
             _ => {
                 return Err(SolifyError::InvalidSetupRequirement.into());
             }
         }
     }
-    ctx.insert("setup_requirements", &map);
-
-    let mut pda_indices = Vec::new();
-    let mut index_1 = 0;
+    ctx.insert("pda_ids", &pda_indices);
 
-    for r in setup_requirements.iter().cloned() {
-        index_1 += 1;
-
-        if r.requirement_type == SetupType::InitializePda {
-            pda_indices.push(index_1);
-        }
-    }
+    let program_constants = if options.constants { program_constants(idl) } else { Vec::new() };
+    ctx.insert("program_constants", &program_constants);
+    let mut skipped = Vec::new();
+    let program_types = if options.emit_types { program_types(idl, &mut skipped) } else { Vec::new() };
+    ctx.insert("program_types", &program_types);
+    // Negatives expecting a declared error check Anchor's error number, which
+    // survives rewording of the message.
+    let error_numbers: HashMap<&str, u32> = idl.errors
+        .iter()
+        .map(|error| (error.name.as_str(), error.code))
+        .collect();
+    ctx.insert("error_numbers", &error_numbers);
 
-    // pda initialization
+    // pda initialization; PDAs seeded by an argument are derived in each test instead,
+    // where the argument value is in scope
     let mut pda_map = HashMap::new();
+    let mut argument_pda_map = HashMap::new();
     let pda_init_sequence = meta.pda_init_sequence.clone();
 
     for (i, pda_init) in pda_init_sequence.iter().enumerate() {
         if let Some(index) = pda_indices.get(i) {
-        let seeds_expr = render_pda_seeds_expression(&pda_init.seeds);
-            pda_map.insert(*index, seeds_expr);
+            let seeds_expr = render_pda_seeds_expression(&pda_init.seeds, &program_constants, &keypair_accounts);
+            let program_expr = if is_foreign_pda(idl, &pda_init.account_name) {
+                format!("new PublicKey(\"{}\")", pda_init.program_id)
+            } else {
+                "program.programId".to_string()
+            };
+            let info = PdaSeedsInfo {
+                seeds: seeds_expr,
+                program: program_expr,
+            };
+            if has_argument_seed(pda_init) {
+                argument_pda_map.insert(*index, info);
+            } else {
+                pda_map.insert(*index, info);
+            }
         }
     }
 
     ctx.insert("pda_seeds", &pda_map);
+    let pda_checks = if options.assert_pdas {
+        build_pda_checks(idl, &pda_init_sequence, &pda_indices, &pda_map, &keypair_accounts)
+    } else {
+        Vec::new()
+    };
+    ctx.insert("pda_checks", &pda_checks);
+    let pda_inits: Vec<(usize, &PdaInit)> = pda_indices
+        .iter()
+        .copied()
+        .zip(meta.pda_init_sequence.iter())
+        .collect();
 
+    // Keyed by the camelCase account name used in `.accountsStrict({...})`.
     let mut account_vars: HashMap<String, String> = HashMap::new();
 
     for ad in meta.account_dependencies.iter() {
+        let key = to_camel_case(&ad.account_name);
         if ad.is_pda {
             if
                 let Some((pos, _)) = meta.pda_init_sequence
@@ -118,60 +529,150 @@ pub fn generate_with_tera(
                     .find(|(_, p)| p.account_name == ad.account_name)
             {
                 let setup_index = pda_indices[pos];
-                account_vars.insert(ad.account_name.clone(), format!("pda{}", setup_index));
+                account_vars.insert(key, format!("pda{}", setup_index));
             } else {
-                account_vars.insert(
-                    ad.account_name.clone(),
-                    format!("/* missing pda for {} */ null", ad.account_name)
-                );
+                account_vars.insert(key, format!("/* missing pda for {} */ null", ad.account_name));
             }
-        } else if ad.account_name == "authority" {
-            account_vars.insert(ad.account_name.clone(), "authorityPubkey".to_string());
-        } else if ad.account_name == "system_program" {
-            account_vars.insert(ad.account_name.clone(), "SystemProgram.programId".to_string());
+        } else if let Some(known) = well_known_account(&ad.account_name) {
+            account_vars.insert(key, known.expression.to_string());
         } else {
-            account_vars.insert(ad.account_name.clone(), format!("{}", ad.account_name));
+            account_vars.insert(key.clone(), key);
+        }
+    }
+    for (account, var_name) in &keypair_accounts {
+        if is_payer_account(account) {
+            account_vars.insert(to_camel_case(account), format!("{}Pubkey", var_name));
         }
     }
 
     for instruction in &idl.instructions {
         for acc in &instruction.accounts {
-            if !account_vars.contains_key(&acc.name) {
-                if acc.name == "system_program" || acc.name == "systemProgram" {
-                    account_vars.insert(acc.name.clone(), "SystemProgram.programId".to_string());
-                } else if acc.name == "authority" {
-                    account_vars.insert(acc.name.clone(), "authorityPubkey".to_string());
-                } else {
-                    if let Some((pos, _)) = meta.pda_init_sequence
-                        .iter()
-                        .enumerate()
-                        .find(|(_, p)| p.account_name == acc.name)
-                    {
-                        if let Some(setup_index) = pda_indices.get(pos) {
-                            account_vars.insert(acc.name.clone(), format!("pda{}", setup_index));
-                        }
-                    }
+            let Entry::Vacant(entry) = account_vars.entry(to_camel_case(&acc.name)) else {
+                continue;
+            };
+            if let Some((pos, _)) = meta.pda_init_sequence
+                .iter()
+                .enumerate()
+                .find(|(_, p)| p.account_name == acc.name)
+            {
+                if let Some(setup_index) = pda_indices.get(pos) {
+                    entry.insert(format!("pda{}", setup_index));
                 }
+            } else if let Some(known) = well_known_account(&acc.name) {
+                entry.insert(known.expression.to_string());
+            }
+        }
+    }
+
+    // accounts pinned by an `address` constraint, unless it is the well-known
+    // program's own address
+    for acc in idl.instructions.iter().flat_map(|instruction| instruction.accounts.iter()) {
+        let Some(address) = &acc.address else {
+            continue;
+        };
+        if well_known_account(&acc.name).is_some_and(|known| known.address == Some(address.as_str())) {
+            continue;
+        }
+        account_vars.insert(to_camel_case(&acc.name), format!("new PublicKey(\"{}\")", address));
+    }
+
+    // associated token accounts
+    let mut ata_accounts: Vec<AtaInfo> = Vec::new();
+    let mut ata_names: HashSet<String> = HashSet::new();
+    for instruction in &idl.instructions {
+        for acc in &instruction.accounts {
+            let Some((owner, mint)) = acc.pda.as_ref().and_then(associated_token_seeds) else {
+                continue;
+            };
+            if !ata_names.insert(acc.name.clone()) {
+                continue;
             }
+            let var_name = format!("ata{}", ata_accounts.len() + 1);
+            let (mint, owner) = (to_camel_case(&mint), to_camel_case(&owner));
+            ata_accounts.push(AtaInfo {
+                var_name: var_name.clone(),
+                mint: account_vars.get(&mint).cloned().unwrap_or(mint),
+                owner: account_vars.get(&owner).cloned().unwrap_or(owner),
+            });
+            account_vars.insert(to_camel_case(&acc.name), var_name);
+        }
+    }
+    ctx.insert("ata_accounts", &ata_accounts);
+
+    // accounts still passed by their own name have no variable behind them
+    let mut external_accounts: Vec<ExternalAccount> = Vec::new();
+    for ad in meta.account_dependencies.iter().filter(|ad| !ad.is_pda && !keypair_accounts.contains_key(&ad.account_name)) {
+        let key = to_camel_case(&ad.account_name);
+        if account_vars.get(&key) == Some(&key) && !external_accounts.iter().any(|e| e.var_name == key) {
+            external_accounts.push(ExternalAccount { var_name: key, account: ad.account_name.clone() });
+        }
+    }
+    ctx.insert("external_accounts", &external_accounts);
+
+    let mut web3_imports = sysvar_imports(&meta.pda_init_sequence);
+    let mut token_imports = Vec::new();
+    for import in well_known_imports(&account_vars) {
+        match import {
+            AccountImport::Web3(name) => web3_imports.push(name),
+            AccountImport::SplToken(name) => token_imports.push(name),
         }
     }
+    web3_imports.sort_unstable();
+    web3_imports.dedup();
+    ctx.insert("sysvar_imports", &web3_imports);
+    ctx.insert("token_imports", &token_imports);
+    let mut spl_token_imports = token_imports.clone();
+    if !ata_accounts.is_empty() {
+        spl_token_imports.insert(0, "getAssociatedTokenAddress");
+    }
+    ctx.insert("spl_token_imports", &spl_token_imports);
+
+    // funding; a funded keypair skips the airdrop it would get on creation
+    let mut funded_accounts: Vec<FundingVar> = Vec::new();
+    for (id, account) in fund_requirements {
+        let target = match keypair_accounts.get(account) {
+            Some(var_name) => {
+                for keypair in keypairs.iter_mut().filter(|k| &k.var_name == var_name) {
+                    keypair.funded = true;
+                }
+                format!("{}Pubkey", var_name)
+            }
+            None => {
+                let key = to_camel_case(account);
+                account_vars.get(&key).cloned().unwrap_or(key)
+            }
+        };
+        funded_accounts.push(FundingVar { id, target });
+    }
+    ctx.insert("keypairs", &keypairs);
+    ctx.insert("funded_accounts", &funded_accounts);
 
     ctx.insert("account_vars", &account_vars);
+    check_account_overrides(idl, &options.account_overrides)?;
     let mut instruction_accounts: HashMap<String, Vec<AccountInfo>> = HashMap::new();
     for instruction in &idl.instructions {
+        let overrides = options.account_overrides.get(&instruction.name);
         let account_infos: Vec<AccountInfo> = instruction.accounts.iter()
             .map(|acc| {
-                AccountInfo {
-                    original_name: acc.name.clone(),
-                    camel_name: to_camel_case(&acc.name),
-                }
+                let camel_name = to_camel_case(&acc.name);
+                let js_var = overrides
+                    .and_then(|overrides| {
+                        overrides.iter().find(|(name, _)| to_camel_case(name) == camel_name)
+                    })
+                    .map(|(_, expression)| expression.clone())
+                    .or_else(|| account_vars.get(&camel_name).cloned())
+                    .unwrap_or_else(|| "null".to_string());
+                AccountInfo { camel_name, js_var }
             })
             .collect();
         instruction_accounts.insert(instruction.name.clone(), account_infos);
     }
     ctx.insert("instruction_accounts", &instruction_accounts);
 
-    let processed_test_cases: Vec<InstructionTestCaseWrapper> = meta.test_cases.iter()
+    let test_cases: &[InstructionTestCases] = if options.init_only { &[] } else { &meta.test_cases };
+    skipped.extend(skipped_features(idl, test_cases, &instruction_accounts, &external_accounts, options));
+    let processed_test_cases: Vec<InstructionTestCaseWrapper> = in_instruction_order(test_cases, &meta.instruction_order)
+        .into_iter()
         .map(|test_case| {
             let mut positive_cases = test_case.positive_cases.clone();
             for arg_value in &mut positive_cases {
@@ -180,43 +681,331 @@ pub fn generate_with_tera(
                 }
             }
             
-            let mut negative_cases = test_case.negative_cases.clone();
+            let mut negative_cases = if options.negative_cases {
+                test_case.negative_cases.clone()
+            } else {
+                Vec::new()
+            };
             for arg_value in &mut negative_cases {
                 for arg in &mut arg_value.argument_values {
                     arg.value_type = convert_to_typescript_value(arg.value_type.clone());
                 }
             }
+
+            let instruction = idl.instructions.iter().find(|i| i.name == test_case.instruction_name);
+            let signers = instruction_signers(instruction, &account_vars);
+            let missing_signer_cases = if options.negative_cases {
+                build_missing_signer_cases(idl, &test_case.instruction_name, &positive_cases, &account_vars, &signers)
+            } else {
+                Vec::new()
+            };
+
+            let validation_cases = if options.negative_cases {
+                build_argument_validation_cases(idl, &test_case.instruction_name, &test_case.arguments, &positive_cases)
+            } else {
+                Vec::new()
+            };
             
+            let remaining_accounts = options.remaining_accounts
+                .get(&test_case.instruction_name)
+                .cloned()
+                .unwrap_or_default();
+            let documents_remaining_accounts = instruction.is_some_and(documents_remaining_accounts);
+            let view = instruction
+                .filter(|i| is_view_instruction(i) || options.view_instructions.contains(&i.name))
+                .map(|i| ViewCall { assertion: view_assertion(i.returns.as_deref()) });
+            let events = match instruction {
+                Some(instruction) if options.assert_events && view.is_none() => documented_events(instruction, &idl.events),
+                _ => Vec::new(),
+            };
+            let argument_types = instruction
+                .map(|instruction| declared_argument_types(instruction, idl, &program_types))
+                .unwrap_or_default();
+
             InstructionTestCaseWrapper {
                 instruction_name: test_case.instruction_name.clone(),
                 instruction_name_camel: to_camel_case(&test_case.instruction_name),
                 arguments: test_case.arguments.clone(),
                 positive_cases,
                 negative_cases,
+                missing_signer_cases,
+                signers,
+                validation_cases,
+                remaining_accounts,
+                documents_remaining_accounts,
+                isolation: build_case_isolation(
+                    idl,
+                    &meta.instruction_order,
+                    &test_case.instruction_name,
+                    &pda_inits,
+                    &pda_map,
+                    &keypairs,
+                    &keypair_accounts,
+                ),
+                argument_pdas: build_argument_pdas(
+                    idl,
+                    &test_case.instruction_name,
+                    &pda_inits,
+                    &argument_pda_map,
+                    &program_constants,
+                    &keypair_accounts,
+                ),
+                closed_accounts: build_lifecycle_checks(
+                    idl,
+                    meta,
+                    &instruction_accounts,
+                    &test_case.instruction_name,
+                    |dep| &dep.closed_by,
+                ),
+                reallocated_accounts: build_lifecycle_checks(
+                    idl,
+                    meta,
+                    &instruction_accounts,
+                    &test_case.instruction_name,
+                    |dep| &dep.reallocated_by,
+                ),
+                view,
+                events,
+                argument_types,
             }
         })
         .collect();
     ctx.insert("instruction_tests", &processed_test_cases);
+    let argument_keypairs = collect_argument_keypairs(&processed_test_cases);
+    ctx.insert("argument_keypairs", &argument_keypairs);
+
+    if options.split {
+        let mut setup_exports: Vec<String> = Vec::new();
+        for keypair in &keypairs {
+            setup_exports.push(keypair.var_name.clone());
+            setup_exports.push(format!("{}Pubkey", keypair.var_name));
+        }
+        setup_exports.extend(argument_keypairs.iter().cloned());
+        for id in &pda_indices {
+            setup_exports.push(format!("pda{}", id));
+            setup_exports.push(format!("bump{}", id));
+        }
+        setup_exports.extend(ata_accounts.iter().map(|ata| ata.var_name.clone()));
+        setup_exports.extend(external_accounts.iter().map(|account| account.var_name.clone()));
+
+        ctx.insert("decl_prefix", "export ");
+        ctx.insert("split_prefix", &program_name_pascal);
+        ctx.insert("setup_exports", &setup_exports);
 
-    let rendered = tera.render("aggregated_tests.tera", &ctx).context("render tera")?;
+        let helpers_path = out_dir.join(format!("{}.helpers.ts", program_name_pascal));
+        let setup_path = out_dir.join(format!("{}.setup.ts", program_name_pascal));
+        let mut outputs = vec![
+            (helpers_path, render(&tera, "split_helpers.tera", &ctx)?),
+            (setup_path.clone(), render(&tera, "split_setup.tera", &ctx)?),
+        ];
+        for instr in &processed_test_cases {
+            ctx.insert("instr", instr);
+            let test_path = out_dir.join(
+                format!("{}.{}.test.ts", program_name_pascal, instr.instruction_name)
+            );
+            outputs.push((test_path, render(&tera, "split_instruction.tera", &ctx)?));
+        }
+        if !pda_checks.is_empty() {
+            let pdas_path = out_dir.join(format!("{}.pdas.test.ts", program_name_pascal));
+            outputs.push((pdas_path, render(&tera, "split_pdas.tera", &ctx)?));
+        }
+        emit_outputs(&outputs, options, stdout)?;
+        return Ok(Generated { path: setup_path, skipped });
+    }
 
+    ctx.insert("decl_prefix", "");
     let out_path = out_dir.join(format!("{}.ts", program_name_pascal));
-    let mut f = File::create(&out_path).with_context(|| format!("create file {:?}", out_path))?;
-    f.write_all(rendered.as_bytes()).with_context(|| format!("write file {:?}", out_path))?;
+    let rendered = render(&tera, "aggregated_tests.tera", &ctx)?;
+    emit_outputs(&[(out_path.clone(), rendered)], options, stdout)?;
+    Ok(Generated { path: out_path, skipped })
+}
+
+fn render(tera: &Tera, template: &str, ctx: &TeraContext) -> Result<String> {
+    tera.render(template, ctx).context("render tera")
+}
+
+/// Tera filter nesting a rendered block one level deeper: every line with content
+/// gets four more spaces.
+fn indent_filter(value: &tera::Value, _: &HashMap<String, tera::Value>) -> tera::Result<tera::Value> {
+    let text = tera::try_get_value!("indent", "value", String, value);
+    let indented: String = text
+        .split_inclusive('\n')
+        .map(|line| if line.trim().is_empty() { line.to_string() } else { format!("    {}", line) })
+        .collect();
+    Ok(tera::Value::String(indented))
+}
+
+/// Tera filter turning a keypair name into the expression that creates it. With
+/// `deterministic`, the keypair comes from a seed derived from the name and the
+/// optional `scope` argument, e.g. the test case redeclaring it.
+fn new_keypair_filter(deterministic: bool) -> impl tera::Filter {
+    move |value: &tera::Value, args: &HashMap<String, tera::Value>| {
+        let name = tera::try_get_value!("new_keypair", "value", String, value);
+        if !deterministic {
+            return Ok(tera::Value::String("Keypair.generate()".to_string()));
+        }
+        let name = match args.get("scope").and_then(tera::Value::as_str) {
+            Some(scope) => format!("{}/{}", scope, name),
+            None => name,
+        };
+        let seed: Vec<String> = keypair_seed(&name).iter().map(u8::to_string).collect();
+        Ok(tera::Value::String(format!("Keypair.fromSeed(Uint8Array.from([{}]))", seed.join(", "))))
+    }
+}
+
+/// 32 seed bytes for `name`: FNV-1a over the name and each byte's position. The
+/// function is fixed, so regenerating the tests keeps their addresses.
+fn keypair_seed(name: &str) -> [u8; 32] {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut seed = [0u8; 32];
+    for (position, byte) in seed.iter_mut().enumerate() {
+        for b in name.bytes().chain([position as u8]) {
+            hash ^= u64::from(b);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        *byte = (hash >> 32) as u8;
+    }
+    seed
+}
+
+fn emit_outputs(outputs: &[(PathBuf, String)], options: &GenerateOptions, stdout: &mut dyn Write) -> Result<()> {
+    if options.stdout {
+        write_concatenated(outputs, stdout).context("write tests to stdout")
+    } else {
+        write_outputs(outputs, options.overwrite)
+    }
+}
+
+/// Writes the rendered files one after another. When there are several, each is
+/// preceded by a comment naming the file it would have been written to.
+fn write_concatenated(outputs: &[(PathBuf, String)], out: &mut dyn Write) -> std::io::Result<()> {
+    for (i, (path, rendered)) in outputs.iter().enumerate() {
+        if outputs.len() > 1 {
+            if i > 0 {
+                writeln!(out)?;
+            }
+            let name = path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned());
+            writeln!(out, "// ===== {} =====", name)?;
+        }
+        out.write_all(rendered.as_bytes())?;
+    }
+    out.flush()
+}
 
-    println!("Wrote {}", out_path.display());
+/// Writes every rendered file. Existing files are checked up front so a refusal
+/// leaves the directory untouched rather than half regenerated.
+fn write_outputs(outputs: &[(PathBuf, String)], overwrite: OverwritePolicy) -> Result<()> {
+    if overwrite == OverwritePolicy::Refuse {
+        if let Some((existing, _)) = outputs.iter().find(|(path, _)| path.exists()) {
+            anyhow::bail!(
+                "{} already exists and may contain your edits; pass --force to overwrite it or --backup to keep a copy",
+                existing.display()
+            );
+        }
+    }
+
+    for (out_path, rendered) in outputs {
+        let merged = match std::fs::read_to_string(out_path) {
+            Ok(existing) => Some(
+                preserve_custom_regions(rendered, &existing)
+                    .with_context(|| format!("keep the custom regions of {:?}", out_path))?
+            ),
+            Err(_) => None,
+        };
+        let rendered = merged.as_ref().unwrap_or(rendered);
+        if overwrite == OverwritePolicy::Backup && out_path.exists() {
+            let backup = backup_path(out_path);
+            std::fs::rename(out_path, &backup)
+                .with_context(|| format!("back up {:?} to {:?}", out_path, backup))?;
+            tracing::info!("Backed up {} to {}", out_path.display(), backup.display());
+        }
+        let mut f = File::create(out_path).with_context(|| format!("create file {:?}", out_path))?;
+        f.write_all(rendered.as_bytes()).with_context(|| format!("write file {:?}", out_path))?;
+
+        tracing::info!("Wrote {}", out_path.display());
+    }
     Ok(())
 }
 
+/// Opens a region of a test file whose content survives regeneration, followed by
+/// the region's id, e.g. `deposit/positive-2` for the second positive case of `deposit`.
+const CUSTOM_BEGIN: &str = "// SOLIFY:BEGIN-CUSTOM";
+/// Closes the region opened by the last [`CUSTOM_BEGIN`].
+const CUSTOM_END: &str = "// SOLIFY:END-CUSTOM";
+
+/// The lines inside each closed custom region of `text`, keyed by region id. An id
+/// that repeats is an error, since only one of the regions could be carried over.
+fn custom_regions(text: &str) -> Result<HashMap<&str, String>> {
+    let mut regions = HashMap::new();
+    let mut open: Option<(&str, String)> = None;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if let Some(id) = trimmed.strip_prefix(CUSTOM_BEGIN) {
+            open = Some((id.trim(), String::new()));
+        } else if trimmed == CUSTOM_END {
+            if let Some((id, content)) = open.take() {
+                if regions.insert(id, content).is_some() {
+                    anyhow::bail!("custom region {:?} appears more than once; give each region its own id", id);
+                }
+            }
+        } else if let Some((_, content)) = &mut open {
+            content.push_str(line);
+        }
+    }
+    Ok(regions)
+}
+
+/// `rendered` with the content of each custom region replaced by that of the
+/// region with the same id in `existing`, so hand edits made there survive while
+/// everything around them is regenerated.
+fn preserve_custom_regions(rendered: &str, existing: &str) -> Result<String> {
+    let preserved = custom_regions(existing)?;
+    let mut merged = String::with_capacity(rendered.len());
+    let mut skipping = false;
+    for line in rendered.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if let Some(id) = trimmed.strip_prefix(CUSTOM_BEGIN) {
+            merged.push_str(line);
+            if let Some(content) = preserved.get(id.trim()) {
+                merged.push_str(content);
+                skipping = true;
+            }
+        } else if trimmed == CUSTOM_END {
+            merged.push_str(line);
+            skipping = false;
+        } else if !skipping {
+            merged.push_str(line);
+        }
+    }
+    Ok(merged)
+}
+
+/// `tests/counter.ts` is backed up to `tests/counter.ts.bak`.
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    PathBuf::from(backup)
+}
+
 const AGGREGATED_TEMPLATE: &str =
     r#"
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
+{%- if not deployed_program_id %}
 import { {{ program_name_pascal_case }} } from "../target/types/{{ program_name }}";
+{%- endif %}
 import { assert } from "chai";
-import { Keypair, SystemProgram, PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { Keypair, SystemProgram, PublicKey, LAMPORTS_PER_SOL{% for name in sysvar_imports %}, {{ name }}{% endfor %} } from "@solana/web3.js";
+{%- if spl_token_imports | length > 0 %}
+import { {{ spl_token_imports | join(sep=", ") }} } from "@solana/spl-token";
+{%- endif %}
 
 // This file is generated by solify. You can edit it manually
+{%- if idl_version %}
+// Generated from version {{ idl_version }} of the {{ program_name }} IDL
+{%- endif %}
+{%- include "constants.tera" %}
+{%- include "types.tera" %}
 
 describe("{{ program_name | default(value='program') }}", () => {
     // Configure the client
@@ -224,108 +1013,378 @@ describe("{{ program_name | default(value='program') }}", () => {
     anchor.setProvider(provider);
     const connection = provider.connection;
 
-    const program = anchor.workspace.{{ program_name }} as Program<{{ program_name_pascal_case }}>;
+{%- if deployed_program_id %}
+
+    // The deployed IDL, kept literal so `.accountsStrict({...})` checks the account names
+    const IDL = {{ deployed_idl }} as const;
+    const programId = new PublicKey("{{ deployed_program_id }}");
+    const program = new Program<typeof IDL>({ ...IDL, address: programId.toBase58() } as typeof IDL, provider);
+{%- else %}
+
+    const program = anchor.workspace{{ program_workspace_key }} as Program<{{ program_name_pascal_case }}>;
+{%- endif %}
+{%- include "airdrop.tera" %}
+{%- include "declarations.tera" %}
+{%- if check_idl_version %}
+
+    before(async () => {
+{%- include "version_check.tera" %}
+    });
+{%- endif %}
+
+    before(async () => {
+{%- include "setup_body.tera" %}
+    });
+{%- if pda_checks | length > 0 %}
+
+    describe("PDA seeds", () => {
+{%- include "pda_checks.tera" %}
+    });
+{%- endif %}
+
+    {%- macro render_accounts(account_list) -%}
+    {%- for acc in account_list %}
+    {%- set var = account_vars[acc] | default(value='/* missing */ null') %}
+    {{ acc }}: {{ var }}{%- if not loop.last %},{%- endif %}
+    {%- endfor %}
+    {%- endmacro %}
+
+    {# ---------------- INSTRUCTION DESCRIBE BLOCKS ---------------- #}
+    // Instructions are tested in execution order, after the ones that initialize their accounts.
+
+    {%- for instr in instruction_tests %}
+{%- if group_by_instruction %}
+
+    describe("{{ instr.instruction_name }}", () => {
+{%- filter indent %}
+{%- include "instruction_tests.tera" %}
+{%- endfilter %}
+    });
+{%- else %}
+{%- include "instruction_tests.tera" %}
+{%- endif %}
+
+    {%- endfor %}
+
+})
+
+"#;
+
+/// The program's IDL constants; `decl_prefix` is `export ` in split mode.
+const CONSTANTS_TEMPLATE: &str =
+    r#"
+{%- if program_constants | length > 0 %}
+
+// Program constants
+{%- for constant in program_constants %}
+{{ decl_prefix }}const {{ constant.name }} = {{ constant.value }};
+{%- endfor %}
+{%- endif %}"#;
+
+/// Re-derives each setup PDA from the seeds in `program.idl`, so a seed the setup
+/// orders or encodes differently from the program fails here rather than as an
+/// obscure constraint error in some instruction's test.
+const PDA_CHECKS_TEMPLATE: &str =
+    r#"
+        const camel = (name: string) => name.replace(/_([a-z0-9])/g, (_, c: string) => c.toUpperCase());
+        function assertDeclaredPda(
+            instructionName: string,
+            accountName: string,
+            address: PublicKey,
+            accounts: Record<string, PublicKey>
+        ) {
+            const instructions: anchor.IdlInstruction[] = program.idl.instructions;
+            const instruction = instructions.find((ix) => camel(ix.name) === instructionName);
+            const account = instruction?.accounts.find((acc) => camel(acc.name) === accountName);
+            if (!account || !("pda" in account) || !account.pda) {
+                assert.fail(`${accountName} has no seeds in the program's IDL`);
+            }
+            const seeds = account.pda.seeds.map((seed) => {
+                if (seed.kind === "const") {
+                    return Buffer.from(seed.value);
+                }
+                if (seed.kind === "account" && accounts[camel(seed.path)]) {
+                    return accounts[camel(seed.path)].toBuffer();
+                }
+                throw new Error(`${accountName}: cannot re-derive the ${seed.kind} seed ${seed.path ?? ""}`);
+            });
+            const programId = account.pda.program?.kind === "const"
+                ? new PublicKey(Buffer.from(account.pda.program.value))
+                : program.programId;
+            const [expected] = PublicKey.findProgramAddressSync(seeds, programId);
+            assert.ok(
+                address.equals(expected),
+                `${accountName}: the setup derived ${address.toBase58()}, the program's seeds give ${expected.toBase58()}`
+            );
+        }
+        {%- for check in pda_checks %}
+
+        it("derives {{ check.account }} from the seeds the program declares", () => {
+            assertDeclaredPda("{{ check.instruction }}", "{{ check.account }}", {{ check.var_name }}, {{ check.accounts }});
+        });
+        {%- endfor %}"#;
+
+/// The program's defined types; `decl_prefix` is `export ` in split mode.
+const TYPES_TEMPLATE: &str =
+    r#"
+{%- if program_types | length > 0 %}
+
+// Program types
+{%- for type in program_types %}
+{{ decl_prefix }}{{ type.declaration }}
+{%- endfor %}
+{%- endif %}"#;
+
+/// Compares the version of the IDL published for the program with the one the
+/// tests were generated from, before any setup runs.
+const VERSION_CHECK_TEMPLATE: &str =
+    r#"
+        const deployedIdl = await anchor.Program.fetchIdl(program.programId, provider);
+        assert.ok(deployedIdl, `No IDL is published for ${program.programId.toBase58()} to check its version against`);
+        assert.strictEqual(
+            deployedIdl.metadata.version,
+            "{{ idl_version }}",
+            "The deployed program's IDL version differs from the one these tests were generated from; regenerate them"
+        );"#;
+
+/// Airdrops 10 SOL and waits for confirmation. Public clusters rate limit airdrops,
+/// so a failed request is retried with exponential backoff.
+const AIRDROP_TEMPLATE: &str =
+    r#"
+
+    // Retries rate-limited airdrops with exponential backoff
+    {{ decl_prefix }}async function airdrop(to: PublicKey, attempts = 5): Promise<void> {
+        for (let attempt = 1; ; attempt++) {
+            try {
+                const signature = await connection.requestAirdrop(to, 10 * LAMPORTS_PER_SOL);
+                await connection.confirmTransaction(signature, "confirmed");
+                return;
+            } catch (err) {
+                if (attempt >= attempts) {
+                    throw err;
+                }
+                await new Promise((resolve) => setTimeout(resolve, 500 * 2 ** (attempt - 1)));
+            }
+        }
+    }"#;
+
+/// Keypairs, PDAs and ATAs shared by every test; `decl_prefix` is `export ` in split mode.
+const DECLARATIONS_TEMPLATE: &str =
+    r#"
 
     // Setup Requirements
-    // keypair decelarations
-    {%- set keypair_found = false %}
-    {%- for id, code in setup_requirements %}
-    {%- if code == "Keypair.generate()" %}
-    {%- if not keypair_found %}
-    {%- set keypair_found = true %}
-    const authority = Keypair.generate();
-    const authorityPubkey = authority.publicKey;
-    {%- else %}
-    const user{{ id }} = Keypair.generate();
-    const user{{ id }}Pubkey = user{{ id }}.publicKey;
-    {%- endif %}
+    // keypair declarations
+    {%- if deterministic_keys %}
+    // Keypairs come from fixed seeds so runs are reproducible. Anyone with this file
+    // can recompute their secret keys: never use them outside local tests.
     {%- endif %}
+    {%- for keypair in keypairs %}
+    {{ decl_prefix }}const {{ keypair.var_name }} = {{ keypair.var_name | new_keypair }};
+    {{ decl_prefix }}const {{ keypair.var_name }}Pubkey = {{ keypair.var_name }}.publicKey;
+    {%- endfor %}
+    {%- for keypair in argument_keypairs %}
+    {{ decl_prefix }}const {{ keypair }} = {{ keypair | new_keypair }};
     {%- endfor %}
 
-    // PDA Decelaration
-    {%- for id, code in setup_requirements %}
-    {%- if code == "PublicKey" %}
-    let pda{{ id }}: PublicKey;
-    let bump{{ id }}: number;
-    {%- endif %}
+    // PDA Declaration
+    {%- for id in pda_ids %}
+    {{ decl_prefix }}let pda{{ id }}: PublicKey;
+    {{ decl_prefix }}let bump{{ id }}: number;
     {%- endfor %}
 
-    before(async () => {
+    // ATA Declaration
+    {%- for ata in ata_accounts %}
+    {{ decl_prefix }}let {{ ata.var_name }}: PublicKey;
+    {%- endfor %}
+    {%- if external_accounts | length > 0 %}
+
+    // Accounts the setup cannot create
+    {%- for account in external_accounts %}
+    // TODO: `{{ account.account }}` must already exist on the cluster. Replace
+    // PublicKey.default with its address, or pass one with --accounts.
+    {{ decl_prefix }}const {{ account.var_name }} = PublicKey.default;
+    {%- endfor %}
+    {%- endif %}"#;
+
+/// Airdrops and address derivations run once before the tests.
+const SETUP_BODY_TEMPLATE: &str =
+    r#"
         // ----- Airdrop for each user Keypair -----
-        {%- set keypair_found_airdrop = false %}
-        {%- for id, code in setup_requirements %}
-        {%- if code == "Keypair.generate()" %}
-        {%- if not keypair_found_airdrop %}
-        {%- set keypair_found_airdrop = true %}
-        const sig{{ id }} = await connection.requestAirdrop(authorityPubkey, 10 * LAMPORTS_PER_SOL);
-        await connection.confirmTransaction(sig{{ id }}, "confirmed");
-        {%- else %}
-        const sig{{ id }} = await connection.requestAirdrop(user{{ id }}Pubkey, 10 * LAMPORTS_PER_SOL);
-        await connection.confirmTransaction(sig{{ id }}, "confirmed");
-        {%- endif %}
+        {%- for keypair in keypairs %}
+        {%- if not keypair.funded %}
+        await airdrop({{ keypair.var_name }}Pubkey);
         {%- endif %}
         {%- endfor %}
 
+        // ----- Fund accounts -----
+        {%- for funding in funded_accounts %}
+        await airdrop({{ funding.target }});
+        {%- endfor %}
+
         // ----- PDA Initialization -----
-        {%- for id, seeds in pda_seeds %}
+        {%- for id, pda in pda_seeds %}
         [pda{{ id }}, bump{{ id }}] = PublicKey.findProgramAddressSync(
-            {{ seeds }},
-            program.programId
+            {{ pda.seeds }},
+            {{ pda.program }}
         );
         {%- endfor %}
 
-    });
+        // ----- ATA Derivation -----
+        {%- for ata in ata_accounts %}
+        {{ ata.var_name }} = await getAssociatedTokenAddress({{ ata.mint }}, {{ ata.owner }});
+        {%- endfor %}
+"#;
 
-    {%- macro render_accounts(account_list) -%}
-    {%- for acc in account_list %}
-    {%- set var = account_vars[acc] | default(value='/* missing */ null') %}
-    {{ acc }}: {{ var }}{%- if not loop.last %},{%- endif %}
-    {%- endfor %}
-    {%- endmacro %}
+/// Derives `instr.argument_pdas` from the argument values declared just above.
+const ARGUMENT_PDAS_TEMPLATE: &str =
+    r#"
+        {%- for pda in instr.argument_pdas %}
+        const [pda{{ pda.id }}] = PublicKey.findProgramAddressSync(
+            {{ pda.seeds }},
+            {{ pda.program }}
+        );
+        {%- endfor %}"#;
 
-    {# ---------------- INSTRUCTION DESCRIBE BLOCKS ---------------- #}
+/// `: <type>` after the `*Value` of `arg` when it holds a valid value of a type
+/// in `instr.argument_types`.
+const VALUE_ANNOTATION_TEMPLATE: &str =
+    r#"{%- if arg.value_type.variant == "Valid" and arg.argument_name in instr.argument_types %}: {{ instr.argument_types[arg.argument_name] }}{%- endif %}"#;
 
-    {%- for instr in instruction_tests %}
+/// `instr.remaining_accounts` chained onto an instruction call, or a TODO when
+/// the instruction documents remaining accounts but none were given.
+const REMAINING_ACCOUNTS_TEMPLATE: &str =
+    r#"
+                {%- if instr.remaining_accounts | length > 0 %}
+                .remainingAccounts([
+                    {%- for remaining in instr.remaining_accounts %}
+                    { pubkey: {{ remaining.pubkey }}, isWritable: {{ remaining.is_writable }}, isSigner: {{ remaining.is_signer }} }{%- if not loop.last %},{%- endif %}
+                    {%- endfor %}
+                ])
+                {%- elif instr.documents_remaining_accounts %}
+                // TODO: this instruction documents remaining accounts; pass them with .remainingAccounts([...])
+                {%- endif %}
+"#;
 
+/// The `it(...)` blocks for a single `instr`.
+const INSTRUCTION_TESTS_TEMPLATE: &str =
+    r#"
 
     {# ---------- POSITIVE TESTS ---------- #}
     {%- for test in instr.positive_cases %}
+    {%- if not loop.first and instr.isolation.fixed_pdas | length > 0 %}
+    // {{ instr.isolation.fixed_pdas | join(sep=", ") }} only has fixed seeds and was initialized by the first
+    // case; the program cannot initialize it twice, so this case is skipped.
+    it.skip("{{ test.description }}", async () => {
+    {%- else %}
     it("{{ test.description }}", async () => {
+    {%- endif %}
+        {%- if not loop.first and instr.isolation.keypairs | length > 0 %}
+        // Fresh keypairs so this case derives its own PDAs instead of the ones initialized above
+        {%- for keypair in instr.isolation.keypairs %}
+        const {{ keypair }} = {{ keypair | new_keypair(scope=test.description) }};
+        const {{ keypair }}Pubkey = {{ keypair }}.publicKey;
+        await airdrop({{ keypair }}Pubkey);
+        {%- endfor %}
+        {%- for pda in instr.isolation.pdas %}
+        const [pda{{ pda.id }}] = PublicKey.findProgramAddressSync(
+            {{ pda.seeds }},
+            {{ pda.program }}
+        );
+        {%- endfor %}
+        {%- endif %}
         // Prepare arguments
+        {%- if test.argument_values | length > 0 %}
+        // SOLIFY:BEGIN-CUSTOM {{ instr.instruction_name }}/positive-{{ loop.index }}
+        {%- endif %}
         {%- for arg in test.argument_values %}
         {%- if arg.value_type.variant == "Valid" %}
-        const {{ arg.argument_name }}Value = {{ arg.value_type.description }};
+        const {{ arg.argument_name }}Value{% include "value_annotation.tera" %} = {{ arg.value_type.description }};
         {%- elif arg.value_type.variant == "Invalid" %}
         const {{ arg.argument_name }}Value = {{ arg.value_type.description }};
         {%- else %}
         const {{ arg.argument_name }}Value = null;
         {%- endif %}
         {%- endfor %}
+        {%- if test.argument_values | length > 0 %}
+        // SOLIFY:END-CUSTOM
+        {%- endif %}
+        {%- include "argument_pdas.tera" %}
+        {%- if not instr.view %}
+        {%- for acc in instr.reallocated_accounts %}
+        const sizeBefore{{ loop.index }} = (await connection.getAccountInfo({{ acc.js_var }}))?.data.length ?? 0;
+        {%- endfor %}
+        {%- endif %}
+        {%- for event in instr.events %}
+        const {{ event.name }}Events: Record<string, unknown>[] = [];
+        const {{ event.name }}Listener = program.addEventListener("{{ event.name }}", (event) => {{ event.name }}Events.push(event));
+        {%- endfor %}
         // Execute instruction
         try {
+            {%- if instr.view %}
+            const result = await program.methods
+            {%- else %}
             await program.methods
+            {%- endif %}
+                {%- if test.argument_values | length == 0 %}
+                .{{ instr.instruction_name_camel }}()
+                {%- else %}
                 .{{ instr.instruction_name_camel }}(
                     {%- for arg in test.argument_values %}
                     {{ arg.argument_name }}Value{%- if not loop.last %},{%- endif %}
                     {%- endfor %}
                 )
+                {%- endif %}
                 .accountsStrict({
                     {%- if instruction_accounts[instr.instruction_name] %}
                     {%- for acc_info in instruction_accounts[instr.instruction_name] %}
-                    {%- set js_var = account_vars[acc_info.original_name] | default(value="null") %}
-                    {{ acc_info.camel_name }}: {{ js_var }}{%- if not loop.last %},{%- endif %}
+                    {{ acc_info.camel_name }}: {{ acc_info.js_var }}{%- if not loop.last %},{%- endif %}
                     {%- endfor %}
                     {%- endif %}
                 })
+                {%- include "remaining_accounts.tera" %}
+                {%- if instr.view %}
+                .view();
+            // Expect the instruction's return data
+            {{ instr.view.assertion }}
+                {%- else %}
                 .signers([
-                    authority
+                    {%- for signer in instr.signers %}
+                    {{ signer }}{%- if not loop.last %},{%- endif %}
+                    {%- endfor %}
                 ])
                 .rpc();
             // Expect success
             assert.ok(true);
+            {%- for acc in instr.closed_accounts %}
+            assert.isNull(await {{ acc.fetch }}, "account should be closed");
+            {%- endfor %}
+            {%- for acc in instr.reallocated_accounts %}
+            const sizeAfter{{ loop.index }} = (await connection.getAccountInfo({{ acc.js_var }}))?.data.length ?? 0;
+            assert.isAbove(sizeAfter{{ loop.index }}, sizeBefore{{ loop.index }}, "account should have grown");
+            {%- endfor %}
+            {%- for event in instr.events %}
+            // Expect the {{ event.name }} event; listeners receive it over the websocket after confirmation
+            for (let i = 0; i < 50 && {{ event.name }}Events.length === 0; i++) {
+                await new Promise((resolve) => setTimeout(resolve, 100));
+            }
+            assert.isNotEmpty({{ event.name }}Events, "{{ event.name }} should have been emitted");
+            {%- for field in event.fields %}
+            assert.property({{ event.name }}Events[0], "{{ field.name }}");
+            {%- if field.argument %}
+            assert.strictEqual(String({{ event.name }}Events[0].{{ field.name }}), String({{ field.argument }}Value));
+            {%- endif %}
+            {%- endfor %}
+            {%- endfor %}
+                {%- endif %}
         } catch (err) {
-            assert.fail("Instruction should not have failed: " + err);
+            assert.fail("Instruction should not have failed: " + String(err));
+        }
+        {%- if instr.events | length > 0 %} finally {
+            {%- for event in instr.events %}
+            await program.removeEventListener({{ event.name }}Listener);
+            {%- endfor %}
         }
+        {%- endif %}
     });
     {%- endfor %}
     {# ---------- NEGATIVE TESTS ---------- #}
@@ -334,57 +1393,665 @@ describe("{{ program_name | default(value='program') }}", () => {
         // Prepare arguments
         {%- for arg in test.argument_values %}
         {%- if arg.value_type.variant == "Valid" %}
-        const {{ arg.argument_name }}Value = {{ arg.value_type.description }};
+        const {{ arg.argument_name }}Value{% include "value_annotation.tera" %} = {{ arg.value_type.description }};
         {%- elif arg.value_type.variant == "Invalid" %}
         const {{ arg.argument_name }}Value = {{ arg.value_type.description }};
         {%- else %}
         const {{ arg.argument_name }}Value = null;
         {%- endif %}
         {%- endfor %}
+        {%- include "argument_pdas.tera" %}
         // Execute instruction expecting failure
         try {
             await program.methods
+                {%- if test.argument_values | length == 0 %}
+                .{{ instr.instruction_name_camel }}()
+                {%- else %}
                 .{{ instr.instruction_name_camel }}(
                     {%- for arg in test.argument_values %}
                     {{ arg.argument_name }}Value{%- if not loop.last %},{%- endif %}
                     {%- endfor %}
                 )
+                {%- endif %}
                 .accountsStrict({
                     {%- if instruction_accounts[instr.instruction_name] %}
                     {%- for acc_info in instruction_accounts[instr.instruction_name] %}
-                    {%- set js_var = account_vars[acc_info.original_name] | default(value="null") %}
-                    {{ acc_info.camel_name }}: {{ js_var }}{%- if not loop.last %},{%- endif %}
+                    {{ acc_info.camel_name }}: {{ acc_info.js_var }}{%- if not loop.last %},{%- endif %}
                     {%- endfor %}
                     {%- endif %}
                 })
+                {%- include "remaining_accounts.tera" %}
                 .signers([
-                    authority
+                    {%- for signer in instr.signers %}
+                    {{ signer }}{%- if not loop.last %},{%- endif %}
+                    {%- endfor %}
                 ])
                 .rpc();
         } catch (err) {
             {%- if test.expected_outcome.variant == "Failure" %}
-            assert(err.message.includes("{{ test.expected_outcome.error_message }}"));
+            {%- if test.expected_outcome.error_code and test.expected_outcome.error_code in error_numbers %}
+            // {{ test.expected_outcome.error_code }}: {{ test.expected_outcome.error_message }}
+            assert.strictEqual((err as anchor.AnchorError).error?.errorCode?.number, {{ error_numbers[test.expected_outcome.error_code] }}, String(err));
+            {%- else %}
+            assert.include(String(err), "{{ test.expected_outcome.error_message }}");
+            {%- endif %}
             {%- endif %}
         }
     });
     {%- endfor %}
+    {# ---------- ARGUMENT VALIDATION TESTS ---------- #}
+    {%- for test in instr.validation_cases %}
+    it("{{ test.description }}", async () => {
+        // Prepare arguments
+        {%- for arg in test.argument_values %}
+        const {{ arg.argument_name }}Value{% include "value_annotation.tera" %} = {{ arg.value_type.description }};
+        {%- endfor %}
+        {%- include "argument_pdas.tera" %}
+        // Execute instruction expecting the invalid argument to be rejected
+        let failed = false;
+        try {
+            await program.methods
+                {%- if test.argument_values | length == 0 %}
+                .{{ instr.instruction_name_camel }}()
+                {%- else %}
+                .{{ instr.instruction_name_camel }}(
+                    {%- for arg in test.argument_values %}
+                    {{ arg.argument_name }}Value{%- if not loop.last %},{%- endif %}
+                    {%- endfor %}
+                )
+                {%- endif %}
+                .accountsStrict({
+                    {%- if instruction_accounts[instr.instruction_name] %}
+                    {%- for acc_info in instruction_accounts[instr.instruction_name] %}
+                    {{ acc_info.camel_name }}: {{ acc_info.js_var }}{%- if not loop.last %},{%- endif %}
+                    {%- endfor %}
+                    {%- endif %}
+                })
+                {%- include "remaining_accounts.tera" %}
+                .signers([
+                    {%- for signer in instr.signers %}
+                    {{ signer }}{%- if not loop.last %},{%- endif %}
+                    {%- endfor %}
+                ])
+                .rpc();
+        } catch (err) {
+            failed = true;
+            {%- if test.expected_outcome.error_code and test.expected_outcome.error_code in error_numbers %}
+            // {{ test.expected_outcome.error_code }}
+            assert.strictEqual((err as anchor.AnchorError).error?.errorCode?.number, {{ error_numbers[test.expected_outcome.error_code] }}, String(err));
+            {%- else %}
+            assert.instanceOf(err, anchor.AnchorError, String(err));
+            {%- endif %}
+        }
+        assert.ok(failed, "{{ test.expected_outcome.error_message }}");
+    });
+    {%- endfor %}
+    {# ---------- MISSING SIGNER TESTS ---------- #}
+    {%- for case in instr.missing_signer_cases %}
+    it("{{ case.test.description }}", async () => {
+        // Prepare arguments
+        {%- for arg in case.test.argument_values %}
+        const {{ arg.argument_name }}Value{% include "value_annotation.tera" %} = {{ arg.value_type.description }};
+        {%- endfor %}
+        {%- include "argument_pdas.tera" %}
+        // Execute instruction without the {{ case.account_name }} signature
+        let failed = false;
+        try {
+            await program.methods
+                {%- if case.test.argument_values | length == 0 %}
+                .{{ instr.instruction_name_camel }}()
+                {%- else %}
+                .{{ instr.instruction_name_camel }}(
+                    {%- for arg in case.test.argument_values %}
+                    {{ arg.argument_name }}Value{%- if not loop.last %},{%- endif %}
+                    {%- endfor %}
+                )
+                {%- endif %}
+                .accountsStrict({
+                    {%- if instruction_accounts[instr.instruction_name] %}
+                    {%- for acc_info in instruction_accounts[instr.instruction_name] %}
+                    {{ acc_info.camel_name }}: {{ acc_info.js_var }}{%- if not loop.last %},{%- endif %}
+                    {%- endfor %}
+                    {%- endif %}
+                })
+                {%- include "remaining_accounts.tera" %}
+                .signers([
+                    {%- for signer in case.signers %}
+                    {{ signer }}{%- if not loop.last %},{%- endif %}
+                    {%- endfor %}
+                ])
+                .rpc();
+        } catch (err) {
+            failed = true;
+            assert.match(String(err), /signature|signer|privilege/i);
+        }
+        assert.ok(failed, "Instruction should have failed without the {{ case.account_name }} signature");
+    });
+    {%- endfor %}"#;
+
+/// `<program>.helpers.ts` in split mode: the client shared by every file.
+const SPLIT_HELPERS_TEMPLATE: &str =
+    r#"
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+{%- if not deployed_program_id %}
+import { {{ program_name_pascal_case }} } from "../target/types/{{ program_name }}";
+{%- endif %}
+
+// This file is generated by solify. You can edit it manually
+{%- if idl_version %}
+// Generated from version {{ idl_version }} of the {{ program_name }} IDL
+{%- endif %}
+{%- include "constants.tera" %}
+{%- include "types.tera" %}
+
+export const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+export const connection = provider.connection;
+{%- if deployed_program_id %}
+
+// The deployed IDL, kept literal so `.accountsStrict({...})` checks the account names
+const IDL = {{ deployed_idl }} as const;
+export const programId = new PublicKey("{{ deployed_program_id }}");
+export const program = new Program<typeof IDL>({ ...IDL, address: programId.toBase58() } as typeof IDL, provider);
+{%- else %}
+
+export const program = anchor.workspace{{ program_workspace_key }} as Program<{{ program_name_pascal_case }}>;
+{%- endif %}
+{%- include "airdrop.tera" %}
+"#;
+
+/// `<program>.setup.ts` in split mode: shared accounts and a `setup()` that runs once.
+const SPLIT_SETUP_TEMPLATE: &str =
+    r#"
+{%- if check_idl_version %}
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+{%- endif %}
+import { Keypair, PublicKey{% for name in sysvar_imports %}, {{ name }}{% endfor %} } from "@solana/web3.js";
+{%- if ata_accounts | length > 0 %}
+import { getAssociatedTokenAddress } from "@solana/spl-token";
+{%- endif %}
+import { airdrop, program{% if check_idl_version %}, provider{% endif %}{% for constant in program_constants %}, {{ constant.name }}{% endfor %} } from "./{{ split_prefix }}.helpers";
+
+// This file is generated by solify. You can edit it manually
+{%- if idl_version %}
+// Generated from version {{ idl_version }} of the {{ program_name }} IDL
+{%- endif %}
+{%- include "declarations.tera" %}
+
+let initialized: Promise<void> | undefined;
+
+export function setup(): Promise<void> {
+    if (!initialized) {
+        initialized = initialize();
+    }
+    return initialized;
+}
+
+async function initialize(): Promise<void> {
+{%- if check_idl_version %}
+{%- include "version_check.tera" %}
+{%- endif %}
+{%- include "setup_body.tera" %}
+}
+"#;
+
+/// `<program>.pdas.test.ts` in split mode, with `--assert-pdas`.
+const SPLIT_PDAS_TEMPLATE: &str =
+    r#"
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { PublicKey{% for name in sysvar_imports %}, {{ name }}{% endfor %} } from "@solana/web3.js";
+import { program } from "./{{ split_prefix }}.helpers";
+import {
+    setup,
+    {%- for name in setup_exports %}
+    {{ name }},
+    {%- endfor %}
+} from "./{{ split_prefix }}.setup";
+
+// This file is generated by solify. You can edit it manually
+{%- if idl_version %}
+// Generated from version {{ idl_version }} of the {{ program_name }} IDL
+{%- endif %}
+
+describe("{{ program_name }}: PDA seeds", () => {
+    before(setup);
+{%- include "pda_checks.tera" %}
+});
+"#;
 
+/// `<program>.<instruction>.test.ts` in split mode.
+const SPLIT_INSTRUCTION_TEMPLATE: &str =
+    r#"
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+{%- if instr.isolation.keypairs | length > 0 %}
+import { Keypair, PublicKey, SystemProgram{% for name in sysvar_imports %}, {{ name }}{% endfor %} } from "@solana/web3.js";
+{%- elif instr.argument_pdas | length > 0 %}
+import { PublicKey, SystemProgram{% for name in sysvar_imports %}, {{ name }}{% endfor %} } from "@solana/web3.js";
+{%- else %}
+import { SystemProgram{% for name in sysvar_imports %}, {{ name }}{% endfor %} } from "@solana/web3.js";
+{%- endif %}
+{%- if token_imports | length > 0 %}
+import { {{ token_imports | join(sep=", ") }} } from "@solana/spl-token";
+{%- endif %}
+import {
+    {%- if instr.isolation.keypairs | length > 0 %} airdrop,{% endif %}
+    {%- if instr.closed_accounts | length > 0 or instr.reallocated_accounts | length > 0 %} connection,{% endif %} program
+    {%- for constant in program_constants %}, {{ constant.name }}{% endfor %}
+    {%- for type in program_types %}, {{ type.name }}{% endfor %} } from "./{{ split_prefix }}.helpers";
+import {
+    setup,
+    {%- for name in setup_exports %}
+    {{ name }},
     {%- endfor %}
+} from "./{{ split_prefix }}.setup";
 
-})
+// This file is generated by solify. You can edit it manually
+{%- if idl_version %}
+// Generated from version {{ idl_version }} of the {{ program_name }} IDL
+{%- endif %}
+
+describe("{{ program_name }}: {{ instr.instruction_name }}", () => {
+    before(setup);
+{%- include "instruction_tests.tera" %}
 
+})
 "#;
 
 // ------------------- Helper functions (rendering helpers) -------------------
 
-fn render_pda_seeds_expression(seeds: &[SeedComponent]) -> String {
+/// Builds one negative case per constrained argument: that argument gets a value
+/// violating its constraint while the others keep the first positive case's values.
+/// A case expects the IDL error naming the argument when there is one, and any
+/// error the program raises otherwise.
+fn build_argument_validation_cases(
+    idl: &IdlData,
+    instruction_name: &str,
+    arguments: &[solify_common::ArgumentInfo],
+    positive_cases: &[TestCase]
+) -> Vec<TestCase> {
+    let Some(valid_case) = positive_cases.first() else {
+        return Vec::new();
+    };
+
+    arguments
+        .iter()
+        .filter_map(|arg| {
+            let (invalid_value, violated) = invalid_value_for(arg)?;
+            let argument_values = valid_case.argument_values
+                .iter()
+                .map(|value| {
+                    if value.argument_name != arg.name {
+                        return value.clone();
+                    }
+                    TestArgumentValue {
+                        argument_name: value.argument_name.clone(),
+                        value_type: TestValueType::Invalid {
+                            description: invalid_value.clone(),
+                            reason: format!("violates {}", violated),
+                        },
+                    }
+                })
+                .collect();
+
+            Some(TestCase {
+                test_type: TestCaseType::NegativeConstraint,
+                description: format!("{} - {} violates {}", instruction_name, arg.name, violated),
+                argument_values,
+                expected_outcome: ExpectedOutcome::Failure {
+                    error_code: argument_error(idl, &arg.name).map(|error| error.name.clone()),
+                    error_message: format!("{} should be rejected when it violates {}", arg.name, violated),
+                },
+            })
+        })
+        .collect()
+}
+
+/// The IDL error whose name or message mentions `argument`, e.g. `AmountTooLarge`
+/// or "Amount must be positive" for `amount`.
+fn argument_error<'a>(idl: &'a IdlData, argument: &str) -> Option<&'a IdlError> {
+    let name = argument.replace('_', "").to_lowercase();
+    let words = argument.replace('_', " ").to_lowercase();
+    idl.errors.iter().find(|error| {
+        error.name.to_lowercase().contains(&name) || error.msg.to_lowercase().contains(&words)
+    })
+}
+
+/// Picks a TypeScript value breaking one of the argument's constraints, along with
+/// a short description of the constraint it breaks.
+fn invalid_value_for(arg: &solify_common::ArgumentInfo) -> Option<(String, String)> {
+    use solify_common::ArgumentConstraint as Constraint;
+
+    let constraints = &arg.constraints;
+    if constraints.iter().any(|c| matches!(c, Constraint::NonZero)) {
+        return Some((convert_rust_to_typescript("0"), "NonZero".to_string()));
+    }
+    constraints.iter().find_map(|constraint| {
+        match constraint {
+            Constraint::Max { value } | Constraint::Range { max: value, .. } => {
+                let invalid = value.checked_add(1)?;
+                Some((convert_rust_to_typescript(&invalid.to_string()), format!("max {}", value)))
+            }
+            Constraint::Min { value } if *value > 0 => {
+                Some((convert_rust_to_typescript(&(value - 1).to_string()), format!("min {}", value)))
+            }
+            Constraint::MaxLength { value } => {
+                Some((format!("\"a\".repeat({})", u64::from(*value) + 1), format!("max length {}", value)))
+            }
+            Constraint::MinLength { value } if *value > 0 => {
+                Some(("\"\"".to_string(), format!("min length {}", value)))
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Fails on an override naming an instruction or account the IDL does not have,
+/// which would otherwise be silently ignored.
+fn check_account_overrides(
+    idl: &IdlData,
+    overrides: &HashMap<String, HashMap<String, String>>
+) -> Result<()> {
+    for (instruction_name, accounts) in overrides {
+        let Some(instruction) = idl.instructions.iter().find(|i| &i.name == instruction_name) else {
+            anyhow::bail!("Account override for unknown instruction '{}'", instruction_name);
+        };
+        for account in accounts.keys() {
+            let camel_name = to_camel_case(account);
+            if !instruction.accounts.iter().any(|acc| to_camel_case(&acc.name) == camel_name) {
+                anyhow::bail!(
+                    "Account override for '{}.{}': {} has no account named {}",
+                    instruction_name,
+                    account,
+                    instruction_name,
+                    account
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether `account` only pays for the accounts an instruction creates, as opposed
+/// to owning them.
+fn is_payer_account(account: &str) -> bool {
+    matches!(to_camel_case(account).as_str(), "payer" | "feePayer")
+}
+
+/// Keypairs that sign `instruction`: the authority when one of its accounts is
+/// passed as `authorityPubkey`, plus a separate payer. A signer that is not an
+/// account of the transaction makes web3.js reject it as an unknown signer.
+fn instruction_signers(instruction: Option<&IdlInstruction>, account_vars: &HashMap<String, String>) -> Vec<String> {
+    let accounts = instruction.into_iter().flat_map(|instruction| instruction.accounts.iter());
+    let mut signers = Vec::new();
+    if accounts.clone().any(|acc| account_vars.get(&to_camel_case(&acc.name)).is_some_and(|var| var == "authorityPubkey")) {
+        signers.push("authority".to_string());
+    }
+    let payers = accounts.filter(|acc| acc.is_signer && is_payer_account(&acc.name));
+    for payer in payers {
+        let var_name = account_vars
+            .get(&to_camel_case(&payer.name))
+            .and_then(|var| var.strip_suffix("Pubkey"));
+        if let Some(var_name) = var_name {
+            if !signers.iter().any(|signer| signer == var_name) {
+                signers.push(var_name.to_string());
+            }
+        }
+    }
+    signers
+}
+
+/// Builds one negative case per signer account of the instruction, reusing the
+/// first positive case's arguments so that only the missing signature can fail.
+fn build_missing_signer_cases(
+    idl: &IdlData,
+    instruction_name: &str,
+    positive_cases: &[TestCase],
+    account_vars: &HashMap<String, String>,
+    signers: &[String],
+) -> Vec<MissingSignerCase> {
+    let Some(instruction) = idl.instructions.iter().find(|i| i.name == instruction_name) else {
+        return Vec::new();
+    };
+    let argument_values = match positive_cases.first() {
+        Some(case) => case.argument_values.clone(),
+        None if instruction.args.is_empty() => Vec::new(),
+        None => {
+            return Vec::new();
+        }
+    };
+
+    instruction.accounts
+        .iter()
+        .filter(|acc| acc.is_signer)
+        .map(|acc| {
+            let missing = account_vars
+                .get(&to_camel_case(&acc.name))
+                .and_then(|var| var.strip_suffix("Pubkey"));
+            let signers = signers
+                .iter()
+                .filter(|signer| Some(signer.as_str()) != missing)
+                .cloned()
+                .collect();
+
+            MissingSignerCase {
+                account_name: acc.name.clone(),
+                signers,
+                test: TestCase {
+                    test_type: TestCaseType::NegativeType,
+                    description: format!("{} - missing signer {}", instruction_name, acc.name),
+                    argument_values: argument_values.clone(),
+                    expected_outcome: ExpectedOutcome::Failure {
+                        error_code: None,
+                        error_message: format!("Missing signature for {}", acc.name),
+                    },
+                },
+            }
+        })
+        .collect()
+}
+
+/// Sorts test cases into `instruction_order`.
+///
+/// Mocha runs `describe` blocks in declaration order, so emitting them in
+/// `instruction_order` runs each instruction's tests after the tests of the
+/// instructions that create its accounts, e.g. `initEscrow` before `cancelEscrow`.
+/// Instructions missing from the order keep their place after the ordered ones.
+fn in_instruction_order<'a>(
+    test_cases: &'a [InstructionTestCases],
+    instruction_order: &[String],
+) -> Vec<&'a InstructionTestCases> {
+    let mut ordered: Vec<&InstructionTestCases> = test_cases.iter().collect();
+    ordered.sort_by_key(|test_case| {
+        instruction_order
+            .iter()
+            .position(|name| *name == test_case.instruction_name)
+            .unwrap_or(usize::MAX)
+    });
+    ordered
+}
+
+/// Keypairs the analyzer assigned to `Pubkey` arguments (`<name>Keypair.publicKey`),
+/// in first-use order, so the declarations can generate them.
+fn collect_argument_keypairs(instruction_tests: &[InstructionTestCaseWrapper]) -> Vec<String> {
+    let mut keypairs: Vec<String> = Vec::new();
+    let values = instruction_tests.iter().flat_map(|instr| {
+        instr.positive_cases
+            .iter()
+            .chain(&instr.negative_cases)
+            .chain(&instr.validation_cases)
+            .flat_map(|case| &case.argument_values)
+    });
+    for value in values {
+        let description = match &value.value_type {
+            TestValueType::Valid { description } | TestValueType::Invalid { description, .. } => description,
+        };
+        let Some(keypair) = description.strip_suffix(".publicKey").filter(|name| name.ends_with("Keypair")) else {
+            continue;
+        };
+        if keypair.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') && !keypairs.iter().any(|k| k == keypair) {
+            keypairs.push(keypair.to_string());
+        }
+    }
+    keypairs
+}
+
+/// Events named by a word of `instruction`'s docs, e.g. "Emits `Deposited`". A field
+/// named like a scalar argument is expected to carry the value passed for it.
+fn documented_events(instruction: &IdlInstruction, events: &[IdlEvent]) -> Vec<EventCheck> {
+    let words: HashSet<&str> = instruction.docs
+        .iter()
+        .flat_map(|doc| doc.split(|c: char| !(c.is_alphanumeric() || c == '_')))
+        .collect();
+    events
+        .iter()
+        .filter(|event| words.contains(event.name.as_str()))
+        .map(|event| EventCheck {
+            name: to_camel_case(&event.name),
+            fields: event.fields
+                .iter()
+                .map(|field| EventField {
+                    name: to_camel_case(&field.name),
+                    argument: instruction.args
+                        .iter()
+                        .find(|arg| to_camel_case(&arg.name) == to_camel_case(&field.name) && is_scalar_type(&arg.field_type))
+                        .map(|arg| arg.name.clone()),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Types whose values compare equal through `String(...)` on both sides.
+fn is_scalar_type(field_type: &str) -> bool {
+    matches!(
+        field_type,
+        "u8" | "u16" | "u32" | "u64" | "u128" | "i8" | "i16" | "i32" | "i64" | "i128" | "bool" | "string" | "pubkey" | "publicKey"
+    )
+}
+
+/// Getters return data without writing to any account; they are read with `.view()`,
+/// which simulates the transaction and decodes its return data.
+fn is_view_instruction(instruction: &IdlInstruction) -> bool {
+    instruction.returns.is_some() && !instruction.accounts.iter().any(|acc| acc.is_mut)
+}
+
+/// Checks the decoded return value has the shape Anchor gives `returns`.
+fn view_assertion(returns: Option<&str>) -> String {
+    match returns {
+        Some("u8" | "u16" | "u32" | "i8" | "i16" | "i32" | "f32" | "f64") => "assert.isNumber(result);".to_string(),
+        Some("u64" | "u128" | "i64" | "i128") => {
+            "assert.ok(anchor.BN.isBN(result), \"expected a BN return value\");".to_string()
+        }
+        Some("bool") => "assert.isBoolean(result);".to_string(),
+        Some("string") => "assert.isString(result);".to_string(),
+        Some("pubkey" | "publicKey") => "assert.isString(result.toBase58());".to_string(),
+        _ => "assert.isDefined(result);".to_string(),
+    }
+}
+
+/// Notes on what the tests for `test_cases` leave out: arguments without a test
+/// value, accounts passed as `null` or as a placeholder, PDA seeds of an unknown
+/// kind and documented remaining accounts nobody supplied. Each is reported once.
+fn skipped_features(
+    idl: &IdlData,
+    test_cases: &[InstructionTestCases],
+    instruction_accounts: &HashMap<String, Vec<AccountInfo>>,
+    external_accounts: &[ExternalAccount],
+    options: &GenerateOptions,
+) -> Vec<String> {
+    let mut skipped = Vec::new();
+    let mut note = |text: String| {
+        if !skipped.contains(&text) {
+            skipped.push(text);
+        }
+    };
+
+    for test_case in test_cases {
+        let name = &test_case.instruction_name;
+        let values = test_case.positive_cases.iter().flat_map(|case| &case.argument_values);
+        for value in values {
+            // The analyzer's fallback for types it has no values for.
+            if let TestValueType::Valid { description } = &value.value_type {
+                if description.trim_start().starts_with("/*") {
+                    note(format!("{}: argument `{}` has an unsupported type; a placeholder is passed", name, value.argument_name));
+                }
+            }
+        }
+
+        let Some(instruction) = idl.instructions.iter().find(|i| &i.name == name) else {
+            continue;
+        };
+        let accounts = instruction_accounts.get(name).map(Vec::as_slice).unwrap_or_default();
+        for (account, info) in instruction.accounts.iter().zip(accounts) {
+            if info.js_var.starts_with("/* missing pda") {
+                note(format!("{}: no setup PDA for account `{}`; null is passed", name, account.name));
+            } else if external_accounts.iter().any(|external| external.var_name == info.js_var) {
+                note(format!("{}: account `{}` must already exist and cannot be created in setup; see its TODO", name, account.name));
+            } else if info.js_var != "null" {
+                continue;
+            } else if account.is_optional {
+                note(format!("{}: optional account `{}` is omitted", name, account.name));
+            } else {
+                note(format!("{}: account `{}` could not be resolved; null is passed", name, account.name));
+            }
+        }
+        for account in &instruction.accounts {
+            let seeds = account.pda.iter().flat_map(|pda| &pda.seeds);
+            for seed in seeds.filter(|seed| !matches!(seed.kind.as_str(), "const" | "constant" | "arg" | "argument" | "account")) {
+                note(format!("{}: seed kind `{}` is unsupported; treated as a constant", account.name, seed.kind));
+            }
+        }
+        if documents_remaining_accounts(instruction) && !options.remaining_accounts.contains_key(name) {
+            note(format!("{}: documents remaining accounts, but none are passed", name));
+        }
+    }
+    skipped
+}
+
+/// Anchor has no IDL field for remaining accounts, so rely on the instruction docs mentioning them.
+fn documents_remaining_accounts(instruction: &IdlInstruction) -> bool {
+    instruction.docs
+        .iter()
+        .any(|doc| {
+            let doc = doc.to_lowercase();
+            doc.contains("remaining accounts") || doc.contains("remaining_accounts")
+        })
+}
+
+/// Static seeds matching one of `constants` use the constant instead of the literal.
+fn render_pda_seeds_expression(
+    seeds: &[SeedComponent],
+    constants: &[ProgramConstant],
+    keypair_accounts: &HashMap<String, String>,
+) -> String {
+    render_pda_seeds_with_args(seeds, &[], constants, keypair_accounts)
+}
+
+/// Like [`render_pda_seeds_expression`], naming argument seeds after the matching
+/// instruction argument in `args`.
+fn render_pda_seeds_with_args(
+    seeds: &[SeedComponent],
+    args: &[IdlField],
+    constants: &[ProgramConstant],
+    keypair_accounts: &HashMap<String, String>,
+) -> String {
     let parts: Vec<String> = seeds
         .iter()
         .map(|seed| {
             match seed.seed_type {
-                SeedType::Static => { format!("Buffer.from(\"{}\")", seed.value) }
-                SeedType::AccountKey => { format!("{}Pubkey.toBuffer()", seed.value) }
-                SeedType::Argument => { format!("Buffer.from(String({}))", seed.value) }
+                SeedType::Static => {
+                    match constants.iter().filter_map(|c| c.seed.as_ref()).find(|(text, _)| *text == seed.value) {
+                        Some((_, expression)) => expression.clone(),
+                        None => format!("Buffer.from(\"{}\")", seed.value),
+                    }
+                }
+                SeedType::AccountKey => format!("{}.toBuffer()", seed_account_key(&seed.value, keypair_accounts)),
+                SeedType::Argument => { format!("Buffer.from(String({}))", argument_seed_expression(&seed.value, args)) }
             }
         })
         .collect();
@@ -392,53 +2059,513 @@ fn render_pda_seeds_expression(seeds: &[SeedComponent]) -> String {
     format!("[{}]", parts.join(", "))
 }
 
-fn cut_program_name(s: &str) -> String {
-    s.split('_').next().unwrap_or(s).to_string()
+/// The address of `account` used as a PDA seed: a sysvar constant, the public key
+/// of the setup keypair created for the account, or `<account>Pubkey`.
+fn seed_account_key(account: &str, keypair_accounts: &HashMap<String, String>) -> String {
+    match sysvar_constant(account) {
+        Some(constant) => constant.to_string(),
+        None => format!("{}Pubkey", seed_keypair(account, keypair_accounts)),
+    }
 }
 
-fn capitalize_first_letter(s: &str) -> String {
-    s.chars().next().unwrap_or('A').to_uppercase().to_string() + &s[1..]
+/// The keypair variable behind an account seed; the account name when setup made none.
+fn seed_keypair<'a>(account: &'a str, keypair_accounts: &'a HashMap<String, String>) -> &'a str {
+    keypair_accounts.get(account).map_or(account, String::as_str)
 }
 
-fn camel_case(s: &str) -> String {
-    let parts: Vec<&str> = s.split('_').collect();
-    if parts.is_empty() {
-        return String::new();
+/// Where the name a well-known account's expression refers to is imported from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum AccountImport {
+    Web3(&'static str),
+    SplToken(&'static str),
+}
+
+/// An account every test passes the same way, whatever casing the IDL names it with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct WellKnownAccount {
+    /// Expression passed for the account in `.accountsStrict({...})`.
+    expression: &'static str,
+    /// The import the expression needs. `SystemProgram` is imported by every test file.
+    import: Option<AccountImport>,
+    /// The program's fixed address; an `address` constraint naming another one wins.
+    address: Option<&'static str>,
+}
+
+/// Resolves programs, sysvars and the signing authority by name, so `system_program`
+/// and `systemProgram` get the same expression. A payer is not one of them: the
+/// setup gives it a keypair of its own.
+fn well_known_account(account: &str) -> Option<WellKnownAccount> {
+    let known = |expression, import, address| Some(WellKnownAccount { expression, import, address });
+    match to_camel_case(account).as_str() {
+        "systemProgram" => known("SystemProgram.programId", None, Some(SYSTEM_PROGRAM_ID)),
+        "tokenProgram" => known(
+            "TOKEN_PROGRAM_ID",
+            Some(AccountImport::SplToken("TOKEN_PROGRAM_ID")),
+            Some(TOKEN_PROGRAM_ID)
+        ),
+        "associatedTokenProgram" => known(
+            "ASSOCIATED_TOKEN_PROGRAM_ID",
+            Some(AccountImport::SplToken("ASSOCIATED_TOKEN_PROGRAM_ID")),
+            Some(ASSOCIATED_TOKEN_PROGRAM_ID)
+        ),
+        "authority" => known("authorityPubkey", None, None),
+        // Other names, e.g. `rewards`, are too likely to be the program's own accounts
+        name if matches!(name, "rent" | "clock" | "instructions") || name.to_lowercase().contains("sysvar") => {
+            let constant = sysvar_constant(account)?;
+            known(constant, Some(AccountImport::Web3(constant)), None)
+        }
+        _ => None,
     }
-    let first = parts[0].to_lowercase();
-    let rest: String = parts[1..].iter()
-        .map(|word| {
-            if word.is_empty() {
-                String::new()
-    } else {
-                word.chars().next().unwrap().to_uppercase().to_string() + &word[1..].to_lowercase()
-            }
+}
+
+/// The imports needed by the well-known account expressions `account_vars` uses.
+fn well_known_imports(account_vars: &HashMap<String, String>) -> Vec<AccountImport> {
+    let mut imports: Vec<AccountImport> = account_vars
+        .iter()
+        .filter_map(|(account, expression)| {
+            let known = well_known_account(account)?;
+            if known.expression == expression { known.import } else { None }
         })
         .collect();
-    first + &rest
+    imports.sort_unstable();
+    imports.dedup();
+    imports
 }
 
-fn to_camel_case(s: &str) -> String {
-    let parts: Vec<&str> = s.split('_').collect();
-    if parts.is_empty() {
-        return String::new();
-    }
-    let first = parts[0].to_lowercase();
-    let rest: String = parts[1..].iter()
-        .map(|word| {
-            if word.is_empty() {
-                String::new()
-            } else {
-                let mut chars = word.chars();
-                if let Some(first_char) = chars.next() {
-                    first_char.to_uppercase().collect::<String>() + &chars.as_str().to_lowercase()
-                } else {
-                    String::new()
+/// The `@solana/web3.js` constant for a seed referring to a sysvar account such as
+/// `clock` or `sysvar_rent`; sysvars are never created by the tests.
+fn sysvar_constant(account: &str) -> Option<&'static str> {
+    let name = account.to_lowercase().replace('_', "");
+    let name = name.strip_prefix("sysvar").unwrap_or(&name);
+    let name = name.strip_suffix("sysvar").unwrap_or(name);
+    let constant = match name {
+        "clock" => "SYSVAR_CLOCK_PUBKEY",
+        "rent" => "SYSVAR_RENT_PUBKEY",
+        "instructions" => "SYSVAR_INSTRUCTIONS_PUBKEY",
+        "epochschedule" => "SYSVAR_EPOCH_SCHEDULE_PUBKEY",
+        "recentblockhashes" => "SYSVAR_RECENT_BLOCKHASHES_PUBKEY",
+        "rewards" => "SYSVAR_REWARDS_PUBKEY",
+        "slothashes" => "SYSVAR_SLOT_HASHES_PUBKEY",
+        "slothistory" => "SYSVAR_SLOT_HISTORY_PUBKEY",
+        "stakehistory" => "SYSVAR_STAKE_HISTORY_PUBKEY",
+        _ => return None,
+    };
+    Some(constant)
+}
+
+/// The sysvar constants PDA seeds refer to, to add to the `@solana/web3.js` import.
+fn sysvar_imports(pdas: &[PdaInit]) -> Vec<&'static str> {
+    let mut constants: Vec<&'static str> = pdas
+        .iter()
+        .flat_map(|pda| pda.seeds.iter())
+        .filter(|seed| matches!(seed.seed_type, SeedType::AccountKey))
+        .filter_map(|seed| sysvar_constant(&seed.value))
+        .collect();
+    constants.sort_unstable();
+    constants.dedup();
+    constants
+}
+
+/// The IDL constants that can be written as TypeScript. String and byte constants
+/// can also stand in for static seeds; other types are skipped.
+fn program_constants(idl: &IdlData) -> Vec<ProgramConstant> {
+    idl.constants
+        .iter()
+        .filter_map(|constant| {
+            let program_constant = program_constant(constant);
+            if program_constant.is_none() {
+                tracing::debug!(name = %constant.name, r#type = %constant.constant_type, "skipping constant");
+            }
+            program_constant
+        })
+        .collect()
+}
+
+fn program_constant(constant: &IdlConstant) -> Option<ProgramConstant> {
+    let name = constant.name.clone();
+    let value = constant.value.trim();
+    let (value, seed) = match constant.constant_type.as_str() {
+        "string" | "&str" => {
+            let text: String = serde_json::from_str(value).ok()?;
+            let seed_expression = format!("Buffer.from({})", name);
+            (serde_json::to_string(&text).ok()?, Some((text, seed_expression)))
+        }
+        "bytes" | "&[u8]" => {
+            let bytes = value
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .split(',')
+                .map(str::trim)
+                .filter(|byte| !byte.is_empty())
+                .map(|byte| byte.parse::<u8>().ok())
+                .collect::<Option<Vec<u8>>>()?;
+            let list = bytes.iter().map(u8::to_string).collect::<Vec<_>>().join(", ");
+            let seed = String::from_utf8(bytes).ok().map(|text| (text, name.clone()));
+            (format!("Buffer.from([{}])", list), seed)
+        }
+        "u8" | "u16" | "u32" | "i8" | "i16" | "i32" => {
+            let number = value.replace('_', "").parse::<i64>().ok()?;
+            (number.to_string(), None)
+        }
+        "u64" | "u128" | "i64" | "i128" => {
+            let number = value.replace('_', "").parse::<i128>().ok()?;
+            (format!("new anchor.BN(\"{}\")", number), None)
+        }
+        "bool" => (value.parse::<bool>().ok()?.to_string(), None),
+        _ => return None,
+    };
+    Some(ProgramConstant { name, value, seed })
+}
+
+/// One check per setup PDA an instruction of `idl` declares seeds for. Argument
+/// seeded PDAs are derived inside each test and are not checked.
+fn build_pda_checks(
+    idl: &IdlData,
+    pdas: &[PdaInit],
+    pda_indices: &[usize],
+    setup_pdas: &HashMap<usize, PdaSeedsInfo>,
+    keypair_accounts: &HashMap<String, String>,
+) -> Vec<PdaCheck> {
+    pdas.iter()
+        .zip(pda_indices)
+        .filter(|(_, index)| setup_pdas.contains_key(index))
+        .filter_map(|(pda, index)| {
+            let instruction = idl.instructions
+                .iter()
+                .find(|instruction| {
+                    instruction.accounts.iter().any(|acc| acc.name == pda.account_name && acc.pda.is_some())
+                })?;
+            let accounts: Vec<String> = pda.seeds
+                .iter()
+                .filter(|seed| matches!(seed.seed_type, SeedType::AccountKey))
+                .map(|seed| format!("{}: {}", to_camel_case(&seed.value), seed_account_key(&seed.value, keypair_accounts)))
+                .collect();
+            Some(PdaCheck {
+                var_name: format!("pda{}", index),
+                account: to_camel_case(&pda.account_name),
+                instruction: to_camel_case(&instruction.name),
+                accounts: if accounts.is_empty() { "{}".to_string() } else { format!("{{ {} }}", accounts.join(", ")) },
+            })
+        })
+        .collect()
+}
+
+/// The IDL's defined types as TypeScript. Struct fields and enum variants are
+/// camelCased the way Anchor's client decodes them; enum values are objects keyed
+/// by variant, e.g. `{ bid: {} }`. Tuple structs are skipped.
+fn program_types(idl: &IdlData, skipped: &mut Vec<String>) -> Vec<ProgramType> {
+    idl.types
+        .iter()
+        .filter_map(|type_def| {
+            let declaration = match type_def.kind.as_str() {
+                "struct" if type_def.fields.iter().any(|field| field.parse::<usize>().is_ok()) => None,
+                "struct" => {
+                    let fields: String = type_def.fields
+                        .iter()
+                        .zip(&type_def.field_types)
+                        .map(|(field, field_type)| {
+                            format!("\n    {}: {};", to_camel_case(field), ts_field_type(field_type, idl))
+                        })
+                        .collect();
+                    Some(format!("interface {} {{{}\n}}", type_def.name, fields))
+                }
+                "enum" if !type_def.fields.is_empty() => {
+                    let variants: Vec<String> = type_def.fields
+                        .iter()
+                        .map(|variant| format!("{{ {}: {{}} }}", to_camel_case(variant)))
+                        .collect();
+                    Some(format!("type {} = {};", type_def.name, variants.join(" | ")))
                 }
+                _ => None,
+            };
+            if declaration.is_none() {
+                tracing::debug!(name = %type_def.name, kind = %type_def.kind, "skipping type");
+                skipped.push(format!("type `{}`: no TypeScript declaration for this {}", type_def.name, type_def.kind));
             }
+            Some(ProgramType { name: type_def.name.clone(), declaration: declaration? })
+        })
+        .collect()
+}
+
+/// The TypeScript type of each of `instruction`'s arguments whose IDL type names
+/// one of `program_types`, e.g. `Item[]` for a `Vec<Item>`.
+fn declared_argument_types(
+    instruction: &IdlInstruction,
+    idl: &IdlData,
+    program_types: &[ProgramType]
+) -> HashMap<String, String> {
+    instruction.args
+        .iter()
+        .filter_map(|arg| {
+            let ts_type = ts_field_type(&arg.field_type, idl);
+            let names_declared_type = ts_type
+                .split(|c: char| !c.is_alphanumeric() && c != '_')
+                .any(|word| program_types.iter().any(|declared| declared.name == word));
+            names_declared_type.then(|| (arg.name.clone(), ts_type))
+        })
+        .collect()
+}
+
+/// The TypeScript type Anchor's client uses for an IDL type string such as
+/// `Vec<Option<u64>>`. Types it cannot name are `unknown`.
+fn ts_field_type(field_type: &str, idl: &IdlData) -> String {
+    if let Some(inner) = field_type.strip_prefix("Vec<").and_then(|rest| rest.strip_suffix('>')) {
+        return format!("{}[]", ts_element_type(inner, idl));
+    }
+    if let Some(inner) = field_type.strip_prefix("Option<").and_then(|rest| rest.strip_suffix('>')) {
+        return format!("{} | null", ts_field_type(inner, idl));
+    }
+    if let Some((inner, _)) = field_type.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')).and_then(|rest| rest.rsplit_once(';')) {
+        return format!("{}[]", ts_element_type(inner.trim(), idl));
+    }
+    match field_type {
+        "u8" | "u16" | "u32" | "i8" | "i16" | "i32" | "f32" | "f64" => "number".to_string(),
+        "u64" | "u128" | "u256" | "i64" | "i128" | "i256" => "anchor.BN".to_string(),
+        "bool" => "boolean".to_string(),
+        "string" => "string".to_string(),
+        "publicKey" | "pubkey" => "PublicKey".to_string(),
+        "bytes" => "Buffer".to_string(),
+        name if idl.types.iter().any(|type_def| type_def.name == name) => name.to_string(),
+        _ => "unknown".to_string(),
+    }
+}
+
+/// [`ts_field_type`] for an array element, parenthesized when it is a union.
+fn ts_element_type(field_type: &str, idl: &IdlData) -> String {
+    let element = ts_field_type(field_type, idl);
+    if element.contains(" | ") {
+        format!("({})", element)
+    } else {
+        element
+    }
+}
+
+/// The test variable holding an argument seed: `amount` becomes `amountValue` and a
+/// struct field path such as `params.id` becomes `paramsValue.id`.
+fn argument_seed_expression(path: &str, args: &[IdlField]) -> String {
+    let (root, field) = match path.split_once('.') {
+        Some((root, field)) => (root, Some(field)),
+        None => (path, None),
+    };
+    let arg = seed_argument(root, args).map_or(root, |arg| arg.name.as_str());
+    match field {
+        Some(field) => format!("{}Value.{}", arg, field),
+        None => format!("{}Value", arg),
+    }
+}
+
+/// The argument a seed path refers to. Handlers often prefix arguments they only
+/// use in constraints with `_`, while the seed keeps the `#[instruction]` name.
+fn seed_argument<'a>(root: &str, args: &'a [IdlField]) -> Option<&'a IdlField> {
+    args.iter().find(|arg| arg.name == root || arg.name.trim_start_matches('_') == root)
+}
+
+fn has_argument_seed(pda: &PdaInit) -> bool {
+    pda.seeds.iter().any(|seed| matches!(seed.seed_type, SeedType::Argument))
+}
+
+/// Accounts of `instruction_name` whose dependency lists it in `instructions`,
+/// i.e. in `closed_by` or `reallocated_by`. Accounts with an IDL account type are
+/// fetched through `program.account` so a closed one reads back as `null`.
+fn build_lifecycle_checks(
+    idl: &IdlData,
+    meta: &TestMetadata,
+    instruction_accounts: &HashMap<String, Vec<AccountInfo>>,
+    instruction_name: &str,
+    instructions: impl Fn(&AccountDependency) -> &Vec<String>,
+) -> Vec<AccountLifecycleCheck> {
+    let Some(accounts) = instruction_accounts.get(instruction_name) else {
+        return Vec::new();
+    };
+    meta.account_dependencies
+        .iter()
+        .filter(|dep| instructions(dep).iter().any(|name| name == instruction_name))
+        .filter_map(|dep| {
+            let camel_name = to_camel_case(&dep.account_name);
+            let account = accounts.iter().find(|acc| acc.camel_name == camel_name)?;
+            let fetch = match idl.accounts.iter().find(|a| to_camel_case(&a.name) == camel_name) {
+                Some(account_type) => format!(
+                    "program.account.{}.fetchNullable({})",
+                    to_camel_case(&account_type.name),
+                    account.js_var
+                ),
+                None => format!("connection.getAccountInfo({})", account.js_var),
+            };
+            Some(AccountLifecycleCheck { js_var: account.js_var.clone(), fetch })
+        })
+        .collect()
+}
+
+/// Argument-seeded PDAs the instruction passes whose argument seeds are all
+/// arguments of that instruction, so each test can derive them from its own values.
+fn build_argument_pdas(
+    idl: &IdlData,
+    instruction_name: &str,
+    pda_inits: &[(usize, &PdaInit)],
+    argument_pda_map: &HashMap<usize, PdaSeedsInfo>,
+    constants: &[ProgramConstant],
+    keypair_accounts: &HashMap<String, String>,
+) -> Vec<IsolatedPda> {
+    let Some(instruction) = idl.instructions.iter().find(|i| i.name == instruction_name) else {
+        return Vec::new();
+    };
+    pda_inits
+        .iter()
+        .filter(|(_, pda)| instruction.accounts.iter().any(|acc| acc.name == pda.account_name))
+        .filter(|(_, pda)| {
+            pda.seeds
+                .iter()
+                .filter(|seed| matches!(seed.seed_type, SeedType::Argument))
+                .all(|seed| {
+                    let root = seed.value.split('.').next().unwrap_or_default();
+                    seed_argument(root, &instruction.args).is_some()
+                })
+        })
+        .filter_map(|(id, pda)| {
+            argument_pda_map.get(id).map(|info| IsolatedPda {
+                id: *id,
+                seeds: render_pda_seeds_with_args(&pda.seeds, &instruction.args, constants, keypair_accounts),
+                program: info.program.clone(),
+            })
+        })
+        .collect()
+}
+
+/// The first instruction, in execution order, that takes `account_name` as writable.
+fn creating_instruction<'a>(idl: &'a IdlData, order: &[String], account_name: &str) -> Option<&'a str> {
+    order
+        .iter()
+        .filter_map(|name| idl.instructions.iter().find(|instruction| &instruction.name == name))
+        .chain(idl.instructions.iter())
+        .find(|instruction| {
+            instruction.accounts.iter().any(|acc| acc.name == account_name && acc.is_mut)
         })
+        .map(|instruction| instruction.name.as_str())
+}
+
+/// A second positive case of the instruction that creates a PDA would hit
+/// "account already in use" on the shared address. PDAs seeded by a setup keypair
+/// move when that keypair is regenerated for the case; PDAs with only fixed seeds
+/// cannot, and are reported so the template can skip those cases.
+fn build_case_isolation(
+    idl: &IdlData,
+    order: &[String],
+    instruction_name: &str,
+    pda_inits: &[(usize, &PdaInit)],
+    pda_map: &HashMap<usize, PdaSeedsInfo>,
+    keypairs: &[KeypairVar],
+    keypair_accounts: &HashMap<String, String>,
+) -> CaseIsolation {
+    let seeding_keypairs = |pda: &PdaInit| -> Vec<String> {
+        keypairs
+            .iter()
+            .filter(|keypair| {
+                pda.seeds.iter().any(|seed| {
+                    matches!(seed.seed_type, SeedType::AccountKey)
+                        && seed_keypair(&seed.value, keypair_accounts) == keypair.var_name
+                })
+            })
+            .map(|keypair| keypair.var_name.clone())
+            .collect()
+    };
+
+    let mut isolation = CaseIsolation::default();
+    for (_, pda) in pda_inits {
+        if creating_instruction(idl, order, &pda.account_name) != Some(instruction_name) {
+            continue;
+        }
+        let seeded_by = seeding_keypairs(pda);
+        if seeded_by.is_empty() && !has_argument_seed(pda) {
+            isolation.fixed_pdas.push(pda.account_name.clone());
+        }
+        for keypair in seeded_by {
+            if !isolation.keypairs.contains(&keypair) {
+                isolation.keypairs.push(keypair);
+            }
+        }
+    }
+
+    for (id, pda) in pda_inits {
+        let moves = seeding_keypairs(pda).iter().any(|keypair| isolation.keypairs.contains(keypair));
+        if let (true, Some(info)) = (moves, pda_map.get(id)) {
+            isolation.pdas.push(IsolatedPda {
+                id: *id,
+                seeds: info.seeds.clone(),
+                program: info.program.clone(),
+            });
+        }
+    }
+    isolation
+}
+
+/// A PDA is derived under another program when its IDL entry names that program.
+fn is_foreign_pda(idl: &IdlData, account_name: &str) -> bool {
+    idl.instructions
+        .iter()
+        .flat_map(|instruction| instruction.accounts.iter())
+        .filter(|acc| acc.name == account_name)
+        .filter_map(|acc| acc.pda.as_ref())
+        .any(|pda| !pda.program.is_empty())
+}
+
+const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+
+/// Returns the `(owner, mint)` account paths when the PDA is an associated token account.
+fn associated_token_seeds(pda: &IdlPda) -> Option<(String, String)> {
+    if pda.program != ASSOCIATED_TOKEN_PROGRAM_ID {
+        return None;
+    }
+    match pda.seeds.as_slice() {
+        [owner, _token_program, mint] if owner.kind == "account" && mint.kind == "account" => {
+            Some((owner.path.clone(), mint.path.clone()))
+        }
+        _ => None,
+    }
+}
+
+fn cut_program_name(s: &str) -> String {
+    s.split('_').next().unwrap_or(s).to_string()
+}
+
+fn capitalize_first_letter(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+/// The program's PascalCase type name as a valid TypeScript identifier. Characters
+/// an identifier cannot hold are dropped, and a leading digit gets a `Program`
+/// prefix, so `2048_game` becomes `Program2048Game`.
+fn ts_type_name(program_name: &str) -> Result<String> {
+    let name: String = to_pascal_case(program_name)
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '_' || *c == '$')
         .collect();
-    first + &rest
+    match name.chars().next() {
+        None => anyhow::bail!(
+            "Program name {:?} has no characters usable in a TypeScript type name; pass --name to set one",
+            program_name
+        ),
+        Some(first) if first.is_ascii_digit() => Ok(format!("Program{}", name)),
+        Some(_) => Ok(name),
+    }
+}
+
+/// `.name` for `anchor.workspace`, or `["name"]` when the name is not a valid
+/// identifier, e.g. `2048_game`.
+fn workspace_key(program_name: &str) -> String {
+    let mut chars = program_name.chars();
+    let is_identifier = chars.next().is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+    if is_identifier {
+        format!(".{}", program_name)
+    } else {
+        format!("[{:?}]", program_name)
+    }
 }
 
 fn to_pascal_case(s: &str) -> String {
@@ -482,6 +2609,10 @@ fn convert_rust_to_typescript(value: &str) -> String {
     let trimmed = value.trim();
     
     match trimmed {
+        "u128::MAX" => "new anchor.BN(\"340282366920938463463374607431768211455\")".to_string(),
+        "u128::MIN" => "new anchor.BN(\"0\")".to_string(),
+        "i128::MAX" => "new anchor.BN(\"170141183460469231731687303715884105727\")".to_string(),
+        "i128::MIN" => "new anchor.BN(\"-170141183460469231731687303715884105728\")".to_string(),
         "u64::MAX" => "new anchor.BN(\"18446744073709551615\")".to_string(),
         "u64::MIN" => "new anchor.BN(\"0\")".to_string(),
         "u32::MAX" => "new anchor.BN(\"4294967295\")".to_string(),
@@ -499,26 +2630,31 @@ fn convert_rust_to_typescript(value: &str) -> String {
         "i8::MAX" => "new anchor.BN(\"127\")".to_string(),
         "i8::MIN" => "new anchor.BN(\"-128\")".to_string(),
         _ => {
-            if let Ok(_) = trimmed.parse::<i128>() {
+            // Integers go through BN as strings; u128 values above i128::MAX
+            // would lose precision as JS numbers.
+            if trimmed.parse::<i128>().is_ok() || trimmed.parse::<u128>().is_ok() {
                 format!("new anchor.BN(\"{}\")", trimmed)
-            } else if let Ok(_) = trimmed.parse::<f64>() {
+            } else if trimmed.parse::<f64>().is_ok() {
                 if trimmed.contains('.') {
                     trimmed.to_string()
                 } else {
                     format!("new anchor.BN(\"{}\")", trimmed)
                 }
-            } else if trimmed.starts_with('"') && trimmed.ends_with('"') {
-                trimmed.to_string()
-            } else if trimmed == "true" || trimmed == "false" {
-                trimmed.to_string()
-            } else if trimmed.starts_with("new ") || trimmed.starts_with("authority.") || trimmed.contains("Pubkey") {
+            } else if trimmed.starts_with('"')
+                || trimmed.starts_with('[')
+                || trimmed.starts_with('{')
+                || trimmed.starts_with("Array.")
+                || trimmed == "true"
+                || trimmed == "false"
+                || trimmed == "null"
+                || trimmed.starts_with("new ")
+                || trimmed.starts_with("authority.")
+                || trimmed.ends_with(".publicKey")
+                || trimmed.contains("Pubkey")
+            {
                 trimmed.to_string()
             } else {
-                if trimmed.starts_with('"') {
-                    trimmed.to_string()
-                } else {
-                    format!("\"{}\"", trimmed)
-                }
+                format!("\"{}\"", trimmed)
             }
         }
     }
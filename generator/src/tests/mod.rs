@@ -0,0 +1,1502 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use solify_common::{
+    AccountDependency,
+    ArgumentConstraint,
+    ArgumentInfo,
+    ArgumentType,
+    ExpectedOutcome,
+    IdlAccount,
+    IdlAccountItem,
+    IdlConstant,
+    IdlData,
+    IdlError,
+    IdlEvent,
+    IdlField,
+    IdlInstruction,
+    IdlPda,
+    IdlSeed,
+    IdlTypeDef,
+    InstructionTestCases,
+    PdaInit,
+    SeedComponent,
+    SeedType,
+    SetupRequirement,
+    SetupType,
+    TestArgumentValue,
+    TestCase,
+    TestCaseType,
+    TestMetadata,
+    TestValueType,
+};
+
+use crate::{
+    backup_path,
+    generate,
+    generate_to,
+    generate_with_options,
+    generate_with_tera,
+    DeployedProgram,
+    GenerateOptions,
+    GroupBy,
+    OverwritePolicy,
+    RemainingAccount,
+    write_concatenated,
+};
+
+fn account(name: &str, is_signer: bool, pda: Option<IdlPda>) -> IdlAccountItem {
+    IdlAccountItem {
+        name: name.to_string(),
+        is_mut: true,
+        is_signer,
+        is_optional: false,
+        docs: vec![],
+        pda,
+        address: None,
+    }
+}
+
+fn seed(kind: &str, path: &str, value: &str) -> IdlSeed {
+    IdlSeed {
+        kind: kind.to_string(),
+        path: path.to_string(),
+        value: value.to_string(),
+    }
+}
+
+fn ata_pda(owner: &str, mint: &str) -> IdlPda {
+    IdlPda {
+        seeds: vec![
+            seed("account", owner, ""),
+            seed("const", "", "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
+            seed("account", mint, ""),
+        ],
+        program: "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL".to_string(),
+    }
+}
+
+fn token_idl() -> IdlData {
+    IdlData {
+        name: "token_vault".to_string(),
+        version: "0.1.0".to_string(),
+        instructions: vec![IdlInstruction {
+            name: "deposit".to_string(),
+            accounts: vec![
+                account("authority", true, None),
+                account("mint", false, None),
+                account("authority_token_account", false, Some(ata_pda("authority", "mint"))),
+            ],
+            args: vec![],
+            docs: vec![],
+            returns: None,
+        }],
+        accounts: vec![],
+        types: vec![],
+        errors: vec![],
+        constants: vec![],
+        events: vec![],
+    }
+}
+
+fn token_metadata() -> TestMetadata {
+    TestMetadata {
+        instruction_order: vec!["deposit".to_string()],
+        // A mint is neither a PDA nor a signer, so nothing in the setup creates it.
+        account_dependencies: vec![lifecycle_dependency("mint", &[], &[])],
+        pda_init_sequence: vec![],
+        setup_requirements: vec![],
+        test_cases: vec![InstructionTestCases {
+            instruction_name: "deposit".to_string(),
+            arguments: vec![],
+            positive_cases: vec![TestCase {
+                test_type: TestCaseType::Positive,
+                description: "deposit succeeds".to_string(),
+                argument_values: vec![],
+                expected_outcome: ExpectedOutcome::Success { state_changes: vec![] },
+            }],
+            negative_cases: vec![],
+        }],
+    }
+}
+
+#[test]
+fn test_generate_derives_associated_token_accounts() {
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&token_metadata(), &token_idl(), out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(rendered.contains("import { getAssociatedTokenAddress } from \"@solana/spl-token\";"));
+    assert!(rendered.contains("let ata1: PublicKey;"));
+    let mint = rendered.find("const mint = PublicKey.default;").expect(&rendered);
+    let derivation = rendered.find("ata1 = await getAssociatedTokenAddress(mint, authorityPubkey);").expect(&rendered);
+    assert!(mint < derivation, "mint must be declared before the ATA derivation uses it");
+    assert!(rendered.contains("authorityTokenAccount: ata1"));
+}
+
+#[test]
+fn test_generate_missing_signer_negative_case() {
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&token_metadata(), &token_idl(), out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(rendered.contains("it(\"deposit - missing signer authority\""));
+    assert!(rendered.contains("// Execute instruction without the authority signature"));
+    assert!(rendered.contains("assert.match(String(err), /signature|signer|privilege/i);"));
+}
+
+#[test]
+fn test_generate_skips_missing_signer_case_without_negative_cases() {
+    let out_dir = tempfile::tempdir().unwrap();
+    let options = GenerateOptions { negative_cases: false, ..Default::default() };
+    generate_with_options(&token_metadata(), &token_idl(), out_dir.path(), &options).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(!rendered.contains("missing signer"));
+}
+
+#[test]
+fn test_generate_derives_foreign_pda_with_its_program_id() {
+    let foreign_program = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
+    let mut idl = token_idl();
+    idl.instructions[0].accounts.push(account(
+        "metadata",
+        false,
+        Some(IdlPda {
+            seeds: vec![seed("const", "", "metadata"), seed("account", "mint", "")],
+            program: foreign_program.to_string(),
+        }),
+    ));
+
+    let mut meta = token_metadata();
+    meta.setup_requirements = vec![SetupRequirement {
+        requirement_type: SetupType::InitializePda,
+        description: "Initialize metadata".to_string(),
+        dependencies: vec![],
+        account_name: Some("metadata".to_string()),
+    }];
+    meta.pda_init_sequence = vec![PdaInit {
+        account_name: "metadata".to_string(),
+        seeds: vec![
+            SeedComponent { seed_type: SeedType::Static, value: "metadata".to_string() },
+            SeedComponent { seed_type: SeedType::AccountKey, value: "mint".to_string() },
+        ],
+        program_id: foreign_program.to_string(),
+        space: None,
+    }];
+
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &idl, out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(rendered.contains(&format!("new PublicKey(\"{}\")", foreign_program)));
+    assert!(!rendered.contains("program.programId\n"));
+}
+
+fn argument(name: &str, arg_type: ArgumentType, constraints: Vec<ArgumentConstraint>) -> ArgumentInfo {
+    ArgumentInfo {
+        name: name.to_string(),
+        arg_type,
+        constraints,
+        is_optional: false,
+    }
+}
+
+fn valid(name: &str, value: &str) -> TestArgumentValue {
+    TestArgumentValue {
+        argument_name: name.to_string(),
+        value_type: TestValueType::Valid { description: value.to_string() },
+    }
+}
+
+#[test]
+fn test_generate_one_validation_case_per_constrained_argument() {
+    let mut meta = token_metadata();
+    let case = &mut meta.test_cases[0];
+    case.arguments = vec![
+        argument("amount", ArgumentType::U64, vec![
+            ArgumentConstraint::Min { value: 0 },
+            ArgumentConstraint::NonZero,
+        ]),
+        argument("memo", ArgumentType::String { max_length: Some(100) }, vec![
+            ArgumentConstraint::MaxLength { value: 100 },
+            ArgumentConstraint::MinLength { value: 1 },
+        ]),
+        argument("flag", ArgumentType::Bool, vec![]),
+    ];
+    case.positive_cases[0].argument_values = vec![
+        valid("amount", "1000"),
+        valid("memo", "\"hello\""),
+        valid("flag", "true"),
+    ];
+
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &token_idl(), out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert_eq!(rendered.matches("it(\"deposit - amount violates").count(), 1);
+    assert_eq!(rendered.matches("it(\"deposit - memo violates").count(), 1);
+    assert!(!rendered.contains("deposit - flag violates"));
+
+    let amount_test = rendered.split("it(\"deposit - amount violates NonZero\"").nth(1).unwrap();
+    let amount_test = amount_test.split("it(").next().unwrap();
+    assert!(amount_test.contains("const amountValue = new anchor.BN(\"0\");"));
+    assert!(amount_test.contains("const memoValue = \"hello\";"));
+    assert!(amount_test.contains("const flagValue = true;"));
+
+    let memo_test = rendered.split("it(\"deposit - memo violates max length 100\"").nth(1).unwrap();
+    let memo_test = memo_test.split("it(").next().unwrap();
+    assert!(memo_test.contains("const memoValue = \"a\".repeat(101);"));
+    assert!(memo_test.contains("const amountValue = new anchor.BN(\"1000\");"));
+    // Without an IDL error to expect, the program must still be the one rejecting it.
+    let memo_test = test_body(&rendered, "deposit - memo violates max length 100");
+    assert!(memo_test.contains("assert.instanceOf(err, anchor.AnchorError, String(err));"), "{}", memo_test);
+}
+
+#[test]
+fn test_validation_case_expects_the_idl_error_naming_its_argument() {
+    let mut meta = token_metadata();
+    let case = &mut meta.test_cases[0];
+    case.arguments = vec![argument("amount", ArgumentType::U64, vec![ArgumentConstraint::NonZero])];
+    case.positive_cases[0].argument_values = vec![valid("amount", "1000")];
+    let mut idl = token_idl();
+    idl.errors = vec![
+        IdlError { code: 6000, name: "Unauthorized".to_string(), msg: "Signer is not the owner".to_string() },
+        IdlError { code: 6001, name: "InvalidAmount".to_string(), msg: "Amount must be positive".to_string() },
+    ];
+
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &idl, out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    let amount_test = test_body(&rendered, "deposit - amount violates NonZero");
+    assert!(amount_test.contains("// InvalidAmount"), "{}", amount_test);
+    assert!(
+        amount_test.contains("assert.strictEqual((err as anchor.AnchorError).error?.errorCode?.number, 6001, String(err));"),
+        "{}",
+        amount_test
+    );
+}
+
+#[test]
+fn test_generate_skips_validation_cases_without_negative_cases() {
+    let mut meta = token_metadata();
+    meta.test_cases[0].arguments = vec![
+        argument("amount", ArgumentType::U64, vec![ArgumentConstraint::NonZero]),
+    ];
+    meta.test_cases[0].positive_cases[0].argument_values = vec![valid("amount", "1000")];
+
+    let out_dir = tempfile::tempdir().unwrap();
+    let options = GenerateOptions { negative_cases: false, ..Default::default() };
+    generate_with_options(&meta, &token_idl(), out_dir.path(), &options).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(!rendered.contains("violates"));
+}
+
+#[test]
+fn test_generate_makes_program_names_valid_typescript_identifiers() {
+    let mut idl = token_idl();
+    idl.name = "2048_game".to_string();
+    let out_dir = tempfile::tempdir().unwrap();
+    let path = generate_with_options(&token_metadata(), &idl, out_dir.path(), &GenerateOptions::default()).unwrap();
+
+    let rendered = std::fs::read_to_string(path).unwrap();
+    assert!(rendered.contains("import { Program2048Game } from \"../target/types/2048_game\";"));
+    assert!(rendered.contains("anchor.workspace[\"2048_game\"] as Program<Program2048Game>;"));
+
+    idl.name = "!!!".to_string();
+    let err = generate_with_tera(&token_metadata(), &idl, out_dir.path()).unwrap_err();
+    assert!(err.to_string().contains("no characters usable in a TypeScript type name"), "Unexpected error: {}", err);
+}
+
+#[test]
+fn test_generate_keys_snake_case_accounts_by_camel_case() {
+    let mut idl = token_idl();
+    idl.instructions[0].accounts.push(account(
+        "user_profile",
+        false,
+        Some(IdlPda { seeds: vec![seed("const", "", "profile")], program: String::new() }),
+    ));
+    let mut meta = token_metadata();
+    meta.setup_requirements.push(SetupRequirement {
+        requirement_type: SetupType::InitializePda,
+        description: "Initialize user_profile".to_string(),
+        dependencies: vec![],
+        account_name: Some("user_profile".to_string()),
+    });
+    meta.pda_init_sequence.push(PdaInit {
+        account_name: "user_profile".to_string(),
+        seeds: vec![SeedComponent { seed_type: SeedType::Static, value: "profile".to_string() }],
+        program_id: String::new(),
+        space: None,
+    });
+
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &idl, out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(rendered.contains("userProfile: pda1"));
+    assert!(!rendered.contains("user_profile:"));
+}
+
+#[test]
+fn test_generate_passes_remaining_accounts_override() {
+    let mut options = GenerateOptions::default();
+    options.remaining_accounts.insert("deposit".to_string(), vec![RemainingAccount {
+        pubkey: "mint".to_string(),
+        is_writable: false,
+        is_signer: false,
+    }]);
+
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_options(&token_metadata(), &token_idl(), out_dir.path(), &options).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(rendered.contains(".remainingAccounts(["));
+    assert!(rendered.contains("{ pubkey: mint, isWritable: false, isSigner: false }"));
+}
+
+#[test]
+fn test_generate_flags_documented_remaining_accounts() {
+    let mut idl = token_idl();
+    idl.instructions[0].docs = vec!["Vault token accounts are passed as remaining accounts.".to_string()];
+
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&token_metadata(), &idl, out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(rendered.contains("// TODO: this instruction documents remaining accounts"));
+    assert!(!rendered.contains("{ pubkey:"));
+}
+
+#[test]
+fn test_generate_emits_no_implicit_or_explicit_any() {
+    let (idl, meta) = vault_init_fixture(vec![
+        SeedComponent { seed_type: SeedType::Static, value: "vault".to_string() },
+        SeedComponent { seed_type: SeedType::AccountKey, value: "authority".to_string() },
+    ]);
+    let out_dir = tempfile::tempdir().unwrap();
+    let options = GenerateOptions { assert_pdas: true, assert_events: true, ..Default::default() };
+    generate_with_options(&meta, &idl, out_dir.path(), &options).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(rendered.contains("as Program<TokenVault>;"));
+    assert!(rendered.contains("assertDeclaredPda("), "{}", rendered);
+    assert!(!rendered.contains(": any"), "{}", rendered);
+    assert!(!rendered.contains("<any>"));
+    assert!(!rendered.contains("as any"));
+    assert!(!rendered.contains("err.message"));
+}
+
+#[test]
+fn test_generate_applies_account_overrides() {
+    let out_dir = tempfile::tempdir().unwrap();
+    let overrides = HashMap::from([(
+        "deposit".to_string(),
+        HashMap::from([("mint".to_string(), "new PublicKey(MINT_ADDRESS)".to_string())]),
+    )]);
+    let options = GenerateOptions { account_overrides: overrides, ..Default::default() };
+    generate_with_options(&token_metadata(), &token_idl(), out_dir.path(), &options).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(rendered.contains("mint: new PublicKey(MINT_ADDRESS),"));
+    assert!(rendered.contains("authority: authorityPubkey,"));
+}
+
+#[test]
+fn test_generate_rejects_overrides_for_unknown_accounts() {
+    let out_dir = tempfile::tempdir().unwrap();
+    let overrides = HashMap::from([(
+        "deposit".to_string(),
+        HashMap::from([("recipient".to_string(), "recipientPubkey".to_string())]),
+    )]);
+    let options = GenerateOptions { account_overrides: overrides, ..Default::default() };
+    let err = generate_with_options(&token_metadata(), &token_idl(), out_dir.path(), &options).unwrap_err();
+    assert!(err.to_string().contains("deposit has no account named recipient"), "{}", err);
+}
+
+#[test]
+fn test_generate_deployed_program_builds_the_client_from_the_embedded_idl() {
+    let out_dir = tempfile::tempdir().unwrap();
+    let options = GenerateOptions {
+        deployed_program: Some(DeployedProgram {
+            program_id: "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS".to_string(),
+            idl_json: "{\"address\": \"\", \"instructions\": []}\n".to_string(),
+        }),
+        ..Default::default()
+    };
+    generate_with_options(&token_metadata(), &token_idl(), out_dir.path(), &options).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    let typed_program = "new Program<typeof IDL>({ ...IDL, address: programId.toBase58() } as typeof IDL, provider);";
+    // The IDL is embedded once and types the program it builds.
+    assert_eq!(rendered.matches("{\"address\": \"\", \"instructions\": []}").count(), 1, "{}", rendered);
+    assert!(rendered.contains("const IDL = {\"address\": \"\", \"instructions\": []} as const;"));
+    assert!(rendered.contains("new PublicKey(\"Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS\")"));
+    assert!(rendered.contains(&format!("const program = {}", typed_program)), "{}", rendered);
+    assert!(!rendered.contains("anchor.workspace"));
+    assert!(!rendered.contains("target/types"));
+
+    let options = GenerateOptions { split: true, overwrite: OverwritePolicy::Force, ..options };
+    generate_with_options(&token_metadata(), &token_idl(), out_dir.path(), &options).unwrap();
+    let helpers = std::fs::read_to_string(out_dir.path().join("token.helpers.ts")).unwrap();
+    assert_eq!(helpers.matches("{\"address\": \"\", \"instructions\": []}").count(), 1, "{}", helpers);
+    assert!(helpers.contains(&format!("export const program = {}", typed_program)), "{}", helpers);
+}
+
+#[test]
+fn test_generate_split_writes_one_file_per_instruction() {
+    let mut idl = token_idl();
+    let mut withdraw = idl.instructions[0].clone();
+    withdraw.name = "withdraw".to_string();
+    idl.instructions.push(withdraw);
+    let mut meta = token_metadata();
+    let mut withdraw_cases = meta.test_cases[0].clone();
+    withdraw_cases.instruction_name = "withdraw".to_string();
+    meta.test_cases.push(withdraw_cases);
+    meta.setup_requirements.push(SetupRequirement {
+        requirement_type: SetupType::CreateKeypair,
+        description: "Create authority keypair".to_string(),
+        dependencies: vec![],
+        account_name: None,
+    });
+
+    let out_dir = tempfile::tempdir().unwrap();
+    let options = GenerateOptions { split: true, ..Default::default() };
+    let setup_path = generate_with_options(&meta, &idl, out_dir.path(), &options).unwrap();
+    assert_eq!(setup_path, out_dir.path().join("token.setup.ts"));
+
+    let mut files: Vec<String> = std::fs::read_dir(out_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    files.sort();
+    assert_eq!(files, [
+        "token.deposit.test.ts",
+        "token.helpers.ts",
+        "token.setup.ts",
+        "token.withdraw.test.ts",
+    ]);
+
+    let setup = std::fs::read_to_string(&setup_path).unwrap();
+    assert!(setup.contains("import { airdrop, program } from \"./token.helpers\";"));
+    assert!(setup.contains("await airdrop(authorityPubkey);"));
+    let helpers = std::fs::read_to_string(out_dir.path().join("token.helpers.ts")).unwrap();
+    assert!(helpers.contains("export async function airdrop(to: PublicKey, attempts = 5)"));
+    assert!(setup.contains("export const authority = Keypair.generate();"));
+    assert!(setup.contains("export let ata1: PublicKey;"));
+
+    for instruction in ["deposit", "withdraw"] {
+        let test_file = out_dir.path().join(format!("token.{}.test.ts", instruction));
+        let rendered = std::fs::read_to_string(test_file).unwrap();
+        assert!(rendered.contains("import { program } from \"./token.helpers\";"));
+        assert!(rendered.contains("} from \"./token.setup\";"));
+        assert!(rendered.contains("    authorityPubkey,\n"));
+        assert!(rendered.contains("    before(setup);"));
+        assert!(rendered.contains(&format!("it(\"{} - missing signer authority\"", instruction)));
+    }
+}
+
+#[test]
+fn test_generate_to_stdout_writes_no_files() {
+    let out_dir = tempfile::tempdir().unwrap();
+    let tests_dir = out_dir.path().join("tests");
+    let options = GenerateOptions { stdout: true, ..Default::default() };
+    let mut printed = Vec::new();
+    let generated = generate_to(&token_metadata(), &token_idl(), &tests_dir, &options, &mut printed).unwrap();
+
+    assert_eq!(generated.path, tests_dir.join("token.ts"));
+    assert!(!tests_dir.exists());
+
+    let written = generate_with_options(&token_metadata(), &token_idl(), out_dir.path(), &GenerateOptions::default()).unwrap();
+    assert_eq!(String::from_utf8(printed).unwrap(), std::fs::read_to_string(written).unwrap());
+}
+
+#[test]
+fn test_generate_names_a_nameless_idl_from_the_program_name_option() {
+    let mut idl = token_idl();
+    idl.name = String::new();
+    let out_dir = tempfile::tempdir().unwrap();
+
+    let err = generate_with_tera(&token_metadata(), &idl, out_dir.path()).unwrap_err();
+    assert!(err.to_string().contains("pass --name"), "Unexpected error: {}", err);
+
+    let options = GenerateOptions { program_name: Some("escrow".to_string()), ..Default::default() };
+    let path = generate_with_options(&token_metadata(), &idl, out_dir.path(), &options).unwrap();
+    assert_eq!(path, out_dir.path().join("escrow.ts"));
+}
+
+#[test]
+fn test_concatenated_output_names_each_split_file() {
+    let outputs = vec![
+        (PathBuf::from("tests/token.helpers.ts"), "export const program = 1;\n".to_string()),
+        (PathBuf::from("tests/token.deposit.test.ts"), "describe(\"deposit\");\n".to_string()),
+    ];
+    let mut printed = Vec::new();
+    write_concatenated(&outputs, &mut printed).unwrap();
+    assert_eq!(
+        String::from_utf8(printed).unwrap(),
+        "// ===== token.helpers.ts =====\nexport const program = 1;\n\n// ===== token.deposit.test.ts =====\ndescribe(\"deposit\");\n"
+    );
+
+    let mut printed = Vec::new();
+    write_concatenated(&outputs[..1], &mut printed).unwrap();
+    assert_eq!(String::from_utf8(printed).unwrap(), "export const program = 1;\n");
+}
+
+#[test]
+fn test_generate_calls_no_arg_instruction_without_dangling_commas() {
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&token_metadata(), &token_idl(), out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert_eq!(rendered.matches(".deposit()\n").count(), 2, "positive and missing signer calls");
+    assert!(!rendered.contains(".deposit(\n"));
+    assert!(!rendered.contains(",\n                )"));
+}
+
+#[test]
+fn test_generate_wires_address_constrained_accounts() {
+    // Token-2022 rather than the well-known token program.
+    let token_program = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+    let mut idl = token_idl();
+    let mut token_program_account = account("token_program", false, None);
+    token_program_account.is_mut = false;
+    token_program_account.address = Some(token_program.to_string());
+    idl.instructions[0].accounts.push(token_program_account);
+
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&token_metadata(), &idl, out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(rendered.contains(&format!("tokenProgram: new PublicKey(\"{}\")", token_program)));
+}
+
+#[test]
+fn test_generate_resolves_well_known_accounts_in_any_casing() {
+    for system_program in ["system_program", "systemProgram"] {
+        let mut idl = token_idl();
+        let mut accounts = vec![account(system_program, false, None), account("token_program", false, None)];
+        for acc in &mut accounts {
+            acc.is_mut = false;
+        }
+        accounts[0].address = Some("11111111111111111111111111111111".to_string());
+        idl.instructions[0].accounts.extend(accounts);
+
+        let out_dir = tempfile::tempdir().unwrap();
+        generate_with_tera(&token_metadata(), &idl, out_dir.path()).unwrap();
+
+        let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+        assert!(rendered.contains("systemProgram: SystemProgram.programId"), "{}", rendered);
+        assert!(rendered.contains("tokenProgram: TOKEN_PROGRAM_ID"));
+        assert!(rendered.contains("import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from \"@solana/spl-token\";"));
+    }
+}
+
+#[test]
+fn test_generate_init_only_emits_setup_without_tests() {
+    let mut idl = token_idl();
+    idl.instructions[0].accounts.push(account(
+        "vault",
+        false,
+        Some(IdlPda { seeds: vec![seed("const", "", "vault")], program: String::new() }),
+    ));
+    let mut meta = token_metadata();
+    meta.setup_requirements = vec![SetupRequirement {
+        requirement_type: SetupType::InitializePda,
+        description: "Initialize vault".to_string(),
+        dependencies: vec![],
+        account_name: Some("vault".to_string()),
+    }];
+    meta.pda_init_sequence = vec![PdaInit {
+        account_name: "vault".to_string(),
+        seeds: vec![SeedComponent { seed_type: SeedType::Static, value: "vault".to_string() }],
+        program_id: String::new(),
+        space: None,
+    }];
+
+    let out_dir = tempfile::tempdir().unwrap();
+    let options = GenerateOptions { init_only: true, ..Default::default() };
+    generate_with_options(&meta, &idl, out_dir.path(), &options).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(rendered.contains("before(async () => {"));
+    assert!(rendered.contains("PublicKey.findProgramAddressSync("));
+    assert!(rendered.contains("Buffer.from(\"vault\")"));
+    assert!(!rendered.contains("it("));
+}
+
+fn vault_init_fixture(vault_seeds: Vec<SeedComponent>) -> (IdlData, TestMetadata) {
+    let mut idl = token_idl();
+    idl.instructions[0].name = "initialize".to_string();
+    idl.instructions[0].accounts = vec![
+        account("authority", true, None),
+        account("vault", false, Some(IdlPda { seeds: vec![], program: String::new() })),
+    ];
+    idl.instructions[0].accounts[1].is_mut = true;
+
+    let mut meta = token_metadata();
+    meta.instruction_order = vec!["initialize".to_string()];
+    meta.setup_requirements = vec![
+        SetupRequirement {
+            requirement_type: SetupType::CreateKeypair,
+            description: "Create authority keypair".to_string(),
+            dependencies: vec![],
+            account_name: None,
+        },
+        SetupRequirement {
+            requirement_type: SetupType::InitializePda,
+            description: "Initialize vault".to_string(),
+            dependencies: vec![],
+            account_name: Some("vault".to_string()),
+        },
+    ];
+    meta.pda_init_sequence = vec![PdaInit {
+        account_name: "vault".to_string(),
+        seeds: vault_seeds,
+        program_id: String::new(),
+        space: None,
+    }];
+    let case = &mut meta.test_cases[0];
+    case.instruction_name = "initialize".to_string();
+    let mut second = case.positive_cases[0].clone();
+    second.description = "initialize succeeds again".to_string();
+    case.positive_cases.push(second);
+    (idl, meta)
+}
+
+/// The body of the `it` block whose title is `description`.
+fn test_body<'a>(rendered: &'a str, description: &str) -> &'a str {
+    let start = rendered.find(&format!("\"{}\"", description)).expect("missing test");
+    let end = rendered[start..].find("\n    });").map_or(rendered.len(), |end| start + end);
+    &rendered[start..end]
+}
+
+#[test]
+fn test_generate_extra_init_cases_derive_their_own_pda() {
+    let (idl, meta) = vault_init_fixture(vec![
+        SeedComponent { seed_type: SeedType::Static, value: "vault".to_string() },
+        SeedComponent { seed_type: SeedType::AccountKey, value: "authority".to_string() },
+    ]);
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &idl, out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    let first = test_body(&rendered, "deposit succeeds");
+    assert!(!first.contains("Keypair.generate()"));
+    assert!(first.contains("vault: pda2"));
+
+    let second = test_body(&rendered, "initialize succeeds again");
+    assert!(second.contains("const authority = Keypair.generate();"));
+    assert!(second.contains("const [pda2] = PublicKey.findProgramAddressSync("));
+    assert!(second.contains("[Buffer.from(\"vault\"), authorityPubkey.toBuffer()]"));
+}
+
+#[test]
+fn test_generate_isolates_pdas_seeded_by_a_keypair_named_apart_from_its_account() {
+    let (mut idl, mut meta) = vault_init_fixture(vec![
+        SeedComponent { seed_type: SeedType::Static, value: "vault".to_string() },
+        SeedComponent { seed_type: SeedType::AccountKey, value: "user".to_string() },
+    ]);
+    // The keypair created for `user` is the first one, so it is named `authority`.
+    idl.instructions[0].accounts[0] = account("user", true, None);
+    meta.setup_requirements[0].description = "Create keypair for user".to_string();
+    meta.setup_requirements[0].account_name = Some("user".to_string());
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &idl, out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(!rendered.contains("userPubkey"), "{}", rendered);
+    let second = test_body(&rendered, "initialize succeeds again");
+    assert!(second.starts_with("\"initialize succeeds again\", async"), "{}", second);
+    assert!(!rendered.contains("it.skip("));
+    assert!(second.contains("const authority = Keypair.generate();"));
+    assert!(second.contains("[Buffer.from(\"vault\"), authorityPubkey.toBuffer()]"), "{}", second);
+}
+
+#[test]
+fn test_generate_skips_extra_init_cases_for_fixed_seed_pdas() {
+    let (idl, meta) = vault_init_fixture(vec![
+        SeedComponent { seed_type: SeedType::Static, value: "vault".to_string() },
+    ]);
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &idl, out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(rendered.contains("it(\"deposit succeeds\""));
+    assert!(rendered.contains("it.skip(\"initialize succeeds again\""));
+    assert!(rendered.contains("// vault only has fixed seeds"));
+}
+
+#[test]
+fn test_generate_derives_argument_seeded_pda_inside_each_test() {
+    let (mut idl, mut meta) = vault_init_fixture(vec![
+        SeedComponent { seed_type: SeedType::Static, value: "vault".to_string() },
+        SeedComponent { seed_type: SeedType::Argument, value: "name".to_string() },
+    ]);
+    idl.instructions[0].args = vec![IdlField {
+        name: "name".to_string(),
+        field_type: "string".to_string(),
+    }];
+    let case = &mut meta.test_cases[0];
+    case.positive_cases[0].argument_values = vec![valid("name", "\"alpha\"")];
+    case.positive_cases[1].argument_values = vec![valid("name", "\"beta\"")];
+
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &idl, out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    let before = &rendered[rendered.find("before(async").unwrap()..rendered.find("it(").unwrap()];
+    assert!(!before.contains("findProgramAddressSync"), "Derived in before(): {}", before);
+
+    let seeds = "[Buffer.from(\"vault\"), Buffer.from(String(nameValue))]";
+    for description in ["deposit succeeds", "initialize succeeds again"] {
+        let body = test_body(&rendered, description);
+        let value = body.find("const nameValue").expect("missing argument");
+        let derivation = body.find("const [pda2] = PublicKey.findProgramAddressSync(").expect("missing derivation");
+        assert!(value < derivation, "PDA derived before its argument: {}", body);
+        assert!(body.contains(seeds), "Unexpected seeds: {}", body);
+    }
+    assert!(!rendered.contains("it.skip("));
+}
+
+#[test]
+fn test_generate_derives_sysvar_seeded_pda_from_the_web3_constant() {
+    let (idl, meta) = vault_init_fixture(vec![
+        SeedComponent { seed_type: SeedType::Static, value: "vault".to_string() },
+        SeedComponent { seed_type: SeedType::AccountKey, value: "clock".to_string() },
+    ]);
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &idl, out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(rendered.contains("[Buffer.from(\"vault\"), SYSVAR_CLOCK_PUBKEY.toBuffer()]"), "{}", rendered);
+    assert!(!rendered.contains("clockPubkey"));
+    assert!(
+        rendered.contains(
+            "import { Keypair, SystemProgram, PublicKey, LAMPORTS_PER_SOL, SYSVAR_CLOCK_PUBKEY } from \"@solana/web3.js\";"
+        )
+    );
+}
+
+#[test]
+fn test_generate_declares_program_constants_and_seeds_pdas_with_them() {
+    let (mut idl, meta) = vault_init_fixture(vec![
+        SeedComponent { seed_type: SeedType::Static, value: "vault".to_string() },
+        SeedComponent { seed_type: SeedType::AccountKey, value: "authority".to_string() },
+    ]);
+    idl.constants = vec![
+        IdlConstant {
+            name: "SEED".to_string(),
+            constant_type: "bytes".to_string(),
+            value: "[118, 97, 117, 108, 116]".to_string(),
+        },
+        IdlConstant {
+            name: "MAX_DEPOSIT".to_string(),
+            constant_type: "u64".to_string(),
+            value: "1_000_000".to_string(),
+        },
+    ];
+    let out_dir = tempfile::tempdir().unwrap();
+    let options = GenerateOptions { constants: true, ..Default::default() };
+    generate_with_options(&meta, &idl, out_dir.path(), &options).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(rendered.contains("const SEED = Buffer.from([118, 97, 117, 108, 116]);"), "{}", rendered);
+    assert!(rendered.contains("const MAX_DEPOSIT = new anchor.BN(\"1000000\");"));
+    assert!(rendered.find("const SEED").unwrap() < rendered.find("describe(").unwrap());
+    assert!(rendered.contains("[SEED, authorityPubkey.toBuffer()]"));
+    assert!(!rendered.contains("Buffer.from(\"vault\")"));
+
+    // Without the option the literal seed is kept.
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &idl, out_dir.path()).unwrap();
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(!rendered.contains("SEED"));
+    assert!(rendered.contains("[Buffer.from(\"vault\"), authorityPubkey.toBuffer()]"));
+}
+
+#[test]
+fn test_generate_emits_typescript_declarations_for_defined_types() {
+    let mut idl = token_idl();
+    idl.types = vec![
+        IdlTypeDef {
+            name: "Item".to_string(),
+            kind: "struct".to_string(),
+            fields: vec!["unit_price".to_string(), "name".to_string(), "owner".to_string(), "side".to_string()],
+            field_types: vec![
+                "u64".to_string(),
+                "string".to_string(),
+                "Option<pubkey>".to_string(),
+                "Side".to_string(),
+            ],
+        },
+        IdlTypeDef {
+            name: "Side".to_string(),
+            kind: "enum".to_string(),
+            fields: vec!["Bid".to_string(), "Ask".to_string()],
+            field_types: vec![],
+        },
+    ];
+    idl.instructions[0].args = vec![
+        IdlField { name: "side".to_string(), field_type: "Side".to_string() },
+        IdlField { name: "amount".to_string(), field_type: "u64".to_string() },
+    ];
+    let mut meta = token_metadata();
+    meta.test_cases[0].positive_cases[0].argument_values = vec![
+        valid("side", "{ bid: {} }"),
+        valid("amount", "new anchor.BN(1000)"),
+    ];
+    let out_dir = tempfile::tempdir().unwrap();
+    let options = GenerateOptions { emit_types: true, ..Default::default() };
+    generate_with_options(&meta, &idl, out_dir.path(), &options).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(
+        rendered.contains(
+            "interface Item {\n    unitPrice: anchor.BN;\n    name: string;\n    owner: PublicKey | null;\n    side: Side;\n}"
+        ),
+        "{}",
+        rendered
+    );
+    assert!(rendered.contains("type Side = { bid: {} } | { ask: {} };"));
+    assert!(rendered.find("interface Item").unwrap() < rendered.find("describe(").unwrap());
+    // Values of a declared type are annotated with it.
+    assert!(rendered.contains("const sideValue: Side = { bid: {} };"), "{}", rendered);
+    assert!(rendered.contains("const amountValue = new anchor.BN(1000);"));
+
+    // In split mode the instruction files import the declarations.
+    let out_dir = tempfile::tempdir().unwrap();
+    let options = GenerateOptions { emit_types: true, split: true, ..Default::default() };
+    generate_with_options(&meta, &idl, out_dir.path(), &options).unwrap();
+    let deposit = std::fs::read_to_string(out_dir.path().join("token.deposit.test.ts")).unwrap();
+    assert!(deposit.contains(", Item, Side } from \"./token.helpers\";"), "{}", deposit);
+    assert!(deposit.contains("const sideValue: Side = { bid: {} };"));
+
+    // Without the option no declarations are emitted.
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &idl, out_dir.path()).unwrap();
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(!rendered.contains("interface Item"));
+    assert!(rendered.contains("const sideValue = { bid: {} };"));
+}
+
+#[test]
+fn test_generate_asserts_setup_pdas_against_the_declared_seeds() {
+    let (idl, meta) = vault_init_fixture(vec![
+        SeedComponent { seed_type: SeedType::Static, value: "vault".to_string() },
+        SeedComponent { seed_type: SeedType::AccountKey, value: "authority".to_string() },
+    ]);
+    let out_dir = tempfile::tempdir().unwrap();
+    let options = GenerateOptions { assert_pdas: true, ..Default::default() };
+    generate_with_options(&meta, &idl, out_dir.path(), &options).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    let checks = rendered.find("describe(\"PDA seeds\"").expect("missing PDA checks");
+    assert!(rendered.find("before(async").unwrap() < checks);
+    assert!(rendered.contains("const [expected] = PublicKey.findProgramAddressSync(seeds, programId);"));
+    assert!(rendered.contains("address.equals(expected)"));
+    assert!(
+        rendered.contains("assertDeclaredPda(\"initialize\", \"vault\", pda2, { authority: authorityPubkey });"),
+        "{}",
+        rendered
+    );
+
+    // Split mode puts the checks in their own file.
+    let out_dir = tempfile::tempdir().unwrap();
+    let options = GenerateOptions { assert_pdas: true, split: true, ..Default::default() };
+    generate_with_options(&meta, &idl, out_dir.path(), &options).unwrap();
+    let pdas = std::fs::read_to_string(out_dir.path().join("token.pdas.test.ts")).unwrap();
+    assert!(pdas.contains("before(setup);"));
+    assert!(pdas.contains("assertDeclaredPda(\"initialize\", \"vault\", pda2, { authority: authorityPubkey });"));
+
+    // Without the option there are no checks.
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &idl, out_dir.path()).unwrap();
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(!rendered.contains("PDA seeds"));
+}
+
+#[test]
+fn test_generate_refuses_to_overwrite_existing_tests_by_default() {
+    let out_dir = tempfile::tempdir().unwrap();
+    let path = out_dir.path().join("token.ts");
+    std::fs::write(&path, "// hand-edited").unwrap();
+
+    let err = generate_with_tera(&token_metadata(), &token_idl(), out_dir.path()).unwrap_err();
+    assert!(err.to_string().contains("--force"), "Unexpected error: {}", err);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "// hand-edited");
+}
+
+#[test]
+fn test_generate_force_overwrites_existing_tests() {
+    let out_dir = tempfile::tempdir().unwrap();
+    let path = out_dir.path().join("token.ts");
+    std::fs::write(&path, "// hand-edited").unwrap();
+
+    let options = GenerateOptions { overwrite: OverwritePolicy::Force, ..Default::default() };
+    generate_with_options(&token_metadata(), &token_idl(), out_dir.path(), &options).unwrap();
+    assert!(std::fs::read_to_string(&path).unwrap().contains("describe(\"token_vault\""));
+    assert!(!backup_path(&path).exists());
+}
+
+#[test]
+fn test_generate_backup_preserves_existing_tests() {
+    let out_dir = tempfile::tempdir().unwrap();
+    let path = out_dir.path().join("token.ts");
+    std::fs::write(&path, "// hand-edited").unwrap();
+
+    let options = GenerateOptions { overwrite: OverwritePolicy::Backup, ..Default::default() };
+    generate_with_options(&token_metadata(), &token_idl(), out_dir.path(), &options).unwrap();
+    assert_eq!(std::fs::read_to_string(out_dir.path().join("token.ts.bak")).unwrap(), "// hand-edited");
+    assert!(std::fs::read_to_string(&path).unwrap().contains("describe(\"token_vault\""));
+}
+
+#[test]
+fn test_generate_declares_keypairs_for_pubkey_arguments() {
+    let mut meta = token_metadata();
+    meta.test_cases[0].positive_cases[0].argument_values = vec![
+        valid("from", "fromKeypair.publicKey"),
+        valid("to", "toKeypair.publicKey"),
+    ];
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &token_idl(), out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(rendered.contains("const fromKeypair = Keypair.generate();"));
+    assert!(rendered.contains("const toKeypair = Keypair.generate();"));
+    let body = test_body(&rendered, "deposit succeeds");
+    assert!(body.contains("const fromValue = fromKeypair.publicKey;"), "Unexpected body: {}", body);
+    assert!(body.contains("const toValue = toKeypair.publicKey;"), "Unexpected body: {}", body);
+}
+
+#[test]
+fn test_generate_reads_getters_with_view() {
+    let mut idl = token_idl();
+    for acc in &mut idl.instructions[0].accounts {
+        acc.is_mut = false;
+    }
+    idl.instructions[0].returns = Some("u64".to_string());
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&token_metadata(), &idl, out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    let body = test_body(&rendered, "deposit succeeds");
+    assert!(body.contains("const result = await program.methods"), "Unexpected body: {}", body);
+    assert!(body.contains(".view();"));
+    assert!(body.contains("assert.ok(anchor.BN.isBN(result)"));
+    assert!(!body.contains(".rpc();"));
+}
+
+#[test]
+fn test_generate_view_flag_forces_view_calls() {
+    let mut idl = token_idl();
+    idl.instructions[0].returns = Some("bool".to_string());
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&token_metadata(), &idl, out_dir.path()).unwrap();
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(test_body(&rendered, "deposit succeeds").contains(".rpc();"));
+
+    let options = GenerateOptions {
+        view_instructions: vec!["deposit".to_string()],
+        overwrite: OverwritePolicy::Force,
+        ..Default::default()
+    };
+    generate_with_options(&token_metadata(), &idl, out_dir.path(), &options).unwrap();
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    let body = test_body(&rendered, "deposit succeeds");
+    assert!(body.contains(".view();"));
+    assert!(body.contains("assert.isBoolean(result);"));
+}
+
+/// `deposit` signed by both `authority` and `payer`, with a keypair created for
+/// each signer in `keypair_order`.
+fn payer_fixture(keypair_order: [&str; 2]) -> (IdlData, TestMetadata) {
+    let mut idl = token_idl();
+    idl.instructions[0].accounts.push(account("payer", true, None));
+    let mut meta = token_metadata();
+    meta.setup_requirements = keypair_order
+        .iter()
+        .map(|name| SetupRequirement {
+            requirement_type: SetupType::CreateKeypair,
+            description: format!("Create keypair for {}", name),
+            dependencies: vec![],
+            account_name: Some(name.to_string()),
+        })
+        .collect();
+    (idl, meta)
+}
+
+#[test]
+fn test_generate_funds_accounts_with_fund_requirements() {
+    let (idl, mut meta) = payer_fixture(["authority", "payer"]);
+    meta.setup_requirements.push(SetupRequirement {
+        requirement_type: SetupType::FundAccount,
+        description: "Fund payer with SOL for transactions".to_string(),
+        dependencies: vec!["payer".to_string()],
+        account_name: Some("payer".to_string()),
+    });
+
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &idl, out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    // The payer is funded once; the unfunded authority keeps its creation airdrop.
+    assert_eq!(rendered.matches("await airdrop(payerPubkey);").count(), 1);
+    assert!(rendered.contains("await airdrop(authorityPubkey);"));
+}
+
+#[test]
+fn test_generate_wires_a_separate_payer_apart_from_the_authority() {
+    // The payer's keypair comes first but must not take over the authority.
+    let (idl, meta) = payer_fixture(["payer", "authority"]);
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &idl, out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(rendered.contains("const payer = Keypair.generate();"));
+    assert!(rendered.contains("const authority = Keypair.generate();"));
+    assert!(rendered.contains("await airdrop(payerPubkey);"));
+
+    let body = test_body(&rendered, "deposit succeeds");
+    assert!(body.contains("authority: authorityPubkey,"), "Unexpected body: {}", body);
+    assert!(body.contains("payer: payerPubkey"));
+    assert!(body.contains(".signers([\n                    authority,\n                    payer\n                ])"));
+
+    // Each missing-signer case drops only the signer it is about.
+    let missing_payer = test_body(&rendered, "deposit - missing signer payer");
+    assert!(missing_payer.contains(".signers([\n                    authority\n                ])"));
+    let missing_authority = test_body(&rendered, "deposit - missing signer authority");
+    assert!(missing_authority.contains(".signers([\n                    payer\n                ])"));
+}
+
+#[test]
+fn test_generate_signs_a_payer_only_instruction_without_the_authority() {
+    // `initialize(counter, payer, systemProgram)`: the authority is not one of its accounts.
+    let (mut idl, meta) = payer_fixture(["authority", "payer"]);
+    idl.instructions[0].accounts = vec![
+        account("counter", false, None),
+        account("payer", true, None),
+        account("system_program", false, None),
+    ];
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &idl, out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    let body = test_body(&rendered, "deposit succeeds");
+    assert!(body.contains("payer: payerPubkey,"), "Unexpected body: {}", body);
+    assert!(body.contains(".signers([\n                    payer\n                ])"), "Unexpected body: {}", body);
+    let missing_payer = test_body(&rendered, "deposit - missing signer payer");
+    assert!(missing_payer.contains(".signers([\n                ])"), "Unexpected body: {}", missing_payer);
+}
+
+#[test]
+fn test_generate_gives_a_lone_payer_its_own_funded_keypair() {
+    let (mut idl, mut meta) = payer_fixture(["authority", "payer"]);
+    meta.setup_requirements.remove(0);
+    idl.instructions[0].accounts = vec![
+        account("counter", false, None),
+        account("payer", true, None),
+        account("system_program", false, None),
+    ];
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &idl, out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(rendered.contains("const payer = Keypair.generate();"));
+    assert!(!rendered.contains("const authority = Keypair.generate();"));
+    assert!(rendered.contains("await airdrop(payerPubkey);"));
+    let body = test_body(&rendered, "deposit succeeds");
+    assert!(body.contains("payer: payerPubkey,"), "Unexpected body: {}", body);
+    assert!(body.contains(".signers([\n                    payer\n                ])"), "Unexpected body: {}", body);
+}
+
+#[test]
+fn test_generate_retries_airdrops() {
+    let mut meta = token_metadata();
+    meta.setup_requirements.push(SetupRequirement {
+        requirement_type: SetupType::CreateKeypair,
+        description: "Create authority keypair".to_string(),
+        dependencies: vec![],
+        account_name: None,
+    });
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &token_idl(), out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    let helper = rendered.find("async function airdrop(to: PublicKey, attempts = 5)").expect("missing airdrop helper");
+    let before = rendered.find("before(async").unwrap();
+    assert!(helper < before);
+    let retry = &rendered[helper..before];
+    assert!(retry.contains("if (attempt >= attempts) {"));
+    assert!(retry.contains("setTimeout(resolve, 500 * 2 ** (attempt - 1))"));
+    assert!(rendered[before..].contains("await airdrop(authorityPubkey);"));
+    // Only the helper requests airdrops.
+    assert_eq!(rendered.matches("requestAirdrop(").count(), 1);
+}
+
+#[test]
+fn test_generate_keeps_array_literals_unquoted() {
+    let mut meta = token_metadata();
+    let literal = "[{ price: new anchor.BN(\"1000\"), name: \"test_value\" }]";
+    let case = &mut meta.test_cases[0];
+    case.arguments = vec![argument("amount", ArgumentType::Struct { name: "Item".to_string() }, vec![])];
+    case.positive_cases[0].argument_values = vec![valid("amount", literal)];
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &token_idl(), out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(rendered.contains(&format!("const amountValue = {};", literal)), "{}", rendered);
+}
+
+#[test]
+fn test_generate_names_the_idl_version_in_the_header() {
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&token_metadata(), &token_idl(), out_dir.path()).unwrap();
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(rendered.contains("// Generated from version 0.1.0 of the token_vault IDL\n"), "{}", rendered);
+    assert!(!rendered.contains("fetchIdl"));
+
+    let options = GenerateOptions { split: true, check_idl_version: true, ..Default::default() };
+    generate_with_options(&token_metadata(), &token_idl(), out_dir.path(), &options).unwrap();
+    for file in ["token.helpers.ts", "token.setup.ts", "token.deposit.test.ts"] {
+        let rendered = std::fs::read_to_string(out_dir.path().join(file)).unwrap();
+        assert!(rendered.contains("// Generated from version 0.1.0 of the token_vault IDL"), "{}: {}", file, rendered);
+    }
+    let setup = std::fs::read_to_string(out_dir.path().join("token.setup.ts")).unwrap();
+    let check = setup.split("async function initialize()").nth(1).unwrap();
+    assert!(check.contains("await anchor.Program.fetchIdl(program.programId, provider);"), "{}", setup);
+    assert!(check.contains("deployedIdl.metadata.version,\n            \"0.1.0\","), "{}", setup);
+    assert!(setup.contains("import { airdrop, program, provider } from"), "{}", setup);
+}
+
+#[test]
+fn test_group_by_instruction_nests_a_describe_per_instruction() {
+    let mut idl = token_idl();
+    let mut withdraw = idl.instructions[0].clone();
+    withdraw.name = "withdraw".to_string();
+    idl.instructions.push(withdraw);
+    let mut meta = token_metadata();
+    let mut withdraw_tests = meta.test_cases[0].clone();
+    withdraw_tests.instruction_name = "withdraw".to_string();
+    withdraw_tests.positive_cases[0].description = "withdraw succeeds".to_string();
+    meta.test_cases.push(withdraw_tests);
+    meta.instruction_order.push("withdraw".to_string());
+    let out_dir = tempfile::tempdir().unwrap();
+
+    generate_with_tera(&meta, &idl, out_dir.path()).unwrap();
+    let flat = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert_eq!(flat.matches("describe(").count(), 1);
+
+    let options = GenerateOptions { group_by: GroupBy::Instruction, overwrite: OverwritePolicy::Force, ..Default::default() };
+    generate_with_options(&meta, &idl, out_dir.path(), &options).unwrap();
+    let grouped = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    for instruction in ["deposit", "withdraw"] {
+        let block = grouped.split(&format!("\n    describe(\"{}\", () => {{\n", instruction)).nth(1).unwrap();
+        let block = block.split("\n    });\n").next().unwrap();
+        assert!(block.contains(&format!("\n        it(\"{} succeeds\", async () => {{", instruction)), "{}", grouped);
+        assert!(block.contains(&format!("\n        it(\"{} - missing signer authority\"", instruction)), "{}", grouped);
+    }
+}
+
+#[test]
+fn test_assert_events_listens_for_documented_events() {
+    let field = |name: &str, field_type: &str| IdlField { name: name.to_string(), field_type: field_type.to_string() };
+    let mut idl = token_idl();
+    idl.instructions[0].args = vec![field("amount", "u64")];
+    idl.instructions[0].docs = vec!["Deposits tokens. Emits a `Deposited` event.".to_string()];
+    idl.events = vec![
+        IdlEvent { name: "Deposited".to_string(), discriminator: vec![], fields: vec![field("amount", "u64"), field("depositor", "pubkey")] },
+        IdlEvent { name: "Withdrawn".to_string(), discriminator: vec![], fields: vec![field("amount", "u64")] },
+    ];
+    let mut meta = token_metadata();
+    meta.test_cases[0].arguments = vec![argument("amount", ArgumentType::U64, vec![])];
+    meta.test_cases[0].positive_cases[0].argument_values = vec![valid("amount", "new anchor.BN(1000)")];
+    let out_dir = tempfile::tempdir().unwrap();
+
+    generate_with_tera(&meta, &idl, out_dir.path()).unwrap();
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(!rendered.contains("addEventListener"));
+
+    let options = GenerateOptions { assert_events: true, overwrite: OverwritePolicy::Force, ..Default::default() };
+    generate_with_options(&meta, &idl, out_dir.path(), &options).unwrap();
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    let body = test_body(&rendered, "deposit succeeds");
+    assert!(body.contains(
+        "const depositedListener = program.addEventListener(\"deposited\", (event) => depositedEvents.push(event));"
+    ), "{}", body);
+    assert!(body.contains("assert.isNotEmpty(depositedEvents, \"deposited should have been emitted\");"));
+    assert!(body.contains("assert.strictEqual(String(depositedEvents[0].amount), String(amountValue));"));
+    // Fields without a matching argument are only checked for presence.
+    assert!(body.contains("assert.property(depositedEvents[0], \"depositor\");"));
+    assert!(!body.contains("depositedEvents[0].depositor)"));
+    assert!(body.contains("} finally {\n            await program.removeEventListener(depositedListener);"));
+    // The docs do not mention it.
+    assert!(!rendered.contains("withdrawn"));
+}
+
+#[test]
+fn test_deterministic_keys_derive_keypairs_from_fixed_seeds() {
+    let mut meta = token_metadata();
+    meta.setup_requirements = vec![SetupRequirement {
+        requirement_type: SetupType::CreateKeypair,
+        description: "Create keypair for authority".to_string(),
+        dependencies: vec![],
+        account_name: Some("authority".to_string()),
+    }];
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &token_idl(), out_dir.path()).unwrap();
+    let random = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(random.contains("const authority = Keypair.generate();"));
+
+    let options = GenerateOptions { deterministic_keys: true, overwrite: OverwritePolicy::Force, ..Default::default() };
+    generate_with_options(&meta, &token_idl(), out_dir.path(), &options).unwrap();
+    let first = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(!first.contains("Keypair.generate()"));
+    assert!(first.contains("never use them outside local tests"));
+    let declaration = first.lines().find(|line| line.contains("const authority = ")).unwrap();
+    let seed = declaration
+        .split_once("Keypair.fromSeed(Uint8Array.from([")
+        .and_then(|(_, rest)| rest.strip_suffix("]));"))
+        .unwrap_or_else(|| panic!("{}", declaration));
+    assert_eq!(seed.split(", ").filter(|byte| byte.parse::<u8>().is_ok()).count(), 32);
+
+    generate_with_options(&meta, &token_idl(), out_dir.path(), &options).unwrap();
+    let second = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_regeneration_keeps_custom_regions() {
+    let mut meta = token_metadata();
+    let case = &mut meta.test_cases[0];
+    case.arguments = vec![argument("amount", ArgumentType::U64, vec![])];
+    case.positive_cases[0].argument_values = vec![valid("amount", "1000")];
+    let out_dir = tempfile::tempdir().unwrap();
+    let path = out_dir.path().join("token.ts");
+    generate_with_tera(&meta, &token_idl(), out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(&path).unwrap();
+    let region = "        // SOLIFY:BEGIN-CUSTOM deposit/positive-1\n        const amountValue = new anchor.BN(\"1000\");\n        // SOLIFY:END-CUSTOM\n";
+    assert!(rendered.contains(region), "{}", rendered);
+    let edited = rendered.replace("new anchor.BN(\"1000\");", "new anchor.BN(\"42\"); // my edit");
+    std::fs::write(&path, edited).unwrap();
+
+    // The regenerated scaffolding picks up the new signer, the custom region keeps the edit.
+    let mut idl = token_idl();
+    idl.instructions[0].accounts.push(account("payer", true, None));
+    let options = GenerateOptions { overwrite: OverwritePolicy::Force, ..Default::default() };
+    generate_with_options(&meta, &idl, out_dir.path(), &options).unwrap();
+
+    let regenerated = std::fs::read_to_string(&path).unwrap();
+    assert!(regenerated.contains("const amountValue = new anchor.BN(\"42\"); // my edit"), "{}", regenerated);
+    assert!(regenerated.contains("payer: "), "{}", regenerated);
+}
+
+#[test]
+fn test_cases_with_the_same_description_keep_their_own_custom_regions() {
+    let mut meta = token_metadata();
+    let case = &mut meta.test_cases[0];
+    case.arguments = vec![argument("amount", ArgumentType::U64, vec![])];
+    case.positive_cases[0].argument_values = vec![valid("amount", "1000")];
+    let mut second = case.positive_cases[0].clone();
+    second.argument_values = vec![valid("amount", "2000")];
+    case.positive_cases.push(second);
+    let out_dir = tempfile::tempdir().unwrap();
+    let path = out_dir.path().join("token.ts");
+    generate_with_tera(&meta, &token_idl(), out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(&path).unwrap();
+    assert!(rendered.contains("// SOLIFY:BEGIN-CUSTOM deposit/positive-1\n"), "{}", rendered);
+    assert!(rendered.contains("// SOLIFY:BEGIN-CUSTOM deposit/positive-2\n"), "{}", rendered);
+    let edited = rendered
+        .replace("new anchor.BN(\"1000\");", "new anchor.BN(\"1\"); // first edit")
+        .replace("new anchor.BN(\"2000\");", "new anchor.BN(\"2\"); // second edit");
+    std::fs::write(&path, edited).unwrap();
+
+    let options = GenerateOptions { overwrite: OverwritePolicy::Force, ..Default::default() };
+    generate_with_options(&meta, &token_idl(), out_dir.path(), &options).unwrap();
+
+    let regenerated = std::fs::read_to_string(&path).unwrap();
+    let first = regenerated.find("// first edit").expect("first edit lost");
+    let second = regenerated.find("// second edit").expect("second edit lost");
+    assert!(first < second, "{}", regenerated);
+}
+
+#[test]
+fn test_regeneration_rejects_a_repeated_custom_region_id() {
+    let meta = token_metadata();
+    let out_dir = tempfile::tempdir().unwrap();
+    let region = "// SOLIFY:BEGIN-CUSTOM deposit/positive-1\nconst amountValue = 1;\n// SOLIFY:END-CUSTOM\n";
+    std::fs::write(out_dir.path().join("token.ts"), format!("{}{}", region, region)).unwrap();
+
+    let options = GenerateOptions { overwrite: OverwritePolicy::Force, ..Default::default() };
+    let err = generate_with_options(&meta, &token_idl(), out_dir.path(), &options).unwrap_err();
+    assert!(format!("{:#}", err).contains("\"deposit/positive-1\" appears more than once"), "{:#}", err);
+}
+
+#[test]
+fn test_generate_reports_what_it_skipped() {
+    let mut meta = token_metadata();
+    let side = ArgumentType::Enum { name: "Side".to_string(), variants: vec!["Bid".to_string(), "Ask".to_string()] };
+    let case = &mut meta.test_cases[0];
+    case.arguments = vec![argument("side", side, vec![])];
+    // The analyzer's value for types it cannot build.
+    case.positive_cases[0].argument_values = vec![valid("side", "/* valid value */")];
+    let out_dir = tempfile::tempdir().unwrap();
+    let generated = generate(&meta, &token_idl(), out_dir.path(), &GenerateOptions::default()).unwrap();
+
+    assert_eq!(generated.path, out_dir.path().join("token.ts"));
+    assert_eq!(generated.skipped, [
+        "deposit: argument `side` has an unsupported type; a placeholder is passed",
+        "deposit: account `mint` must already exist and cannot be created in setup; see its TODO",
+    ]);
+
+    meta.test_cases[0].positive_cases[0].argument_values = vec![valid("side", "{ bid: {} }")];
+    let mut idl = token_idl();
+    idl.instructions[0].accounts.remove(1);
+    let generated = generate(&meta, &idl, out_dir.path(), &GenerateOptions {
+        overwrite: OverwritePolicy::Force,
+        ..Default::default()
+    }).unwrap();
+    assert!(generated.skipped.is_empty(), "{:?}", generated.skipped);
+}
+
+#[test]
+fn test_generate_declares_a_todo_for_accounts_setup_cannot_create() {
+    let out_dir = tempfile::tempdir().unwrap();
+    let generated = generate(&token_metadata(), &token_idl(), out_dir.path(), &GenerateOptions::default()).unwrap();
+
+    let rendered = std::fs::read_to_string(&generated.path).unwrap();
+    let declaration = rendered.find("const mint = PublicKey.default;").expect(&rendered);
+    assert!(rendered[..declaration].contains("// TODO: `mint` must already exist on the cluster."));
+    assert!(declaration < rendered.find("mint: mint").unwrap());
+    assert_eq!(generated.skipped, [
+        "deposit: account `mint` must already exist and cannot be created in setup; see its TODO",
+    ]);
+}
+
+#[test]
+fn test_generate_checks_the_error_number_of_declared_errors() {
+    let mut meta = token_metadata();
+    let negative = |code: &str, message: &str| TestCase {
+        test_type: TestCaseType::NegativeConstraint,
+        description: format!("deposit - {}", code),
+        argument_values: vec![],
+        expected_outcome: ExpectedOutcome::Failure {
+            error_code: Some(code.to_string()),
+            error_message: message.to_string(),
+        },
+    };
+    meta.test_cases[0].negative_cases = vec![
+        negative("InvalidAmount", "Amount must be positive"),
+        negative("Overflow", "Arithmetic overflow"),
+    ];
+    let mut idl = token_idl();
+    idl.errors = vec![IdlError {
+        code: 6001,
+        name: "InvalidAmount".to_string(),
+        msg: "Amount must be positive".to_string(),
+    }];
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &idl, out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    let declared = test_body(&rendered, "deposit - InvalidAmount");
+    assert!(
+        declared.contains("assert.strictEqual((err as anchor.AnchorError).error?.errorCode?.number, 6001, String(err));"),
+        "{}",
+        declared
+    );
+    assert!(!declared.contains("assert.include(String(err)"));
+    // Errors the IDL does not declare keep the message check.
+    let undeclared = test_body(&rendered, "deposit - Overflow");
+    assert!(undeclared.contains("assert.include(String(err), \"Arithmetic overflow\");"));
+}
+
+#[test]
+fn test_generate_renders_u128_overflow_as_full_bn_string() {
+    let mut meta = token_metadata();
+    let case = &mut meta.test_cases[0];
+    case.arguments = vec![argument("amount", ArgumentType::U128, vec![])];
+    case.positive_cases[0].argument_values = vec![valid("amount", "1000")];
+    case.negative_cases = vec![TestCase {
+        test_type: TestCaseType::NegativeOverflow,
+        description: "deposit - amount overflow".to_string(),
+        argument_values: vec![TestArgumentValue {
+            argument_name: "amount".to_string(),
+            value_type: TestValueType::Invalid {
+                description: "u128::MAX".to_string(),
+                reason: "Potential arithmetic overflow".to_string(),
+            },
+        }],
+        expected_outcome: ExpectedOutcome::Failure {
+            error_code: Some("Overflow".to_string()),
+            error_message: "Arithmetic overflow".to_string(),
+        },
+    }];
+
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &token_idl(), out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    let overflow_test = test_body(&rendered, "deposit - amount overflow");
+    assert!(overflow_test.contains("new anchor.BN(\"340282366920938463463374607431768211455\")"));
+}
+
+fn lifecycle_dependency(account_name: &str, closed_by: &[&str], reallocated_by: &[&str]) -> AccountDependency {
+    AccountDependency {
+        account_name: account_name.to_string(),
+        depends_on: vec![],
+        is_pda: false,
+        is_signer: false,
+        is_mut: true,
+        must_be_initialized: true,
+        initialization_order: 0,
+        closed_by: closed_by.iter().map(|name| name.to_string()).collect(),
+        reallocated_by: reallocated_by.iter().map(|name| name.to_string()).collect(),
+    }
+}
+
+#[test]
+fn test_generate_asserts_closed_and_reallocated_accounts() {
+    let mut idl = token_idl();
+    idl.accounts = vec![IdlAccount { name: "AuthorityTokenAccount".to_string(), fields: vec![] }];
+    let mut meta = token_metadata();
+    meta.account_dependencies = vec![
+        lifecycle_dependency("authority_token_account", &["deposit"], &[]),
+        lifecycle_dependency("mint", &[], &["deposit"]),
+    ];
+
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &idl, out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    let body = test_body(&rendered, "deposit succeeds");
+    assert!(
+        body.contains("assert.isNull(await program.account.authorityTokenAccount.fetchNullable(ata1)"),
+        "Unexpected body: {}",
+        body
+    );
+    assert!(body.contains("const sizeBefore1 = (await connection.getAccountInfo(mint))?.data.length ?? 0;"));
+    assert!(body.contains("assert.isAbove(sizeAfter1, sizeBefore1"));
+
+    let options = GenerateOptions { split: true, ..Default::default() };
+    generate_with_options(&meta, &idl, out_dir.path(), &options).unwrap();
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.deposit.test.ts")).unwrap();
+    assert!(rendered.contains("import { connection, program } from \"./token.helpers\";"), "{}", rendered);
+}
+
+#[test]
+fn test_generate_checks_closed_accounts_without_an_account_type_by_info() {
+    let mut meta = token_metadata();
+    meta.account_dependencies = vec![lifecycle_dependency("mint", &["deposit"], &[])];
+
+    let out_dir = tempfile::tempdir().unwrap();
+    generate_with_tera(&meta, &token_idl(), out_dir.path()).unwrap();
+
+    let rendered = std::fs::read_to_string(out_dir.path().join("token.ts")).unwrap();
+    assert!(rendered.contains("assert.isNull(await connection.getAccountInfo(mint)"));
+    assert!(!rendered.contains("sizeBefore"));
+}
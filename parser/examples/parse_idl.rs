@@ -1,5 +1,4 @@
-
-/// Run with: cargo run --example parse_idl -p solify-parser
+//! Run with: cargo run --example parse_idl -p solify-parser
 
 use solify_parser::{parse_idl, get_pda_accounts, get_signer_accounts, get_writable_accounts};
 
@@ -4,44 +4,182 @@ use solify_common::{
     IdlPda, IdlSeed, IdlError, IdlConstant, IdlEvent, ParsedIdl
 };
 
+use flate2::read::ZlibDecoder;
 use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
 use std::fs;
+use std::io::Read;
 use std::path::Path;
 
+#[cfg(test)]
+mod tests;
 
+/// Maximum nesting of `Vec`/`Option`/`Array`/`Defined` wrappers accepted in an IDL type.
+pub const MAX_TYPE_DEPTH: usize = 32;
+
+/// Parses an IDL for test generation, rejecting IDLs without instructions.
 pub fn parse_idl<P: AsRef<Path>>(idl_path: P) -> Result<IdlData> {
     let path = idl_path.as_ref();
-    let idl_content = fs::read_to_string(path)
-        .with_context(|| format!("Failed to read IDL file at {:?}", path))?;
-    let parsed_idl: ParsedIdl = serde_json::from_str(&idl_content)
+    let _span = tracing::info_span!("parse", path = %path.display()).entered();
+    parse_idl_str(&read_idl_file(path)?)
+}
+
+/// Like [`parse_idl`], for IDL JSON that is already in memory.
+pub fn parse_idl_str(idl_content: &str) -> Result<IdlData> {
+    let idl_data = convert_to_idl_data(parse_idl_content(idl_content)?, false)?;
+    tracing::debug!(instructions = idl_data.instructions.len(), "parsed IDL");
+    ensure_has_instructions(&idl_data)?;
+    Ok(idl_data)
+}
+
+/// Parses an IDL without requiring any instructions, for read-only tooling that
+/// reports an empty IDL as a diagnostic instead of failing outright. Instructions
+/// with a duplicate name are dropped with a warning, keeping the first.
+pub fn parse_idl_lenient<P: AsRef<Path>>(idl_path: P) -> Result<IdlData> {
+    let path = idl_path.as_ref();
+    let _span = tracing::info_span!("parse", path = %path.display()).entered();
+    let idl_data = convert_to_idl_data(read_parsed_idl(path)?, true)?;
+    tracing::debug!(instructions = idl_data.instructions.len(), "parsed IDL");
+    Ok(idl_data)
+}
+
+/// The check `parse_idl` applies on top of `parse_idl_lenient`.
+pub fn ensure_has_instructions(idl_data: &IdlData) -> Result<()> {
+    if idl_data.instructions.is_empty() {
+        anyhow::bail!("IDL must have at least one instruction");
+    }
+    Ok(())
+}
+
+fn read_idl_file(path: &Path) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!("Failed to read IDL file at {:?}", path))
+}
+
+fn read_parsed_idl(path: &Path) -> Result<ParsedIdl> {
+    parse_idl_content(&read_idl_file(path)?)
+}
+
+fn parse_idl_content(idl_content: &str) -> Result<ParsedIdl> {
+    serde_json::from_str(idl_content)
         .with_context(|| {
-            if let Err(e) = serde_json::from_str::<serde_json::Value>(&idl_content) {
+            if let Err(e) = serde_json::from_str::<serde_json::Value>(idl_content) {
                 format!("Invalid JSON: {}", e)
             } else {
                 "Failed to deserialize IDL JSON - structure mismatch".to_string()
             }
-        })?;
-    
-    convert_to_idl_data(parsed_idl)
+        })
 }
 
-fn convert_to_idl_data(parsed: ParsedIdl) -> Result<IdlData> {
-    if parsed.instructions.is_empty() {
-        anyhow::bail!("IDL must have at least one instruction");
-    }
-    
+/// Seed Anchor uses with `Pubkey::create_with_seed` for a program's IDL account.
+const ANCHOR_IDL_SEED: &str = "anchor:idl";
+
+/// Discriminator and authority pubkey that precede the payload length in an IDL account.
+const IDL_ACCOUNT_HEADER_LEN: usize = 8 + 32;
+
+/// Address of the IDL account `anchor idl init` creates for `program_id`.
+pub fn anchor_idl_address(program_id: &Pubkey) -> Result<Pubkey> {
+    let (base, _) = Pubkey::find_program_address(&[], program_id);
+    Pubkey::create_with_seed(&base, ANCHOR_IDL_SEED, program_id)
+        .context("Failed to derive the Anchor IDL address")
+}
+
+/// Parses the raw data of an Anchor IDL account, stored at [`anchor_idl_address`],
+/// producing the same `IdlData` as `parse_idl` on the equivalent file.
+pub fn decode_idl_account(data: &[u8]) -> Result<IdlData> {
+    parse_idl_str(&decode_idl_account_json(data)?)
+}
+
+/// Inflates the zlib-compressed JSON stored after the IDL account header.
+pub fn decode_idl_account_json(data: &[u8]) -> Result<String> {
+    let len_bytes = data
+        .get(IDL_ACCOUNT_HEADER_LEN..IDL_ACCOUNT_HEADER_LEN + 4)
+        .context("IDL account is too short to hold a payload length")?;
+    let payload_len = u32::from_le_bytes(len_bytes.try_into()?) as usize;
+    let payload_start = IDL_ACCOUNT_HEADER_LEN + 4;
+    let payload = data
+        .get(payload_start..payload_start + payload_len)
+        .with_context(|| format!("IDL account holds fewer than the {} payload bytes it declares", payload_len))?;
+
+    let mut idl_json = String::new();
+    ZlibDecoder::new(payload)
+        .read_to_string(&mut idl_json)
+        .context("Failed to inflate the IDL account payload")?;
+    Ok(idl_json)
+}
+
+/// Version reported for an IDL whose metadata has none.
+pub const UNKNOWN_IDL_VERSION: &str = "0.0.0";
+
+fn convert_to_idl_data(parsed: ParsedIdl, lenient: bool) -> Result<IdlData> {
+    let instructions = parsed.instructions.into_iter().map(convert_instruction).collect::<Result<Vec<_>>>()?;
+    let name = program_name(first_non_empty(&parsed.metadata.name, &parsed.name), &parsed.address);
+    let version = match first_non_empty(&parsed.metadata.version, &parsed.version) {
+        "" => UNKNOWN_IDL_VERSION.to_string(),
+        version => version.to_string(),
+    };
     Ok(IdlData {
-        name: parsed.metadata.name,
-        version: parsed.metadata.version,
-        instructions: parsed.instructions.into_iter().map(convert_instruction).collect(),
+        name,
+        version,
+        instructions: dedup_instructions(instructions, lenient)?,
         accounts: parsed.accounts.into_iter().map(convert_account).collect(),
-        types: parsed.types.into_iter().map(convert_type).collect(),
+        types: parsed.types.into_iter().map(convert_type).collect::<Result<Vec<_>>>()?,
         errors: parsed.errors.into_iter().map(convert_error).collect(),
         constants: parsed.constants.into_iter().map(convert_constant).collect(),
-        events: parsed.events.into_iter().map(convert_event).collect(),
+        events: parsed.events.into_iter().map(convert_event).collect::<Result<Vec<_>>>()?,
     })
 }
 
+/// `current`, or the pre-0.30 top-level field `legacy` when it is blank.
+fn first_non_empty<'a>(current: &'a str, legacy: &'a str) -> &'a str {
+    match current.trim() {
+        "" => legacy.trim(),
+        current => current,
+    }
+}
+
+/// The IDL's `metadata.name`, or a name derived from its address when the name
+/// is missing, e.g. `program7tvj6jxj`. Empty when the IDL has neither, which the
+/// generator rejects unless a name is supplied.
+fn program_name(metadata_name: &str, address: &str) -> String {
+    let name = metadata_name.trim();
+    if !name.is_empty() {
+        return name.to_string();
+    }
+    let address = address.trim();
+    if address.is_empty() {
+        return String::new();
+    }
+    let derived = format!("program{}", address.chars().take(8).collect::<String>()).to_lowercase();
+    tracing::warn!(name = %derived, "IDL has no metadata.name, naming it after its address");
+    derived
+}
+
+/// Rejects instructions that reuse an earlier instruction's name, since lookups by
+/// name would silently pick the first. In lenient mode the later ones are dropped
+/// with a warning instead.
+fn dedup_instructions(instructions: Vec<IdlInstruction>, lenient: bool) -> Result<Vec<IdlInstruction>> {
+    let mut seen = HashSet::new();
+    let mut unique = Vec::with_capacity(instructions.len());
+    for (index, instruction) in instructions.into_iter().enumerate() {
+        if seen.insert(instruction.name.clone()) {
+            unique.push(instruction);
+        } else if lenient {
+            tracing::warn!(
+                instruction = %instruction.name,
+                index,
+                "dropping instruction with a duplicate name, keeping the first"
+            );
+        } else {
+            anyhow::bail!(
+                "IDL defines instruction '{}' more than once (again at index {}); instruction names must be unique",
+                instruction.name,
+                index
+            );
+        }
+    }
+    Ok(unique)
+}
+
 fn convert_error(error: solify_common::ErrorDef) -> IdlError {
     IdlError {
         code: error.code,
@@ -58,28 +196,35 @@ fn convert_constant(constant: solify_common::ConstantDef) -> IdlConstant {
     }
 }
 
-fn convert_event(event: solify_common::EventDef) -> IdlEvent {
-    IdlEvent {
+fn convert_event(event: solify_common::EventDef) -> Result<IdlEvent> {
+    Ok(IdlEvent {
         name: event.name,
         discriminator: event.discriminator,
-        fields: event.fields.into_iter().map(convert_field_def).collect(),
-    }
+        fields: event.fields.into_iter().map(convert_field_def).collect::<Result<Vec<_>>>()?,
+    })
 }
 
-fn convert_field_def(field: solify_common::FieldDef) -> IdlField {
-    IdlField {
+fn convert_field_def(field: solify_common::FieldDef) -> Result<IdlField> {
+    let field_type = type_to_string(&field.field_type)
+        .with_context(|| format!("Invalid type for field '{}'", field.name))?;
+    Ok(IdlField {
         name: field.name,
-        field_type: type_to_string(&field.field_type),
-    }
+        field_type,
+    })
 }
 
-fn convert_instruction(instr: solify_common::Instruction) -> IdlInstruction {
-    IdlInstruction {
+fn convert_instruction(instr: solify_common::Instruction) -> Result<IdlInstruction> {
+    let args = instr.args.into_iter().map(convert_argument).collect::<Result<Vec<_>>>()
+        .with_context(|| format!("Invalid arguments for instruction '{}'", instr.name))?;
+    let returns = instr.returns.as_ref().map(type_to_string).transpose()
+        .with_context(|| format!("Invalid return type for instruction '{}'", instr.name))?;
+    Ok(IdlInstruction {
         name: instr.name,
         accounts: instr.accounts.into_iter().map(convert_account_info).collect(),
-        args: instr.args.into_iter().map(convert_argument).collect(),
+        args,
         docs: instr.docs,
-    }
+        returns,
+    })
 }
 
 fn convert_account_info(acc: solify_common::AccountInfo) -> IdlAccountItem {
@@ -90,6 +235,7 @@ fn convert_account_info(acc: solify_common::AccountInfo) -> IdlAccountItem {
         is_optional: acc.optional,
         docs: acc.docs,
         pda: acc.pda.map(convert_pda_config),
+        address: acc.address,
     }
 }
 
@@ -177,11 +323,13 @@ fn bytes_to_hex(bytes: &[u8]) -> String {
     }
 }
 
-fn convert_argument(arg: solify_common::ArgumentDef) -> IdlField {
-    IdlField {
+fn convert_argument(arg: solify_common::ArgumentDef) -> Result<IdlField> {
+    let field_type = type_to_string(&arg.arg_type)
+        .with_context(|| format!("Invalid type for argument '{}'", arg.name))?;
+    Ok(IdlField {
         name: arg.name,
-        field_type: type_to_string(&arg.arg_type),
-    }
+        field_type,
+    })
 }
 
 fn convert_account(acc: solify_common::AccountDef) -> IdlAccount {
@@ -191,37 +339,52 @@ fn convert_account(acc: solify_common::AccountDef) -> IdlAccount {
     }
 }
 
-fn convert_type(type_def: solify_common::TypeDef) -> IdlTypeDef {
+fn convert_type(type_def: solify_common::TypeDef) -> Result<IdlTypeDef> {
     match type_def.type_kind {
         solify_common::TypeKind::Struct { fields } => {
-            IdlTypeDef {
+            let field_types = fields
+                .iter()
+                .map(|f| type_to_string(&f.field_type))
+                .collect::<Result<Vec<_>>>()
+                .with_context(|| format!("Invalid field type in type '{}'", type_def.name))?;
+            Ok(IdlTypeDef {
                 name: type_def.name,
                 kind: "struct".to_string(),
                 fields: fields.into_iter().map(|f| f.name).collect(),
-            }
+                field_types,
+            })
         }
         solify_common::TypeKind::Enum { variants } => {
-            IdlTypeDef {
+            Ok(IdlTypeDef {
                 name: type_def.name,
                 kind: "enum".to_string(),
                 fields: variants.into_iter().map(|v| v.name).collect(),
-            }
+                field_types: vec![],
+            })
         }
     }
 }
 
-fn type_to_string(idl_type: &solify_common::IdlType) -> String {
-    match idl_type {
+fn type_to_string(idl_type: &solify_common::IdlType) -> Result<String> {
+    type_to_string_with_depth(idl_type, 0)
+}
+
+fn type_to_string_with_depth(idl_type: &solify_common::IdlType, depth: usize) -> Result<String> {
+    if depth > MAX_TYPE_DEPTH {
+        anyhow::bail!("Type nesting exceeds maximum depth of {}", MAX_TYPE_DEPTH);
+    }
+
+    let type_str = match idl_type {
         solify_common::IdlType::Simple(s) => s.clone(),
         solify_common::IdlType::Vec { vec } => {
-            format!("Vec<{}>", type_to_string(vec))
+            format!("Vec<{}>", type_to_string_with_depth(vec, depth + 1)?)
         }
         solify_common::IdlType::Option { option } => {
-            format!("Option<{}>", type_to_string(option))
+            format!("Option<{}>", type_to_string_with_depth(option, depth + 1)?)
         }
         solify_common::IdlType::Array { array } => {
             let (inner, size) = array;
-            format!("[{}; {}]", type_to_string(inner), size)
+            format!("[{}; {}]", type_to_string_with_depth(inner, depth + 1)?, size)
         }
         solify_common::IdlType::Defined { defined } => {
             match defined {
@@ -230,13 +393,18 @@ fn type_to_string(idl_type: &solify_common::IdlType) -> String {
                     if generics.is_empty() {
                         name.clone()
                     } else {
-                        let generic_strs: Vec<String> = generics.iter().map(type_to_string).collect();
+                        let generic_strs = generics
+                            .iter()
+                            .map(|g| type_to_string_with_depth(g, depth + 1))
+                            .collect::<Result<Vec<String>>>()?;
                         format!("{}<{}>", name, generic_strs.join(", "))
                     }
                 }
             }
         }
-    }
+    };
+
+    Ok(type_str)
 }
 
 
@@ -258,6 +426,15 @@ pub fn get_pda_accounts(instruction: &IdlInstruction) -> Vec<String> {
         .collect()
 }
 
+/// Each PDA account of `instruction` with its seeds, in declaration order.
+pub fn describe_pdas(instruction: &IdlInstruction) -> Vec<(String, Vec<IdlSeed>)> {
+    instruction
+        .accounts
+        .iter()
+        .filter_map(|acc| acc.pda.as_ref().map(|pda| (acc.name.clone(), pda.seeds.clone())))
+        .collect()
+}
+
 pub fn get_signer_accounts(instruction: &IdlInstruction) -> Vec<String> {
     instruction
         .accounts
@@ -277,17 +454,6 @@ pub fn get_writable_accounts(instruction: &IdlInstruction) -> Vec<String> {
 }
 
 pub fn get_program_id<P: AsRef<Path>>(idl_path: P) -> Result<String> {
-    let path = idl_path.as_ref();
-    let idl_content = fs::read_to_string(path)
-        .with_context(|| format!("Failed to read IDL file at {:?}", path))?;
-    let parsed_idl: ParsedIdl = serde_json::from_str(&idl_content)
-        .with_context(|| {
-            if let Err(e) = serde_json::from_str::<serde_json::Value>(&idl_content) {
-                format!("Invalid JSON: {}", e)
-            } else {
-                "Failed to deserialize IDL JSON - structure mismatch".to_string()
-            }
-        })?;
-    let program_id = parsed_idl.address;
-    Ok(program_id)
-}
\ No newline at end of file
+    let parsed_idl = read_parsed_idl(idl_path.as_ref())?;
+    Ok(parsed_idl.address)
+}
@@ -0,0 +1,264 @@
+use solify_common::IdlType;
+
+use std::io::Write;
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use crate::{
+    decode_idl_account,
+    describe_pdas,
+    find_instruction,
+    parse_idl,
+    parse_idl_lenient,
+    parse_idl_str,
+    type_to_string,
+    MAX_TYPE_DEPTH,
+    UNKNOWN_IDL_VERSION,
+};
+
+fn nested_vec(depth: usize) -> IdlType {
+    let mut ty = IdlType::Simple("u8".to_string());
+    for _ in 0..depth {
+        ty = IdlType::Vec { vec: Box::new(ty) };
+    }
+    ty
+}
+
+#[test]
+fn test_type_to_string_nested() {
+    let ty = IdlType::Option {
+        option: Box::new(IdlType::Vec {
+            vec: Box::new(IdlType::Simple("pubkey".to_string())),
+        }),
+    };
+    assert_eq!(type_to_string(&ty).unwrap(), "Option<Vec<pubkey>>");
+}
+
+#[test]
+fn test_type_to_string_at_max_depth() {
+    let result = type_to_string(&nested_vec(MAX_TYPE_DEPTH));
+    assert!(result.is_ok(), "Type at max depth should parse: {:?}", result);
+}
+
+#[test]
+fn test_type_to_string_rejects_pathological_nesting() {
+    let err = type_to_string(&nested_vec(10_000)).unwrap_err();
+    assert!(err.to_string().contains("maximum depth"), "Unexpected error: {}", err);
+}
+
+fn write_empty_idl() -> tempfile::NamedTempFile {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    let idl = r#"{
+        "address": "7tvJ6jxJF81pozUSa2o8yPo6zsQCxG4GyF2b6JgaHqaa",
+        "metadata": { "name": "empty", "version": "0.1.0", "spec": "0.1.0" },
+        "instructions": []
+    }"#;
+    std::io::Write::write_all(&mut file, idl.as_bytes()).unwrap();
+    file
+}
+
+#[test]
+fn test_parse_idl_lenient_accepts_empty_instructions() {
+    let file = write_empty_idl();
+    let idl_data = parse_idl_lenient(file.path()).unwrap();
+    assert_eq!(idl_data.name, "empty");
+    assert!(idl_data.instructions.is_empty());
+}
+
+#[test]
+fn test_parse_idl_rejects_empty_instructions() {
+    let file = write_empty_idl();
+    let err = parse_idl(file.path()).unwrap_err();
+    assert!(err.to_string().contains("at least one instruction"), "Unexpected error: {}", err);
+}
+
+fn write_nameless_idl(address: &str) -> tempfile::NamedTempFile {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    let idl = format!(r#"{{
+        "address": "{}",
+        "metadata": {{ "spec": "0.1.0" }},
+        "instructions": [
+            {{ "name": "deposit", "discriminator": [1, 0, 0, 0, 0, 0, 0, 0], "accounts": [], "args": [] }}
+        ]
+    }}"#, address);
+    file.write_all(idl.as_bytes()).unwrap();
+    file
+}
+
+#[test]
+fn test_parse_idl_names_a_nameless_idl_after_its_address() {
+    let file = write_nameless_idl("7tvJ6jxJF81pozUSa2o8yPo6zsQCxG4GyF2b6JgaHqaa");
+    let idl_data = parse_idl(file.path()).unwrap();
+    assert_eq!(idl_data.name, "program7tvj6jxj");
+    assert_eq!(idl_data.version, UNKNOWN_IDL_VERSION);
+
+    // Without an address there is nothing to derive a name from; the generator
+    // rejects the empty name unless one is supplied.
+    let file = write_nameless_idl("");
+    assert_eq!(parse_idl(file.path()).unwrap().name, "");
+}
+
+#[test]
+fn test_parse_idl_reads_the_legacy_top_level_name() {
+    let idl_data = parse_idl(concat!(env!("CARGO_MANIFEST_DIR"), "/idls/mini_escrow.json")).unwrap();
+    assert_eq!(idl_data.name, "mini_escrow");
+    assert_eq!(idl_data.version, "0.1.0");
+}
+
+#[test]
+fn test_parse_idl_str_parses_in_memory_json() {
+    let idl_data = parse_idl_str(r#"{
+        "address": "7tvJ6jxJF81pozUSa2o8yPo6zsQCxG4GyF2b6JgaHqaa",
+        "metadata": { "name": "counter", "version": "0.2.0", "spec": "0.1.0" },
+        "instructions": [
+            {
+                "name": "increment",
+                "discriminator": [11, 18, 104, 9, 104, 174, 59, 33],
+                "accounts": [{ "name": "counter", "writable": true }],
+                "args": [{ "name": "by", "type": "u64" }]
+            }
+        ]
+    }"#).unwrap();
+    assert_eq!((idl_data.name.as_str(), idl_data.version.as_str()), ("counter", "0.2.0"));
+    assert_eq!(idl_data.instructions[0].name, "increment");
+    assert!(idl_data.instructions[0].accounts[0].is_mut);
+    assert_eq!(idl_data.instructions[0].args[0].field_type, "u64");
+
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/idls/mini_escrow.json");
+    assert_eq!(parse_idl_str(&std::fs::read_to_string(path).unwrap()).unwrap(), parse_idl(path).unwrap());
+    assert!(parse_idl_str("{").unwrap_err().to_string().contains("Invalid JSON"));
+}
+
+#[test]
+fn test_parse_idl_records_tuple_struct_field_types() {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    let idl = r#"{
+        "address": "7tvJ6jxJF81pozUSa2o8yPo6zsQCxG4GyF2b6JgaHqaa",
+        "metadata": { "name": "vault", "version": "0.1.0", "spec": "0.1.0" },
+        "instructions": [{
+            "name": "deposit",
+            "discriminator": [0, 0, 0, 0, 0, 0, 0, 0],
+            "accounts": [],
+            "args": [{ "name": "amount", "type": { "defined": { "name": "Amount" } } }]
+        }],
+        "types": [{ "name": "Amount", "type": { "kind": "struct", "fields": ["u64"] } }]
+    }"#;
+    std::io::Write::write_all(&mut file, idl.as_bytes()).unwrap();
+
+    let idl_data = parse_idl(file.path()).unwrap();
+    assert_eq!(idl_data.instructions[0].args[0].field_type, "Amount");
+    assert_eq!(idl_data.types[0].fields, vec!["0".to_string()]);
+    assert_eq!(idl_data.types[0].field_types, vec!["u64".to_string()]);
+}
+
+#[test]
+fn test_independently_parsed_idls_compare_equal() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/idls/voting_dapp.json");
+    let first = parse_idl(path).unwrap();
+    let mut second = parse_idl(path).unwrap();
+    let hash = |idl: &solify_common::IdlData| {
+        let mut hasher = DefaultHasher::new();
+        idl.hash(&mut hasher);
+        hasher.finish()
+    };
+    assert_eq!(first, second);
+    assert_eq!(hash(&first), hash(&second));
+
+    second.instructions[0].args.pop();
+    assert_ne!(first, second);
+}
+
+fn write_duplicate_instruction_idl() -> tempfile::NamedTempFile {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    let idl = r#"{
+        "address": "7tvJ6jxJF81pozUSa2o8yPo6zsQCxG4GyF2b6JgaHqaa",
+        "metadata": { "name": "merged", "version": "0.1.0", "spec": "0.1.0" },
+        "instructions": [
+            { "name": "deposit", "discriminator": [1, 0, 0, 0, 0, 0, 0, 0], "accounts": [], "args": [] },
+            { "name": "withdraw", "discriminator": [2, 0, 0, 0, 0, 0, 0, 0], "accounts": [], "args": [] },
+            { "name": "deposit", "discriminator": [3, 0, 0, 0, 0, 0, 0, 0], "accounts": [],
+              "args": [{ "name": "amount", "type": "u64" }] }
+        ]
+    }"#;
+    std::io::Write::write_all(&mut file, idl.as_bytes()).unwrap();
+    file
+}
+
+#[test]
+fn test_duplicate_instruction_names() {
+    let file = write_duplicate_instruction_idl();
+    let err = parse_idl(file.path()).unwrap_err();
+    assert!(
+        err.to_string().contains("instruction 'deposit' more than once (again at index 2)"),
+        "Unexpected error: {}",
+        err
+    );
+
+    let idl_data = parse_idl_lenient(file.path()).unwrap();
+    let names: Vec<&str> = idl_data.instructions.iter().map(|ix| ix.name.as_str()).collect();
+    assert_eq!(names, ["deposit", "withdraw"]);
+    assert!(find_instruction(&idl_data, "deposit").unwrap().args.is_empty());
+}
+
+/// Lays out an IDL account the way `anchor idl init` writes it.
+fn idl_account_data(idl_json: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(idl_json).unwrap();
+    let payload = encoder.finish().unwrap();
+
+    let mut data = vec![0u8; 8 + 32];
+    data.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    data.extend_from_slice(&payload);
+    data
+}
+
+#[test]
+fn test_decode_idl_account_matches_idl_file() {
+    let idl_path = concat!(env!("CARGO_MANIFEST_DIR"), "/idls/counter_program.json");
+    let from_file = parse_idl(idl_path).unwrap();
+
+    let data = idl_account_data(&std::fs::read(idl_path).unwrap());
+    let from_account = decode_idl_account(&data).unwrap();
+
+    assert_eq!(from_account, from_file);
+}
+
+#[test]
+fn test_decode_idl_account_rejects_truncated_payload() {
+    let mut data = idl_account_data(br#"{"address": ""}"#);
+    data.truncate(data.len() - 1);
+    let err = decode_idl_account(&data).unwrap_err();
+    assert!(err.to_string().contains("payload bytes"), "Unexpected error: {}", err);
+}
+
+#[test]
+fn test_decode_idl_account_rejects_idl_without_instructions() {
+    let data = idl_account_data(br#"{"address": "", "metadata": {"name": "empty"}, "instructions": []}"#);
+    let err = decode_idl_account(&data).unwrap_err();
+    assert!(err.to_string().contains("at least one instruction"), "Unexpected error: {}", err);
+}
+
+#[test]
+fn test_describe_pdas_returns_seed_breakdown() {
+    let idl = parse_idl(concat!(env!("CARGO_MANIFEST_DIR"), "/idls/journal.json")).unwrap();
+    let instruction = find_instruction(&idl, "create_journal_entry").unwrap();
+
+    let pdas = describe_pdas(instruction);
+    assert_eq!(pdas.len(), 1);
+    let (name, seeds) = &pdas[0];
+    assert_eq!(name, "journal_entry");
+    let seeds: Vec<(&str, &str)> = seeds.iter().map(|s| (s.kind.as_str(), s.path.as_str())).collect();
+    assert_eq!(seeds, [("arg", "title"), ("account", "owner")]);
+}
+
+#[test]
+fn test_describe_pdas_is_empty_for_legacy_idl_without_seeds() {
+    let idl = parse_idl_lenient(concat!(env!("CARGO_MANIFEST_DIR"), "/idls/mini_escrow.json")).unwrap();
+    for instruction in &idl.instructions {
+        assert!(describe_pdas(instruction).is_empty(), "{}", instruction.name);
+    }
+}
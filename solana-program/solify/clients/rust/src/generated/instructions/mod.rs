@@ -7,9 +7,11 @@
 
   pub(crate) mod r#generate_metadata;
   pub(crate) mod r#store_idl_data;
+  pub(crate) mod r#update_execution_order;
   pub(crate) mod r#update_idl_data;
 
   pub use self::r#generate_metadata::*;
   pub use self::r#store_idl_data::*;
+  pub use self::r#update_execution_order::*;
   pub use self::r#update_idl_data::*;
 
@@ -70,7 +70,9 @@ impl AccountOrder {
         }
 
         for edge in &graph.edges {
-            *in_degree.get_mut(&edge.to).unwrap() += 1;
+            *in_degree
+                .get_mut(&edge.to)
+                .ok_or_else(|| edge.dangling())? += 1;
         }
 
         let mut queue: std::collections::VecDeque<String> = in_degree
@@ -86,7 +88,9 @@ impl AccountOrder {
 
             for edge in &graph.edges {
                 if edge.from == node {
-                    let degree = in_degree.get_mut(&edge.to).unwrap();
+                    let degree = in_degree
+                        .get_mut(&edge.to)
+                        .ok_or_else(|| edge.dangling())?;
                     *degree -= 1;
                     if *degree == 0 {
                         queue.push_back(edge.to.clone());
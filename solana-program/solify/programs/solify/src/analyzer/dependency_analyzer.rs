@@ -17,21 +17,21 @@ pub struct AccountInfo {
     pub constraints: Vec<ConstraintInfo>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct SeedInfo {
     pub seed_type: SeedType,
     pub value: String,
     pub source: SeedSource,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum SeedType {
     Static,
     AccountKey,
     Argument,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum SeedSource {
     Authority,
     UserAccount,
@@ -79,6 +79,16 @@ pub struct DependencyEdge {
     pub account: String,
 }
 
+impl DependencyEdge {
+    /// The error for an edge whose target instruction is not a node of its graph,
+    /// which the graph builder never produces. The account is logged, since
+    /// program errors carry no payload.
+    pub fn dangling(&self) -> SolifyError {
+        msg!("The edge for account {} leads to {}, which is not in the dependency graph", self.account, self.to);
+        SolifyError::InvalidAccountDependency
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum DependencyType {
     Initialization,
@@ -99,12 +109,17 @@ impl AccountRegistry {
 
     pub fn add_or_update_account(&mut self, account: AccountInfo) {
         if let Some(existing) = self.accounts.iter_mut().find(|a| a.name == account.name) {
-            // Update existing account
-            existing.used_in.extend(account.used_in);
+            // Update existing account; an account listed twice in one
+            // instruction must not count as two uses.
+            for instruction in account.used_in {
+                if !existing.used_in.contains(&instruction) {
+                    existing.used_in.push(instruction);
+                }
+            }
             if account.initialized_by.is_some() {
                 existing.initialized_by = account.initialized_by;
             }
-            if !account.seeds.is_empty() {
+            if !account.seeds.is_empty() && existing.seeds != account.seeds {
                 existing.seeds = account.seeds;
             }
         } else {
@@ -116,6 +131,25 @@ impl AccountRegistry {
         self.accounts.iter().find(|a| a.name == name)
     }
 
+    /// Account a PDA seed depends on: user and vault seeds by name, and any other
+    /// account-key seed whose path resolves to a registered account. Authority
+    /// seeds only count when they name another PDA, since a signing authority is
+    /// never initialized. Constants, arguments and bumps never create a dependency.
+    pub fn seed_dependency<'a>(&self, seed: &'a SeedInfo) -> Option<&'a str> {
+        match &seed.source {
+            SeedSource::UserAccount | SeedSource::Vault => Some(&seed.value),
+            source if matches!(seed.seed_type, SeedType::AccountKey) => {
+                let account = seed.value.split('.').next().unwrap_or(&seed.value);
+                let registered = self.get_account(account)?;
+                match source {
+                    SeedSource::Authority => registered.is_pda.then_some(account),
+                    _ => Some(account),
+                }
+            }
+            _ => None,
+        }
+    }
+
     pub fn find_accounts_initialized_by(&self, instruction: &str) -> Vec<&AccountInfo> {
         self.accounts
             .iter()
@@ -124,6 +158,50 @@ impl AccountRegistry {
     }
 }
 
+/// Words in an instruction name that mark it as creating the accounts it takes.
+/// Mirrors the off-chain analyzer's `DEFAULT_INIT_KEYWORDS`; the list is fixed
+/// here because the program's instructions take no analyzer configuration.
+pub const INIT_KEYWORDS: &[&str] = &["init", "create", "open", "new", "setup", "register"];
+
+/// Whether any word of `instruction_name`, split on `_` and camelCase humps, starts
+/// with an init keyword, either directly or after a `re` prefix.
+pub fn is_initializing_instruction(instruction_name: &str) -> bool {
+    name_words(instruction_name).iter().any(|word| {
+        let unprefixed = word.strip_prefix("re");
+        INIT_KEYWORDS.iter().any(|keyword| {
+            word.starts_with(keyword) || unprefixed.is_some_and(|rest| rest.starts_with(keyword))
+        })
+    })
+}
+
+/// The lowercase words of a snake_case or camelCase name.
+fn name_words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut previous_lowercase = false;
+    for c in name.chars() {
+        if (c == '_' || (c.is_uppercase() && previous_lowercase)) && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        if c != '_' {
+            word.extend(c.to_lowercase());
+        }
+        previous_lowercase = c.is_lowercase() || c.is_ascii_digit();
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Whether the account's docs carry an Anchor `init` or `init_if_needed` constraint.
+fn declares_init(account_item: &IdlAccountItem) -> bool {
+    account_item.docs.iter().any(|doc| {
+        doc.split(|c: char| !c.is_alphanumeric() && c != '_')
+            .any(|token| token == "init" || token == "init_if_needed")
+    })
+}
+
 pub struct DependencyAnalyzerImpl;
 
 impl DependencyAnalyzerImpl {
@@ -165,10 +243,9 @@ impl DependencyAnalyzerImpl {
         if let Some(pda_info) = &account_item.pda {
             is_pda = true;
 
-            if pda_info.program == Some(program.clone()) {
-                program_pda = pda_info.program.clone().unwrap().to_string();
-            } else {
-                program_pda = program.clone().to_string();
+            // The IDL only carries a program for PDAs owned by another program.
+            if let Some(pda_program) = pda_info.program.as_ref().filter(|p| !p.is_empty()) {
+                program_pda = pda_program.clone();
             }
             for idl_seed in &pda_info.seeds {
                 let seed_type = match idl_seed.kind.as_str() {
@@ -232,13 +309,11 @@ impl DependencyAnalyzerImpl {
             });
         }
 
-        let instruction_name_lower = instruction.name.to_lowercase();
-        if instruction_name_lower.contains("init") || 
-           instruction_name_lower.contains("create") ||
-           instruction_name_lower.contains("initialize") {
+        let declares_init = declares_init(account_item);
+        if declares_init || is_initializing_instruction(&instruction.name) {
             initialized_by = Some(instruction.name.clone());
             
-            if account_item.is_mut {
+            if declares_init || account_item.is_mut {
                 constraints.push(ConstraintInfo {
                     constraint_type: ConstraintType::Init,
                     value: None,
@@ -310,8 +385,8 @@ impl DependencyAnalyzerImpl {
             for account_name in &node.initializes {
                 if let Some(account) = registry.get_account(account_name) {
                     for seed in &account.seeds {
-                        if let SeedSource::UserAccount | SeedSource::Vault = seed.source {
-                            if let Some(dep_node_index) = graph.nodes[..i].iter().position(|n| n.initializes.contains(&seed.value)) {
+                        if let Some(seed_account) = registry.seed_dependency(seed) {
+                            if let Some(dep_node_index) = graph.nodes[..i].iter().position(|n| n.initializes.iter().any(|a| a == seed_account)) {
                                 graph.edges.push(DependencyEdge {
                                     from: graph.nodes[dep_node_index].name.clone(),
                                     to: node.name.clone(),
@@ -414,7 +489,9 @@ impl DependencyAnalyzerImpl {
 
         // Calculate in-degree
         for edge in &graph.edges {
-            *in_degree.get_mut(&edge.to).unwrap() += 1;
+            *in_degree
+                .get_mut(&edge.to)
+                .ok_or_else(|| edge.dangling())? += 1;
         }
 
         // Find nodes with in-degree 0
@@ -433,7 +510,9 @@ impl DependencyAnalyzerImpl {
             // Decrease in-degree of neighbors
             for edge in &graph.edges {
                 if edge.from == node {
-                    let to_degree = in_degree.get_mut(&edge.to).unwrap();
+                    let to_degree = in_degree
+                        .get_mut(&edge.to)
+                        .ok_or_else(|| edge.dangling())?;
                     *to_degree -= 1;
                     visited_edges += 1;
 
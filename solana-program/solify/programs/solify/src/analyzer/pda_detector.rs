@@ -42,6 +42,11 @@ impl PdaDetector {
         // Estimate space requirement based on account usage
         let space = self.estimate_account_space(account);
 
+        let program_id = account.program
+            .as_deref()
+            .and_then(|program| program.parse::<Pubkey>().ok())
+            .unwrap_or(program_id);
+
         Ok(PdaInit {
             account_name: account.name.clone(),
             seeds,
@@ -71,22 +76,22 @@ impl PdaDetector {
             let account = registry.get_account(&pda_init.account_name)
                 .ok_or(SolifyError::AccountConstraintParseFailed)?;
             
-            let mut deps = Vec::new();
-            for seed in &account.seeds {
-                if let SeedType::AccountKey = seed.seed_type {
-                    deps.push(seed.value.clone());
-                }
-            }
+            let deps = account.seeds
+                .iter()
+                .filter_map(|seed| registry.seed_dependency(seed))
+                .map(String::from)
+                .collect();
             dependencies.push((i, deps));
         }
 
         // Simple topological sort for PDAs
+        let pda_names: Vec<&str> = pda_inits.iter().map(|pda_init| pda_init.account_name.as_str()).collect();
         let mut sorted_indices = Vec::new();
         let mut visited = std::collections::HashSet::new();
 
         for i in 0..pda_inits.len() {
             if !visited.contains(&i) {
-                self.visit_pda(i, &dependencies, &mut visited, &mut sorted_indices)?;
+                self.visit_pda(i, &pda_names, &dependencies, &mut visited, &mut sorted_indices)?;
             }
         }
 
@@ -103,6 +108,7 @@ impl PdaDetector {
     fn visit_pda(
         &self,
         index: usize,
+        pda_names: &[&str],
         dependencies: &[(usize, Vec<String>)],
         visited: &mut std::collections::HashSet<usize>,
         sorted: &mut Vec<usize>,
@@ -111,11 +117,10 @@ impl PdaDetector {
 
         let (_, deps) = &dependencies[index];
         for dep_name in deps {
-            if let Some(dep_index) = dependencies.iter()
-                .position(|(_, d)| d.contains(dep_name))
-            {
+            // A seed naming another PDA puts that PDA first.
+            if let Some(dep_index) = pda_names.iter().position(|name| name == dep_name) {
                 if !visited.contains(&dep_index) {
-                    self.visit_pda(dep_index, dependencies, visited, sorted)?;
+                    self.visit_pda(dep_index, pda_names, dependencies, visited, sorted)?;
                 }
             }
         }
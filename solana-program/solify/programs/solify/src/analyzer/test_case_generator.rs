@@ -86,7 +86,7 @@ impl TestCaseGenerator {
             "i128" => Ok(ArgumentType::I128),
             "bool" => Ok(ArgumentType::Bool),
             "string" => Ok(ArgumentType::String { max_length: None }),
-            "publicKey" => Ok(ArgumentType::Pubkey),
+            "publicKey" | "pubkey" => Ok(ArgumentType::Pubkey),
             _ => Ok(ArgumentType::VecType { inner_type_name: "u8".to_string(), max_length: None }),
         }
     }
@@ -141,17 +141,21 @@ fn create_basic_positive_case(
         .iter()
         .map(|arg| {
             let value = match &arg.arg_type {
-                | ArgumentType::U8
+                // Small types get values inside their range (u8 max 255, i8 max 127).
+                ArgumentType::U8 => "100".to_string(),
                 | ArgumentType::U16
                 | ArgumentType::U32
                 | ArgumentType::U64
                 | ArgumentType::U128 => "1000".to_string(),
-                | ArgumentType::I8
+                ArgumentType::I8 => "50".to_string(),
                 | ArgumentType::I16
                 | ArgumentType::I32
                 | ArgumentType::I64
                 | ArgumentType::I128 => "500".to_string(),
                 ArgumentType::Bool => "true".to_string(),
+                ArgumentType::String { max_length: Some(max_length) } => {
+                    format!("\"a\".repeat({})", (max_length / 2).max(1))
+                }
                 ArgumentType::String { .. } => "\"test_value\"".to_string(),
                 ArgumentType::Pubkey => self.truncate_string("authority.publicKey", 20),
                 _ => "/* valid value */".to_string(),
@@ -294,14 +298,15 @@ fn create_constraint_violation_case(
     constraint: &ArgumentConstraint
 ) -> Result<Option<TestCase>> {
     let test_case = match constraint {
+        // Skip the case when the violating value doesn't fit in an i64.
         ArgumentConstraint::Min { value } =>
-            Some(TestCase {
+            value.checked_sub(1).map(|below| TestCase {
                 test_type: TestCaseType::NegativeBoundary,
                 description: format!("{} - {} below minimum", instruction_name, argument.name),
                 argument_values: vec![TestArgumentValue {
                     argument_name: argument.name.clone(),
                     value_type: TestValueType::Invalid {
-                        description: (value - 1).to_string(),
+                        description: below.to_string(),
                         reason: self.truncate_string(&format!("Below minimum value of {}", value), 20),
                     },
                 }],
@@ -311,13 +316,13 @@ fn create_constraint_violation_case(
                 },
             }),
         ArgumentConstraint::Max { value } =>
-            Some(TestCase {
+            value.checked_add(1).map(|above| TestCase {
                 test_type: TestCaseType::NegativeBoundary,
                 description: format!("{} - {} above maximum", instruction_name, argument.name),
                 argument_values: vec![TestArgumentValue {
                     argument_name: argument.name.clone(),
                     value_type: TestValueType::Invalid {
-                        description: (value + 1).to_string(),
+                        description: above.to_string(),
                         reason: self.truncate_string(&format!("Above maximum value of {}", value), 20),
                     },
                 }],
@@ -355,14 +360,19 @@ fn generate_numeric_negative_cases(
 ) -> Result<Vec<TestCase>> {
     let mut cases = Vec::new();
 
-    // Overflow case
+    // Overflow case; 128-bit arguments need a sentinel beyond u64
+    let overflow_value = match argument.arg_type {
+        ArgumentType::U128 => "u128::MAX",
+        ArgumentType::I128 => "i128::MAX",
+        _ => "u64::MAX",
+    };
     cases.push(TestCase {
         test_type: TestCaseType::NegativeOverflow,
         description: format!("{} - {} overflow", instruction_name, argument.name),
         argument_values: vec![TestArgumentValue {
             argument_name: argument.name.clone(),
             value_type: TestValueType::Invalid {
-                description: "u64::MAX".to_string(),
+                description: overflow_value.to_string(),
                 reason: self.truncate_string("Potential arithmetic overflow", 20),
             },
         }],
@@ -423,7 +433,10 @@ fn generate_string_negative_cases(
         argument_values: vec![TestArgumentValue {
             argument_name: argument.name.clone(),
             value_type: TestValueType::Invalid {
-                description: self.truncate_string("\"a\".repeat(1000)", 20),
+                description: self.truncate_string(
+                    &format!("\"a\".repeat({})", too_long_string_length(&argument.arg_type)),
+                    20
+                ),
                 reason: self.truncate_string("Exceeds maximum length", 20),
             },
         }],
@@ -498,4 +511,13 @@ fn generate_pubkey_negative_cases(
         },
     })
 }
-}
\ No newline at end of file
+}
+
+/// Length of the "too long" string case: one past the declared `max_length`, or a
+/// generous 1000 characters when the limit is unknown.
+fn too_long_string_length(arg_type: &ArgumentType) -> u64 {
+    match arg_type {
+        ArgumentType::String { max_length: Some(max_length) } => u64::from(*max_length) + 1,
+        _ => 1000,
+    }
+}
@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{ transfer, Transfer };
 use crate::{
     analyzer::DependencyAnalyzer,
     error::SolifyError,
@@ -10,7 +11,7 @@ use crate::{
 #[instruction(execution_order: Vec<String>, program_id: Pubkey, program_name: String, paraphrase: String)]
 pub struct GenerateMetadata<'info> {
     #[account(
-        init,
+        init_if_needed,
         payer = authority,
         space = TestMetadataConfig::DISCRIMINATOR.len() + TestMetadataConfig::INIT_SPACE,
         seeds = [
@@ -40,25 +41,7 @@ impl<'info> GenerateMetadata<'info> {
     ) -> Result<()> {
         let clock = Clock::get()?;
 
-        let idl_storage_data = self.idl_storage.try_borrow_data()?;
-        
-        require!(
-            idl_storage_data.len() >= 8,
-            SolifyError::InvalidAccountData
-        );
-
-        let discriminator = &idl_storage_data[0..8];
-        let expected_discriminator = IdlStorage::DISCRIMINATOR;
-        require!(discriminator == expected_discriminator, SolifyError::InvalidAccountData);
-
-        let mut data_slice = &idl_storage_data[8..];
-        let authority = Pubkey::deserialize(&mut data_slice)?;
-        let stored_program_id = Pubkey::deserialize(&mut data_slice)?;
-
-        require!(authority == self.authority.key(), SolifyError::Unauthorized);
-        require!(stored_program_id == program_id, SolifyError::InvalidProgramId);
-
-        let idl_data = IdlData::deserialize(&mut data_slice)?;
+        let idl_data = read_stored_idl(&self.idl_storage, self.authority.key(), program_id)?;
 
         let analyzer = DependencyAnalyzer::new();
         let test_metadata = analyzer.analyze_dependencies(
@@ -76,6 +59,73 @@ impl<'info> GenerateMetadata<'info> {
             clock.unix_timestamp
         )?;
 
+        grow_test_metadata_config(
+            &self.test_metadata_config,
+            &self.authority,
+            &self.system_program
+        )?;
+
         Ok(())
     }
 }
+
+/// Reads the IDL stored for `program_id`, deserializing only the header fields needed
+/// to check ownership before decoding the IDL itself.
+pub(crate) fn read_stored_idl(
+    idl_storage: &UncheckedAccount,
+    authority: Pubkey,
+    program_id: Pubkey
+) -> Result<IdlData> {
+    let idl_storage_data = idl_storage.try_borrow_data()?;
+
+    require!(
+        idl_storage_data.len() >= 8,
+        SolifyError::InvalidAccountData
+    );
+
+    let discriminator = &idl_storage_data[0..8];
+    let expected_discriminator = IdlStorage::DISCRIMINATOR;
+    require!(discriminator == expected_discriminator, SolifyError::InvalidAccountData);
+
+    let mut data_slice = &idl_storage_data[8..];
+    let stored_authority = Pubkey::deserialize(&mut data_slice)?;
+    let stored_program_id = Pubkey::deserialize(&mut data_slice)?;
+
+    require!(stored_authority == authority, SolifyError::Unauthorized);
+    require!(stored_program_id == program_id, SolifyError::InvalidProgramId);
+
+    Ok(IdlData::deserialize(&mut data_slice)?)
+}
+
+/// Reallocates the config account when the regenerated metadata no longer fits,
+/// topping up rent from the authority first.
+pub(crate) fn grow_test_metadata_config<'info>(
+    test_metadata_config: &Account<'info, TestMetadataConfig>,
+    authority: &Signer<'info>,
+    system_program: &Program<'info, System>
+) -> Result<()> {
+    let required_len = TestMetadataConfig::DISCRIMINATOR.len()
+        + (**test_metadata_config).try_to_vec()?.len();
+    let account_info = test_metadata_config.to_account_info();
+    if required_len <= account_info.data_len() {
+        return Ok(());
+    }
+
+    let required_lamports = Rent::get()?.minimum_balance(required_len);
+    let top_up = required_lamports.saturating_sub(account_info.lamports());
+    if top_up > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: authority.to_account_info(),
+                    to: account_info.clone(),
+                },
+            ),
+            top_up
+        )?;
+    }
+
+    account_info.resize(required_len)?;
+    Ok(())
+}
@@ -1,7 +1,9 @@
 
 pub mod generate_metadata;
 pub mod store_idl;
+pub mod update_execution_order;
 pub mod update_idl;
 pub use generate_metadata::*;
 pub use store_idl::*;
+pub use update_execution_order::*;
 pub use update_idl::*;
\ No newline at end of file
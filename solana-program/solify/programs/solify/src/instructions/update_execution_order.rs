@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use crate::{
+    analyzer::DependencyAnalyzer,
+    instructions::generate_metadata::{ grow_test_metadata_config, read_stored_idl },
+    state::TestMetadataConfig,
+};
+
+#[derive(Accounts)]
+#[instruction(execution_order: Vec<String>, program_id: Pubkey, paraphrase: String)]
+pub struct UpdateExecutionOrder<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"tests_metadata",
+            program_id.as_ref(),
+            authority.key().as_ref(),
+            paraphrase.as_bytes(),
+        ],
+        bump
+    )]
+    pub test_metadata_config: Account<'info, TestMetadataConfig>,
+    /// CHECK: Read-only; the stored IDL is deserialized manually and never rewritten
+    pub idl_storage: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> UpdateExecutionOrder<'info> {
+    pub fn update_execution_order(
+        &mut self,
+        execution_order: Vec<String>,
+        program_id: Pubkey
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+
+        let idl_data = read_stored_idl(&self.idl_storage, self.authority.key(), program_id)?;
+
+        let analyzer = DependencyAnalyzer::new();
+        let test_metadata = analyzer.analyze_dependencies(
+            &idl_data,
+            &execution_order,
+            program_id.to_string()
+        )?;
+
+        self.test_metadata_config.update(test_metadata, clock.unix_timestamp)?;
+
+        grow_test_metadata_config(
+            &self.test_metadata_config,
+            &self.authority,
+            &self.system_program
+        )?;
+
+        Ok(())
+    }
+}
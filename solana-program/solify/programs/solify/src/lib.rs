@@ -41,5 +41,15 @@ pub mod solify {
         ctx.accounts.generate_metadata(execution_order, program_id, program_name, paraphrase)
     }
 
+    pub fn update_execution_order(
+        ctx: Context<UpdateExecutionOrder>,
+        execution_order: Vec<String>,
+        program_id: Pubkey,
+        paraphrase: String,
+    ) -> Result<()> {
+        let _ = paraphrase; // Used in seeds constraint
+        ctx.accounts.update_execution_order(execution_order, program_id)
+    }
+
 }
 
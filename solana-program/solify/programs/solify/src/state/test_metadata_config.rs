@@ -25,4 +25,10 @@ impl TestMetadataConfig {
         self.timestamp = timestamp;
         Ok(())
     }
+
+    pub fn update(&mut self, test_metadata: TestMetadata, timestamp: i64) -> Result<()> {
+        self.test_metadata = test_metadata;
+        self.timestamp = timestamp;
+        Ok(())
+    }
 }
\ No newline at end of file
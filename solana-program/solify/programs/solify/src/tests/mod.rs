@@ -1,5 +1,5 @@
 use std::path::PathBuf;
-use anchor_lang::{AnchorDeserialize, InstructionData, system_program};
+use anchor_lang::{AnchorDeserialize, InstructionData, Space, system_program};
 use litesvm::LiteSVM;
 use solana_sdk::{
     pubkey, 
@@ -18,7 +18,8 @@ use std::io::{Write, BufWriter};
 use std::fs::File;
 
 
-use crate::types::IdlData;
+use crate::analyzer::{ConstraintType, DependencyAnalyzerImpl, DependencyType, PdaDetector, is_initializing_instruction};
+use crate::types::{IdlAccountItem, IdlData, IdlInstruction, IdlPda, IdlSeed, TestMetadata};
 
 pub mod parse_idl;
 pub use parse_idl::*;
@@ -29,6 +30,109 @@ const PROGRAM_ID: Pubkey = pubkey!("7tvJ6jxJF81pozUSa2o8yPo6zsQCxG4GyF2b6JgaHqaa
 
 
 
+/// `solify_client::TEST_METADATA_SPACE`, which the CLI's `estimate` command
+/// compares serialized metadata against.
+const CLIENT_TEST_METADATA_SPACE: usize = 3661;
+
+#[test]
+fn test_client_test_metadata_space_matches_the_account_layout() {
+    assert_eq!(TestMetadata::INIT_SPACE, CLIENT_TEST_METADATA_SPACE);
+}
+
+fn pda_account_item(name: &str, seed_paths: &[&str]) -> IdlAccountItem {
+    let mut seeds = vec![IdlSeed {
+        kind: "const".to_string(),
+        path: String::new(),
+        value: name.to_string(),
+    }];
+    seeds.extend(seed_paths.iter().map(|path| IdlSeed {
+        kind: "account".to_string(),
+        path: path.to_string(),
+        value: String::new(),
+    }));
+    IdlAccountItem {
+        name: name.to_string(),
+        is_mut: true,
+        is_signer: false,
+        is_optional: false,
+        docs: vec![],
+        pda: Some(IdlPda { seeds, program: None }),
+    }
+}
+
+fn instruction_with(name: &str, accounts: Vec<IdlAccountItem>) -> IdlInstruction {
+    IdlInstruction {
+        name: name.to_string(),
+        accounts,
+        args: vec![],
+        docs: vec![],
+    }
+}
+
+#[test]
+fn test_pda_seeded_by_another_pda_initializes_after_it() {
+    // The parent's name marks its seed as an authority, yet it is a PDA.
+    let idl_data = IdlData {
+        name: "pools".to_string(),
+        version: "0.1.0".to_string(),
+        instructions: vec![
+            instruction_with("create_position", vec![pda_account_item("position", &["pool_authority"])]),
+            instruction_with("initialize_pool", vec![pda_account_item("pool_authority", &[])]),
+        ],
+        accounts: vec![],
+        types: vec![],
+        errors: vec![],
+        constants: vec![],
+        events: vec![],
+    };
+
+    let analyzer = DependencyAnalyzerImpl;
+    let registry = analyzer.build_account_registry(&idl_data, &PROGRAM_ID.to_string()).unwrap();
+    let order = ["initialize_pool".to_string(), "create_position".to_string()];
+    let graph = analyzer.build_dependency_graph(&idl_data, &order, &registry).unwrap();
+
+    assert_eq!(graph.edges.len(), 1, "{:?}", graph.edges);
+    assert!(matches!(graph.edges[0].dependency_type, DependencyType::SeedDependency));
+    assert_eq!(graph.edges[0].from, "initialize_pool");
+    assert_eq!(graph.edges[0].to, "create_position");
+
+    let pdas = PdaDetector.detect_pdas(&registry, AnchorPubkey::default()).unwrap();
+    let names: Vec<&str> = pdas.iter().map(|pda| pda.account_name.as_str()).collect();
+    assert_eq!(names, ["pool_authority", "position"]);
+}
+
+#[test]
+fn test_init_keywords_match_camel_case_words() {
+    for name in ["userInit", "initializeVault", "reinitialize", "createPool"] {
+        assert!(is_initializing_instruction(name), "{name}");
+    }
+    for name in ["deregister", "withdrawFunds", "mintTokens"] {
+        assert!(!is_initializing_instruction(name), "{name}");
+    }
+}
+
+#[test]
+fn test_documented_init_constraint_initializes_the_account() {
+    let mut vault = pda_account_item("vault", &[]);
+    vault.is_mut = false;
+    vault.docs = vec!["#[account(init, payer = user, space = 8 + 32)]".to_string()];
+    let idl_data = IdlData {
+        name: "vaults".to_string(),
+        version: "0.1.0".to_string(),
+        instructions: vec![instruction_with("deposit", vec![vault])],
+        accounts: vec![],
+        types: vec![],
+        errors: vec![],
+        constants: vec![],
+        events: vec![],
+    };
+
+    let registry = DependencyAnalyzerImpl.build_account_registry(&idl_data, &PROGRAM_ID.to_string()).unwrap();
+    let account = registry.get_account("vault").unwrap();
+    assert_eq!(account.initialized_by.as_deref(), Some("deposit"));
+    assert!(account.constraints.iter().any(|c| matches!(c.constraint_type, ConstraintType::Init)));
+}
+
 fn system_program_id() -> Pubkey {
     Pubkey::new_from_array(system_program::ID.to_bytes())
 }
@@ -543,3 +647,223 @@ fn test_for_idl5() {
 
     file.flush().unwrap();
 }
+
+
+fn store_journal_idl(svm: &mut LiteSVM, user: &Keypair, test_program_id: Pubkey) -> (Pubkey, AnchorPubkey) {
+    let user_pubkey = user.pubkey();
+    let idl_storage_pda = get_idl_storage_pda(&test_program_id, &user_pubkey);
+    let idl_data = create_test_idl_data("src/tests/idls/journal.json".to_string());
+    let anchor_test_program_id = AnchorPubkey::new_from_array(test_program_id.to_bytes());
+
+    let data = crate::instruction::StoreIdlData {
+        idl_data,
+        program_id: anchor_test_program_id,
+    }.data();
+    let instruction = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(idl_storage_pda, false),
+            AccountMeta::new(user_pubkey, true),
+            AccountMeta::new_readonly(system_program_id(), false),
+        ],
+        data,
+    };
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&user_pubkey),
+        &[user],
+        svm.latest_blockhash(),
+    );
+    let result = svm.send_transaction(transaction);
+    assert!(result.is_ok(), "Failed to store IDL data: {:?}", result);
+
+    (idl_storage_pda, anchor_test_program_id)
+}
+
+fn send_generate_metadata(
+    svm: &mut LiteSVM,
+    user: &Keypair,
+    idl_storage_pda: Pubkey,
+    test_metadata_pda: Pubkey,
+    program_id: AnchorPubkey,
+    execution_order: Vec<String>,
+    paraphrase: &str,
+) {
+    let user_pubkey = user.pubkey();
+    let gen_accounts = vec![
+        AccountMeta::new(test_metadata_pda, false),
+        AccountMeta::new(idl_storage_pda, false),
+        AccountMeta::new(user_pubkey, true),
+        AccountMeta::new_readonly(system_program_id(), false),
+    ];
+
+    let gen_data = crate::instruction::GenerateMetadata {
+        execution_order,
+        program_id,
+        program_name: "journal".to_string(),
+        paraphrase: paraphrase.to_string(),
+    }.data();
+
+    let gen_instruction = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: gen_accounts,
+        data: gen_data,
+    };
+
+    svm.expire_blockhash();
+    let recent_blockhash = svm.latest_blockhash();
+    let gen_tx = Transaction::new_signed_with_payer(
+        &[gen_instruction],
+        Some(&user_pubkey),
+        &[user],
+        recent_blockhash,
+    );
+
+    let result = svm.send_transaction(gen_tx);
+    assert!(result.is_ok(), "Failed to generate metadata: {:?}", result);
+}
+
+#[test]
+fn test_regenerate_metadata_grows_account() {
+    let (mut svm, user) = setup_test_environment();
+    let user_pubkey = user.pubkey();
+
+    let test_program_id = pubkey!("7tvJ6jxJF81pozUSa2o8yPo6zsQCxG4GyF2b6JgaHqaa");
+    let (idl_storage_pda, anchor_test_program_id) = store_journal_idl(&mut svm, &user, test_program_id);
+
+    let paraphrase = "regrow";
+    let test_metadata_pda = get_test_metadata_pda(&test_program_id, &user_pubkey, paraphrase);
+
+    send_generate_metadata(
+        &mut svm,
+        &user,
+        idl_storage_pda,
+        test_metadata_pda,
+        anchor_test_program_id,
+        vec!["create_journal_entry".to_string()],
+        paraphrase,
+    );
+    let small_account = svm.get_account(&test_metadata_pda).unwrap();
+
+    let execution_order = vec![
+        "create_journal_entry".to_string(),
+        "update_journal_entry".to_string(),
+        "delete_journal_entry".to_string(),
+    ];
+    send_generate_metadata(
+        &mut svm,
+        &user,
+        idl_storage_pda,
+        test_metadata_pda,
+        anchor_test_program_id,
+        execution_order.clone(),
+        paraphrase,
+    );
+    let grown_account = svm.get_account(&test_metadata_pda).unwrap();
+
+    assert!(
+        grown_account.data.len() > small_account.data.len(),
+        "Account did not grow: {} -> {}",
+        small_account.data.len(),
+        grown_account.data.len()
+    );
+    assert!(grown_account.lamports >= svm.minimum_balance_for_rent_exemption(grown_account.data.len()));
+
+    let mut data_slice = &grown_account.data[8..];
+    let test_metadata_config = TestMetadataConfig::deserialize(&mut data_slice).unwrap();
+    assert_eq!(test_metadata_config.test_metadata.instruction_order, execution_order);
+    assert_eq!(test_metadata_config.test_metadata.test_cases.len(), execution_order.len());
+}
+
+fn send_update_execution_order(
+    svm: &mut LiteSVM,
+    user: &Keypair,
+    idl_storage_pda: Pubkey,
+    test_metadata_pda: Pubkey,
+    program_id: AnchorPubkey,
+    execution_order: Vec<String>,
+    paraphrase: &str,
+) {
+    let user_pubkey = user.pubkey();
+    let accounts = vec![
+        AccountMeta::new(test_metadata_pda, false),
+        AccountMeta::new_readonly(idl_storage_pda, false),
+        AccountMeta::new(user_pubkey, true),
+        AccountMeta::new_readonly(system_program_id(), false),
+    ];
+
+    let data = crate::instruction::UpdateExecutionOrder {
+        execution_order,
+        program_id,
+        paraphrase: paraphrase.to_string(),
+    }.data();
+
+    let instruction = Instruction {
+        program_id: PROGRAM_ID,
+        accounts,
+        data,
+    };
+
+    svm.expire_blockhash();
+    let recent_blockhash = svm.latest_blockhash();
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&user_pubkey),
+        &[user],
+        recent_blockhash,
+    );
+
+    let result = svm.send_transaction(transaction);
+    assert!(result.is_ok(), "Failed to update execution order: {:?}", result);
+}
+
+#[test]
+fn test_update_execution_order_leaves_idl_untouched() {
+    let (mut svm, user) = setup_test_environment();
+    let user_pubkey = user.pubkey();
+
+    let test_program_id = pubkey!("7tvJ6jxJF81pozUSa2o8yPo6zsQCxG4GyF2b6JgaHqaa");
+    let (idl_storage_pda, anchor_test_program_id) = store_journal_idl(&mut svm, &user, test_program_id);
+
+    let paraphrase = "reorder";
+    let test_metadata_pda = get_test_metadata_pda(&test_program_id, &user_pubkey, paraphrase);
+
+    send_generate_metadata(
+        &mut svm,
+        &user,
+        idl_storage_pda,
+        test_metadata_pda,
+        anchor_test_program_id,
+        vec![
+            "create_journal_entry".to_string(),
+            "update_journal_entry".to_string(),
+            "delete_journal_entry".to_string(),
+        ],
+        paraphrase,
+    );
+    let idl_account_before = svm.get_account(&idl_storage_pda).unwrap();
+
+    let reordered = vec![
+        "create_journal_entry".to_string(),
+        "delete_journal_entry".to_string(),
+        "update_journal_entry".to_string(),
+    ];
+    send_update_execution_order(
+        &mut svm,
+        &user,
+        idl_storage_pda,
+        test_metadata_pda,
+        anchor_test_program_id,
+        reordered.clone(),
+        paraphrase,
+    );
+
+    let idl_account_after = svm.get_account(&idl_storage_pda).unwrap();
+    assert_eq!(idl_account_after.data, idl_account_before.data);
+    assert_eq!(idl_account_after.lamports, idl_account_before.lamports);
+
+    let metadata_account = svm.get_account(&test_metadata_pda).unwrap();
+    let mut data_slice = &metadata_account.data[8..];
+    let test_metadata_config = TestMetadataConfig::deserialize(&mut data_slice).unwrap();
+    assert_eq!(test_metadata_config.test_metadata.instruction_order, reordered);
+}